@@ -1,101 +1,291 @@
+use actix_web::web::Data;
 use actix_web::{
     Error, HttpMessage,
     cookie::{Cookie, SameSite},
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    error::{ErrorInternalServerError, ErrorUnauthorized, InternalError},
-    http::StatusCode,
+    error::{ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized, InternalError},
+    http::{StatusCode, header},
 };
 use app::SecuritySettings;
+use app::api_keys::{API_KEY_PREFIX, Access, ApiKeyPrincipal};
 pub use app::auth::SessionUser;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use chacha20poly1305::{
     XChaCha20Poly1305, XNonce,
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures_util::{
     future::{Ready, ok},
     task::{Context, Poll},
 };
+use infra::{
+    db::DbPool,
+    domains::{api_key, auth},
+};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use std::{future::Future, pin::Pin, rc::Rc};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
 
 /// Internal wrapper to include expiration in the encrypted payload
 #[derive(Serialize, Deserialize, Debug, bitcode::Encode, bitcode::Decode)]
 pub struct AuthSession {
+    /// Token id, so a single session can be revoked
+    pub jti: Uuid,
     pub exp: i64,
+    /// Sign-in time in milliseconds, kept across refreshes and compared against
+    /// `users.sessions_revoked_at`
+    pub iat: i64,
     pub user: SessionUser,
 }
 
 #[derive(Clone)]
-pub struct TokenEngine {
+struct SessionKey {
+    id: u8,
     cipher: XChaCha20Poly1305,
-    duration: i64,
 }
 
-impl TokenEngine {
-    pub fn new(config: &SecuritySettings) -> Self {
-        let key_bytes = config.key.as_bytes();
+impl SessionKey {
+    fn new(key: &str) -> Self {
+        let key_bytes = key.as_bytes();
         if key_bytes.len() != 32 {
             panic!("Security key must be exactly 32 bytes long for XChaCha20Poly1305");
         }
 
-        let key = chacha20poly1305::Key::from_slice(key_bytes);
+        Self {
+            // Derived from the key so a rotation needs no bookkeeping; a clash only costs
+            // an extra decryption attempt
+            id: Sha256::digest(key_bytes)[0],
+            cipher: XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(key_bytes)),
+        }
+    }
+}
+
+/// Seals sessions into `base64(key id || nonce || ciphertext)` tokens.
+///
+/// New tokens use the primary key; tokens from previous keys keep working until they
+/// expire, so a key can be rotated without signing everyone out.
+#[derive(Clone)]
+pub struct TokenEngine {
+    /// The primary key first, then the previous keys
+    keys: Vec<SessionKey>,
+    duration: i64,
+    refresh_threshold: i64,
+    max_age: i64,
+}
+
+impl TokenEngine {
+    pub fn new(config: &SecuritySettings) -> Self {
+        let keys = std::iter::once(&config.key)
+            .chain(&config.previous_keys)
+            .map(|key| SessionKey::new(key))
+            .collect();
 
         Self {
-            cipher: XChaCha20Poly1305::new(key),
+            keys,
             duration: config.session_duration as i64,
+            refresh_threshold: config.refresh_threshold() as i64,
+            max_age: config.max_session_age as i64,
         }
     }
 
     pub fn create_token(&self, user: SessionUser) -> Result<String, Error> {
+        let now = Utc::now();
         let session = AuthSession {
-            exp: Utc::now().timestamp() + self.duration,
+            jti: Uuid::new_v4(),
+            exp: now.timestamp() + self.duration.min(self.max_age),
+            iat: now.timestamp_millis(),
             user,
         };
 
-        let payload_bytes = bitcode::encode(&session);
+        self.seal(&session)
+    }
 
+    /// Re-issues a session whose remaining lifetime fell below the refresh threshold.
+    ///
+    /// The new token keeps the session's id and sign-in time, and never outlives
+    /// `max_session_age`. Returns `None` when the session is fresh enough or cannot be extended.
+    pub fn refresh_token(&self, session: &AuthSession) -> Result<Option<String>, Error> {
+        let now = Utc::now().timestamp();
+        if session.exp - now >= self.refresh_threshold {
+            return Ok(None);
+        }
+
+        let exp = (now + self.duration).min(self.session_ends_at(session));
+        if exp <= session.exp {
+            return Ok(None);
+        }
+
+        let refreshed = AuthSession {
+            jti: session.jti,
+            exp,
+            iat: session.iat,
+            user: session.user.clone(),
+        };
+
+        self.seal(&refreshed).map(Some)
+    }
+
+    /// The latest moment any token of this session can be valid, in seconds.
+    pub fn session_ends_at(&self, session: &AuthSession) -> i64 {
+        session.iat.div_euclid(1000) + self.max_age
+    }
+
+    fn seal(&self, session: &AuthSession) -> Result<String, Error> {
+        let payload_bytes = bitcode::encode(session);
+        let key = &self.keys[0];
 
         let mut nonce = XNonce::default();
         rand::rng().fill_bytes(&mut nonce);
 
-        let ciphertext = self
+        // The key id is authenticated, so it cannot be swapped to steer decryption
+        let ciphertext = key
             .cipher
-            .encrypt(&nonce, payload_bytes.as_ref())
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &payload_bytes,
+                    aad: &[key.id],
+                },
+            )
             .map_err(|_| ErrorInternalServerError("Encryption failed"))?;
 
-        let mut final_buffer = Vec::with_capacity(nonce.len() + ciphertext.len());
+        let mut final_buffer = Vec::with_capacity(1 + nonce.len() + ciphertext.len());
+        final_buffer.push(key.id);
         final_buffer.extend_from_slice(&nonce);
         final_buffer.extend_from_slice(&ciphertext);
         Ok(BASE64.encode(final_buffer))
     }
 
     pub fn verify_token(&self, token_str: &str) -> Result<SessionUser, Error> {
+        self.verify_session(token_str).map(|session| session.user)
+    }
+
+    pub fn verify_session(&self, token_str: &str) -> Result<AuthSession, Error> {
         let encrypted_data = BASE64
             .decode(token_str)
             .map_err(|_| ErrorUnauthorized("Invalid token encoding"))?;
 
-        if encrypted_data.len() < 24 {
+        if encrypted_data.len() < 25 {
             return Err(ErrorUnauthorized("Token too short"));
         }
-        let (nonce_bytes, ciphertext) = encrypted_data.split_at(24);
+        let (key_id, rest) = encrypted_data.split_at(1);
+        let (nonce_bytes, ciphertext) = rest.split_at(24);
         let nonce = XNonce::from_slice(nonce_bytes);
 
         let plaintext = self
-            .cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|_| ErrorUnauthorized("Invalid token signature or data"))?;
-
-        let session: AuthSession = bitcode::decode(&plaintext)
-            .map_err(|_| ErrorUnauthorized("Invalid session data"))?;
+            .keys
+            .iter()
+            .filter(|key| key.id == key_id[0])
+            .find_map(|key| {
+                key.cipher
+                    .decrypt(
+                        nonce,
+                        Payload {
+                            msg: ciphertext,
+                            aad: key_id,
+                        },
+                    )
+                    .ok()
+            })
+            .ok_or(ErrorUnauthorized("Invalid token signature or data"))?;
+
+        let session: AuthSession =
+            bitcode::decode(&plaintext).map_err(|_| ErrorUnauthorized("Invalid session data"))?;
 
         if session.exp < Utc::now().timestamp() {
             return Err(ErrorUnauthorized("Token expired"));
         }
 
-        Ok(session.user)
+        Ok(session)
+    }
+}
+
+/// The token behind the current request, for handlers that act on the session itself.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionToken {
+    pub id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct CachedCheck {
+    user_id: Uuid,
+    exp: i64,
+    checked_at: Instant,
+    revoked: bool,
+}
+
+/// Remembers recent revocation checks so each request does not hit the database.
+///
+/// Revocations made through this instance apply at once; revocations made by other
+/// instances are picked up once the cached answer is older than the TTL.
+pub struct RevocationCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Uuid, CachedCheck>>,
+}
+
+impl RevocationCache {
+    /// Entries kept before expired tokens are pruned.
+    const PRUNE_THRESHOLD: usize = 10_000;
+
+    pub fn new(ttl_seconds: u64) -> Self {
+        Self {
+            ttl: Duration::from_secs(ttl_seconds),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached answer for a token, if it is still trustworthy.
+    fn get(&self, token_id: Uuid) -> Option<bool> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(&token_id)?;
+
+        // A revoked token never comes back, so only a clean bill of health goes stale
+        (entry.revoked || entry.checked_at.elapsed() < self.ttl).then_some(entry.revoked)
+    }
+
+    fn put(&self, session: &AuthSession, revoked: bool) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if entries.len() >= Self::PRUNE_THRESHOLD {
+            let now = Utc::now().timestamp();
+            entries.retain(|_, entry| entry.exp >= now);
+        }
+
+        entries.insert(
+            session.jti,
+            CachedCheck {
+                user_id: session.user.id,
+                exp: session.exp,
+                checked_at: Instant::now(),
+                revoked,
+            },
+        );
+    }
+
+    /// Records that a single token was signed out.
+    pub fn revoke_token(&self, token_id: Uuid) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&token_id) {
+            entry.revoked = true;
+        }
+    }
+
+    /// Records that every session of `user_id` issued so far was signed out.
+    pub fn revoke_user(&self, user_id: Uuid) {
+        for entry in self.entries.lock().unwrap().values_mut() {
+            if entry.user_id == user_id {
+                entry.revoked = true;
+            }
+        }
     }
 }
 
@@ -106,12 +296,23 @@ pub fn generate_auth_cookie(
 ) -> Result<Cookie<'static>, Error> {
     let token_str = token_engine.create_token(user)?;
 
-    Ok(Cookie::build("auth-token", token_str)
+    Ok(auth_cookie(token_str))
+}
+
+/// A cookie that makes the browser drop the session cookie.
+pub fn removal_auth_cookie() -> Cookie<'static> {
+    let mut cookie = auth_cookie(String::new());
+    cookie.make_removal();
+    cookie
+}
+
+fn auth_cookie(token_str: String) -> Cookie<'static> {
+    Cookie::build("auth-token", token_str)
         .secure(true)
         .http_only(true)
         .same_site(SameSite::Strict)
         .path("/")
-        .finish())
+        .finish()
 }
 
 pub struct AuthMiddleware;
@@ -164,35 +365,147 @@ where
                         StatusCode::INTERNAL_SERVER_ERROR,
                     ))?;
 
-            if let Some(cookie) = req.cookie("auth-token") {
-                let token_str = cookie.value();
+            let bearer = req
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(|token| token.trim().to_string());
+
+            // An explicit Authorization header wins over the cookie
+            let (token_str, from_cookie) = match bearer {
+                Some(token) => (token, false),
+                None => match req.cookie("auth-token") {
+                    Some(cookie) => (cookie.value().to_string(), true),
+                    None => return Err(ErrorUnauthorized("Missing authentication token")),
+                },
+            };
+
+            if !from_cookie && token_str.starts_with(API_KEY_PREFIX) {
+                let principal = authenticate_api_key(&req, &token_str).await?;
+                req.extensions_mut().insert(Rc::new(principal.user));
+                return srv.call(req).await;
+            }
 
-                match token_engine.verify_token(token_str) {
-                    Ok(user) => {
-                        req.extensions_mut().insert(Rc::new(user));
-                        return srv.call(req).await;
-                    }
-                    Err(e) => return Err(e),
-                }
+            let session = token_engine.verify_session(&token_str)?;
+            check_revocation(&req, &session).await?;
+
+            // Bearer clients hold on to their own token, so only cookies are refreshed
+            let refreshed = if from_cookie {
+                token_engine.refresh_token(&session)?
+            } else {
+                None
+            };
+
+            // Refreshed tokens share the id, so a revocation has to outlive all of them
+            req.extensions_mut().insert(SessionToken {
+                id: session.jti,
+                expires_at: DateTime::from_timestamp(token_engine.session_ends_at(&session), 0)
+                    .unwrap_or_default(),
+            });
+            req.extensions_mut().insert(Rc::new(session.user));
+            let mut res = srv.call(req).await?;
+
+            // Handlers that already set or cleared the cookie (login, logout) take precedence
+            if let Some(token_str) = refreshed
+                && !res
+                    .response()
+                    .cookies()
+                    .any(|cookie| cookie.name() == "auth-token")
+            {
+                res.response_mut()
+                    .add_cookie(&auth_cookie(token_str))
+                    .map_err(|_| ErrorInternalServerError("Failed to refresh session"))?;
             }
 
-            Err(ErrorUnauthorized("Missing authentication token"))
+            Ok(res)
         })
     }
 }
 
+/// Refuses sessions that were signed out before they expired.
+async fn check_revocation(req: &ServiceRequest, session: &AuthSession) -> Result<(), Error> {
+    // Without a pool there is nothing to check revocations against
+    let Some(pool) = req.app_data::<Data<DbPool>>() else {
+        return Ok(());
+    };
+    let cache = req.app_data::<Data<RevocationCache>>();
+
+    let revoked = match cache.and_then(|cache| cache.get(session.jti)) {
+        Some(revoked) => revoked,
+        None => {
+            let issued_at = DateTime::from_timestamp_millis(session.iat)
+                .ok_or(ErrorUnauthorized("Invalid session data"))?;
+
+            let revoked = auth::is_session_revoked(pool, session.user.id, session.jti, issued_at)
+                .await
+                .map_err(|_| ErrorInternalServerError("Failed to check session"))?;
+
+            if let Some(cache) = cache {
+                cache.put(session, revoked);
+            }
+            revoked
+        }
+    };
+
+    if revoked {
+        return Err(ErrorUnauthorized("Session has been revoked"));
+    }
+
+    Ok(())
+}
+
+/// Resolves an API key and checks it is scoped for the requested resource.
+async fn authenticate_api_key(req: &ServiceRequest, key: &str) -> Result<ApiKeyPrincipal, Error> {
+    let pool = req.app_data::<Data<DbPool>>().ok_or(InternalError::new(
+        "Internal Error: API keys need a database pool",
+        StatusCode::INTERNAL_SERVER_ERROR,
+    ))?;
+
+    let principal = api_key::authenticate(pool, key)
+        .await
+        .map_err(|_| ErrorInternalServerError("Failed to check API key"))?
+        .ok_or(ErrorUnauthorized("Invalid API key"))?;
+
+    let (resource, access) = required_scope(req);
+    if !principal.grants(resource, access) {
+        return Err(ErrorForbidden("API key is not scoped for this resource"));
+    }
+
+    Ok(principal)
+}
+
+/// The scope a request needs: the first path segment under `/api/v1`, read for safe methods and
+/// write for the rest.
+fn required_scope(req: &ServiceRequest) -> (&str, Access) {
+    let path = req.path();
+    let path = path.strip_prefix("/api/v1").unwrap_or(path);
+    let resource = path
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default();
+
+    let access = if req.method().is_safe() {
+        Access::Read
+    } else {
+        Access::Write
+    };
+
+    (resource, access)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use actix_web::test::{self, TestRequest};
-    use actix_web::web::Data;
-    use uuid::Uuid;
 
     fn default_user() -> SessionUser {
         SessionUser {
             id: Uuid::new_v4(),
             staff_id: None,
             email: "perf_test@test.com".to_string(),
+            permissions: Vec::new(),
         }
     }
 
@@ -200,6 +513,9 @@ mod tests {
         let config = SecuritySettings {
             key: "01234567890123456789012345678901".to_string(),
             session_duration: 3600,
+            revocation_cache_ttl: 30,
+            max_session_age: 86400,
+            previous_keys: Vec::new(),
         };
         TokenEngine::new(&config)
     }
@@ -238,6 +554,9 @@ mod tests {
         let config = SecuritySettings {
             key: "01234567890123456789012345678901".to_string(),
             session_duration: 0,
+            revocation_cache_ttl: 30,
+            max_session_age: 86400,
+            previous_keys: Vec::new(),
         };
 
         let service = TokenEngine::new(&config);
@@ -271,4 +590,132 @@ mod tests {
 
         assert!(resp.is_ok());
     }
+
+    fn sealed_session(service: &TokenEngine, exp_in: i64, signed_in_ago: i64) -> String {
+        let now = Utc::now();
+        let session = AuthSession {
+            jti: Uuid::new_v4(),
+            exp: now.timestamp() + exp_in,
+            iat: (now.timestamp() - signed_in_ago) * 1000,
+            user: default_user(),
+        };
+        service.seal(&session).unwrap()
+    }
+
+    #[test]
+    fn test_refresh_token() {
+        let service = get_test_service();
+
+        let fresh = service
+            .verify_session(&sealed_session(&service, 3000, 600))
+            .unwrap();
+        assert!(service.refresh_token(&fresh).unwrap().is_none());
+
+        let stale = service
+            .verify_session(&sealed_session(&service, 60, 600))
+            .unwrap();
+        let token = service.refresh_token(&stale).unwrap().unwrap();
+        let refreshed = service.verify_session(&token).unwrap();
+        assert_eq!(refreshed.jti, stale.jti);
+        assert_eq!(refreshed.iat, stale.iat);
+        assert!(refreshed.exp > stale.exp);
+    }
+
+    #[test]
+    fn test_refresh_stops_at_max_session_age() {
+        let service = get_test_service();
+
+        // Twenty minutes of the day left: the refresh is cut short
+        let late = service
+            .verify_session(&sealed_session(&service, 60, 85200))
+            .unwrap();
+        let token = service.refresh_token(&late).unwrap().unwrap();
+        let refreshed = service.verify_session(&token).unwrap();
+        assert_eq!(refreshed.exp, service.session_ends_at(&late));
+
+        // Already at the limit: nothing left to extend
+        assert!(service.refresh_token(&refreshed).unwrap().is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_middleware_refreshes_cookie() {
+        let service = get_test_service();
+
+        let req = TestRequest::default()
+            .app_data(Data::new(service.clone()))
+            .cookie(Cookie::new("auth-token", sealed_session(&service, 60, 600)))
+            .to_srv_request();
+        let resp = AuthMiddleware
+            .new_transform(test::ok_service())
+            .await
+            .unwrap()
+            .call(req)
+            .await
+            .unwrap();
+        let cookie = resp.response().cookies().next().expect("Cookie refreshed");
+        assert!(service.verify_session(cookie.value()).is_ok());
+
+        let req = TestRequest::default()
+            .app_data(Data::new(service.clone()))
+            .cookie(generate_auth_cookie(&service, default_user()).unwrap())
+            .to_srv_request();
+        let resp = AuthMiddleware
+            .new_transform(test::ok_service())
+            .await
+            .unwrap()
+            .call(req)
+            .await
+            .unwrap();
+        assert!(resp.response().cookies().next().is_none());
+    }
+
+    fn get_rotated_service(key: &str, previous_keys: &[&str]) -> TokenEngine {
+        let config = SecuritySettings {
+            key: key.to_string(),
+            session_duration: 3600,
+            revocation_cache_ttl: 30,
+            max_session_age: 86400,
+            previous_keys: previous_keys.iter().map(|key| key.to_string()).collect(),
+        };
+        TokenEngine::new(&config)
+    }
+
+    #[test]
+    fn test_previous_key_still_decrypts() {
+        let old_key = "01234567890123456789012345678901";
+        let new_key = "abcdefghijklmnopqrstuvwxyzABCDEF";
+        let before = get_rotated_service(old_key, &[]);
+        let after = get_rotated_service(new_key, &[old_key]);
+        let user = default_user();
+
+        let old_token = before.create_token(user.clone()).unwrap();
+        assert_eq!(after.verify_token(&old_token).unwrap(), user);
+
+        // New tokens are sealed with the primary key only
+        let new_token = after.create_token(user.clone()).unwrap();
+        assert_eq!(after.verify_token(&new_token).unwrap(), user);
+        assert!(before.verify_token(&new_token).is_err());
+    }
+
+    #[test]
+    fn test_removed_key_rejected() {
+        let old_key = "01234567890123456789012345678901";
+        let new_key = "abcdefghijklmnopqrstuvwxyzABCDEF";
+        let before = get_rotated_service(old_key, &[]);
+        let after = get_rotated_service(new_key, &[]);
+
+        let old_token = before.create_token(default_user()).unwrap();
+        assert!(after.verify_token(&old_token).is_err());
+    }
+
+    #[test]
+    fn test_key_id_is_authenticated() {
+        let service = get_test_service();
+        let token = service.create_token(default_user()).unwrap();
+
+        let mut raw = BASE64.decode(&token).unwrap();
+        raw[0] ^= 0x01;
+
+        assert!(service.verify_token(&BASE64.encode(raw)).is_err());
+    }
 }
@@ -0,0 +1,83 @@
+use actix_web::{
+    Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, dev::Payload, error::InternalError,
+    http::StatusCode,
+};
+use app::auth::{Permission, SessionUser};
+use futures_util::future::{Ready, ready};
+use std::{marker::PhantomData, ops::Deref, rc::Rc};
+
+/// A permission a handler requires, named by a marker type so it can sit in the signature.
+pub trait Requirement {
+    const PERMISSION: Permission;
+}
+
+macro_rules! requirements {
+    ($($name:ident),* $(,)?) => {
+        $(
+            pub struct $name;
+
+            impl Requirement for $name {
+                const PERMISSION: Permission = Permission::$name;
+            }
+        )*
+    };
+}
+
+requirements!(
+    ReadRooms,
+    ManageBookings,
+    ManageHousekeeping,
+    ManageMaintenance,
+    ModerateReports,
+    ReadUsers,
+    ManageUsers,
+    ManageApiKeys,
+    ManageRoles,
+    ManageRates,
+    ManagePromotions,
+);
+
+/// The signed-in user, extracted only when they hold the permission `P` stands for.
+///
+/// Declare it in a handler behind `AuthMiddleware`, e.g. `user: Authorized<ManageHousekeeping>`.
+/// Users without the permission get the same 401 the domains answer with.
+pub struct Authorized<P: Requirement> {
+    user: Rc<SessionUser>,
+    _requirement: PhantomData<P>,
+}
+
+impl<P: Requirement> Deref for Authorized<P> {
+    type Target = SessionUser;
+
+    fn deref(&self) -> &SessionUser {
+        &self.user
+    }
+}
+
+impl<P: Requirement> FromRequest for Authorized<P> {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let Some(user) = req.extensions().get::<Rc<SessionUser>>().cloned() else {
+            return ready(Err(InternalError::new(
+                "Internal Error: route is not behind AuthMiddleware",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into()));
+        };
+
+        if !user.can(P::PERMISSION) {
+            return ready(Err(InternalError::from_response(
+                "Unauthorized",
+                HttpResponse::Unauthorized().json("Unauthorized"),
+            )
+            .into()));
+        }
+
+        ready(Ok(Self {
+            user,
+            _requirement: PhantomData,
+        }))
+    }
+}
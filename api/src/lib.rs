@@ -1,13 +1,22 @@
 pub mod auth;
+pub mod guard;
 pub mod openapi;
 pub mod v1;
 
+#[cfg(test)]
+mod test_utils;
+
 use crate::openapi::ApiDoc;
-use crate::{auth::TokenEngine, v1::configure_v1_routes};
+use crate::{
+    auth::{RevocationCache, TokenEngine},
+    v1::configure_v1_routes,
+};
 use actix_web::{App, HttpServer, web};
 use app::AppSettings;
+use app::notifications::NotificationSender;
 use diesel_async::AsyncPgConnection;
 use diesel_async::pooled_connection::deadpool::Pool;
+use infra::services::notifications::init_sender;
 
 use tracing_actix_web::TracingLogger;
 use utoipa::OpenApi;
@@ -23,17 +32,26 @@ pub async fn run(pool: Pool<AsyncPgConnection>, settings: AppSettings) -> std::i
 
     let pool = web::Data::new(pool.clone());
     let token_engine = web::Data::new(token_engine.clone());
+    let revocations = web::Data::new(RevocationCache::new(settings.security.revocation_cache_ttl));
     let app_settings = web::Data::new(settings.clone());
+    let sender: web::Data<dyn NotificationSender> = web::Data::from(
+        init_sender(&settings.notifications, &settings.application.environment)
+            .map_err(std::io::Error::other)?,
+    );
 
     HttpServer::new(move || {
         let pool = pool.clone();
         let token_engine = token_engine.clone();
         let app_settings = app_settings.clone();
+        let sender = sender.clone();
+        let revocations = revocations.clone();
         App::new()
             .wrap(TracingLogger::default())
             .app_data(pool)
             .app_data(app_settings)
             .app_data(token_engine)
+            .app_data(sender)
+            .app_data(revocations)
             .configure(|cfg| {
                 cfg.service(web::scope("/api").configure(configure_v1_routes))
                     .service(
@@ -2,6 +2,33 @@ use crate::v1;
 use app::auth::SessionUser;
 use app::auth::login::LoginRequest;
 use app::auth::onboard::OnboardRequest;
+use app::auth::otp::{IssueOtpRequest, IssueOtpSuccess};
+use app::auth::password::{ChangePasswordRequest, ForgotPasswordRequest, ResetPasswordRequest};
+use app::auth::signup::{SignupRequest, SignupSuccess};
+use app::bookings::amend::AmendBookingSuccess;
+use app::bookings::cancel::CancelBookingSuccess;
+use app::bookings::create::{CreateBookingRequest, CreateBookingSuccess};
+use app::bookings::lifecycle::BookingAction;
+use app::bookings::move_room::MoveBookingSuccess;
+use app::bookings::transition::{BookingTransition, TransitionBookingSuccess};
+use app::bookings::{BookingDetails, BookingStatus};
+use app::housekeeping::create::{CreateHousekeepingRequest, CreateHousekeepingSuccess};
+use app::housekeeping::delete::DeleteHousekeepingSuccess;
+use app::housekeeping::get::GetHousekeepingSuccess;
+use app::housekeeping::update::UpdateHousekeepingSuccess;
+use app::housekeeping::{HousekeepingDetails, HousekeepingStatus};
+use app::maintenance::close::CloseWorkOrderSuccess;
+use app::maintenance::create::{CreateWorkOrderRequest, CreateWorkOrderSuccess};
+use app::maintenance::extend::ExtendWorkOrderSuccess;
+use app::maintenance::list::ListWorkOrdersSuccess;
+use app::maintenance::reassign::ReassignWorkOrderSuccess;
+use app::maintenance::{MaintenanceKind, MaintenanceSeverity, WorkOrder};
+use app::reports::ReportDetails;
+use app::reports::create::{CreateReportRequest, CreateReportSuccess};
+use app::reports::delete::DeleteReportSuccess;
+use app::reports::list::ListReportsSuccess;
+use app::reports::restore::RestoreReportSuccess;
+use app::reports::update::UpdateReportSuccess;
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
@@ -10,8 +37,73 @@ use utoipa::OpenApi;
         // Auth
         v1::auth::routes::login,
         v1::auth::routes::onboard,
+        v1::auth::routes::issue_otp,
+        v1::auth::routes::signup,
+        v1::auth::routes::forgot_password,
+        v1::auth::routes::reset_password,
+        v1::auth::routes::change_password,
+        v1::auth::routes::logout,
+        v1::auth::routes::verify_login_totp,
+        v1::auth::routes::enroll_totp,
+        v1::auth::routes::confirm_totp,
+        v1::auth::routes::disable_totp,
+        // API keys
+        v1::api_keys::routes::create_api_key,
+        v1::api_keys::routes::list_api_keys,
+        v1::api_keys::routes::revoke_api_key,
+        // Bookings
+        v1::bookings::routes::create_booking,
+        v1::bookings::routes::cancel_booking,
+        v1::bookings::routes::amend_booking,
+        v1::bookings::routes::move_booking,
+        v1::bookings::routes::confirm_booking,
+        v1::bookings::routes::check_in_booking,
+        v1::bookings::routes::check_out_booking,
+        v1::bookings::routes::mark_no_show,
+        // Housekeeping
+        v1::housekeeping::routes::create_housekeeping,
+        v1::housekeeping::routes::get_housekeeping,
+        v1::housekeeping::routes::update_housekeeping,
+        v1::housekeeping::routes::delete_housekeeping,
+        // Maintenance
+        v1::maintenance::routes::create_work_order,
+        v1::maintenance::routes::list_work_orders,
+        v1::maintenance::routes::reassign_work_order,
+        v1::maintenance::routes::extend_work_order,
+        v1::maintenance::routes::close_work_order,
+        // Pricing
+        v1::pricing::routes::list_occupancy_bands,
+        v1::pricing::routes::set_occupancy_bands,
+        v1::pricing::routes::price_curve,
+        // Promo codes
+        v1::promo_codes::routes::create_promo_code,
+        v1::promo_codes::routes::list_promo_codes,
+        v1::promo_codes::routes::disable_promo_code,
+        // Quotes
+        v1::quotes::routes::get_quote,
+        // Rate plans
+        v1::rate_plans::routes::list_rate_plans,
+        v1::rate_plans::routes::create_rate_plan,
+        v1::rate_plans::routes::archive_rate_plan,
+        v1::rate_plans::routes::add_season,
+        v1::rate_plans::routes::remove_season,
+        v1::rate_plans::routes::set_weekday_rules,
+        // Reports
+        v1::reports::routes::create_report,
+        v1::reports::routes::list_reports,
+        v1::reports::routes::update_report,
+        v1::reports::routes::delete_report,
+        v1::reports::routes::restore_report,
+        // Stay rules
+        v1::stay_rules::routes::list_stay_rules,
+        v1::stay_rules::routes::create_stay_rule,
+        v1::stay_rules::routes::delete_stay_rule,
         // Users
         v1::users::routes::get_user,
+        v1::users::routes::invite_user,
+        v1::users::routes::revoke_user_sessions,
+        v1::users::routes::unlock_user,
+        v1::users::routes::assign_user_roles,
         // Rooms
         v1::rooms::routes::get_room_availability,
         v1::rooms::routes::get_room_details,
@@ -22,9 +114,102 @@ use utoipa::OpenApi;
         schemas(
             LoginRequest,
             OnboardRequest,
+            IssueOtpRequest,
+            IssueOtpSuccess,
+            SignupRequest,
+            SignupSuccess,
+            ForgotPasswordRequest,
+            ResetPasswordRequest,
+            ChangePasswordRequest,
+            app::auth::totp::LoginChallenge,
+            app::auth::totp::VerifyTotpRequest,
+            app::auth::totp::EnrollTotpSuccess,
+            app::auth::totp::ConfirmTotpRequest,
+            app::auth::totp::ConfirmTotpSuccess,
+            app::auth::totp::DisableTotpRequest,
+            app::api::ValidationErrors,
+            app::users::details::UserDetails,
+            app::users::invite::InviteUserRequest,
+            app::users::invite::InviteUserSuccess,
+            app::users::sessions::RevokeSessionsSuccess,
+            app::users::lockout::UnlockAccountSuccess,
+            app::users::roles::AssignRolesRequest,
+            app::users::roles::AssignRolesSuccess,
+            app::auth::Permission,
+            app::api_keys::ApiKeyDetails,
+            app::api_keys::create::CreateApiKeyRequest,
+            app::api_keys::create::CreateApiKeySuccess,
+            app::api_keys::list::ListApiKeysSuccess,
+            app::api_keys::revoke::RevokeApiKeySuccess,
             SessionUser,
+            BookingDetails,
+            BookingStatus,
+            CreateBookingRequest,
+            CreateBookingSuccess,
+            CancelBookingSuccess,
+            AmendBookingSuccess,
+            MoveBookingSuccess,
+            TransitionBookingSuccess,
+            BookingTransition,
+            BookingAction,
+            v1::bookings::dtos::AmendBookingBody,
+            v1::bookings::dtos::MoveBookingBody,
+            HousekeepingDetails,
+            HousekeepingStatus,
+            CreateHousekeepingRequest,
+            CreateHousekeepingSuccess,
+            GetHousekeepingSuccess,
+            UpdateHousekeepingSuccess,
+            DeleteHousekeepingSuccess,
+            v1::housekeeping::dtos::UpdateHousekeepingBody,
+            WorkOrder,
+            MaintenanceKind,
+            MaintenanceSeverity,
+            CreateWorkOrderRequest,
+            CreateWorkOrderSuccess,
+            ListWorkOrdersSuccess,
+            ReassignWorkOrderSuccess,
+            ExtendWorkOrderSuccess,
+            CloseWorkOrderSuccess,
+            v1::maintenance::dtos::ReassignWorkOrderBody,
+            v1::maintenance::dtos::ExtendWorkOrderBody,
+            app::pricing::Weekday,
+            app::pricing::Season,
+            app::pricing::WeekdayRule,
+            app::pricing::PriceSource,
+            app::pricing::NightlyPrice,
+            app::pricing::quote::Quote,
+            app::pricing::quote::TaxLine,
+            app::pricing::plans::RatePlanDetails,
+            app::pricing::plans::ListRatePlansSuccess,
+            app::pricing::plans::CreateRatePlanRequest,
+            app::pricing::seasons::AddSeasonRequest,
+            app::pricing::weekdays::SetWeekdayRulesRequest,
+            app::pricing::occupancy::OccupancyBand,
+            app::pricing::occupancy::OccupancyBands,
+            app::pricing::occupancy::SetOccupancyBandsRequest,
+            app::pricing::curve::PricePoint,
+            app::pricing::curve::PriceCurve,
+            app::promotions::PromoCodeDetails,
+            app::promotions::PromoRejection,
+            app::promotions::AppliedDiscount,
+            app::promotions::create::CreatePromoCodeRequest,
+            app::promotions::list::ListPromoCodesSuccess,
+            app::stay_rules::StayRule,
+            app::stay_rules::StayRestriction,
+            app::stay_rules::list::ListStayRulesSuccess,
+            app::stay_rules::create::CreateStayRuleRequest,
+            ReportDetails,
+            CreateReportRequest,
+            CreateReportSuccess,
+            ListReportsSuccess,
+            UpdateReportSuccess,
+            DeleteReportSuccess,
+            RestoreReportSuccess,
+            v1::reports::dtos::UpdateReportBody,
             v1::rooms::dtos::RoomAvailability,
             v1::rooms::dtos::CalendarBlock,
+            app::rooms::availability::BlockConflict,
             v1::rooms::dtos::BlockKind,
             v1::rooms::dtos::AmenityDto,
             v1::rooms::dtos::RoomClassResponse,
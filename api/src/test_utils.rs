@@ -0,0 +1,129 @@
+use app::AppSettings;
+use app::notifications::{Notification, NotificationSender, SendFuture};
+use bigdecimal::BigDecimal;
+use config::{Config, File};
+use diesel_async::RunQueryDsl;
+use infra::db;
+use infra::models::{NewRoom, NewRoomClass, NewStaff, NewUser, NewUserRole};
+use infra::schema::{room_classes, rooms, staff, user_roles, users};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+pub fn get_test_config() -> AppSettings {
+    dotenvy::dotenv().ok();
+    let run_mode = std::env::var("RUN_MODE").unwrap_or("development".to_string());
+
+    Config::builder()
+        .add_source(File::with_name("config/default").required(false))
+        .add_source(File::with_name("../config/default").required(false))
+        .add_source(File::with_name(&format!("config/{}", run_mode)).required(false))
+        .add_source(File::with_name(&format!("../config/{}", run_mode)).required(false))
+        .add_source(config::Environment::with_prefix("APP").separator("__"))
+        .build()
+        .expect("Failed to build configuration")
+        .try_deserialize()
+        .expect("Failed to deserialize configuration")
+}
+
+pub async fn get_test_pool(config: &AppSettings) -> db::DbPool {
+    db::init_pool(&config.database).expect("Failed to init pool")
+}
+
+pub async fn setup_test_room(pool: &db::DbPool) -> (Uuid, Uuid) {
+    let mut conn = pool.get().await.expect("Failed to get conn");
+
+    let class_id = Uuid::new_v4();
+    let new_class = NewRoomClass {
+        id: Some(class_id),
+        name: "Test Class",
+        base_price: BigDecimal::from(100),
+        turnover_hours: None,
+    };
+
+    diesel::insert_into(room_classes::table)
+        .values(&new_class)
+        .execute(&mut conn)
+        .await
+        .expect("Failed to insert room class");
+
+    let room_id = Uuid::new_v4();
+    let new_room = NewRoom {
+        id: Some(room_id),
+        label: "Test Room",
+        class_id,
+    };
+
+    diesel::insert_into(rooms::table)
+        .values(&new_room)
+        .execute(&mut conn)
+        .await
+        .expect("Failed to insert room");
+
+    (room_id, class_id)
+}
+
+pub async fn setup_test_user(pool: &db::DbPool) -> Uuid {
+    let mut conn = pool.get().await.expect("Failed to get conn");
+
+    let user_id = Uuid::new_v4();
+    let email = format!("{}@test.com", user_id);
+
+    diesel::insert_into(users::table)
+        .values(&NewUser {
+            id: Some(user_id),
+            email: &email,
+            password_hash: None,
+        })
+        .execute(&mut conn)
+        .await
+        .expect("Failed to insert user");
+
+    user_id
+}
+
+pub async fn setup_test_staff(pool: &db::DbPool) -> (Uuid, Uuid) {
+    let user_id = setup_test_user(pool).await;
+    let mut conn = pool.get().await.expect("Failed to get conn");
+
+    let staff_id = Uuid::new_v4();
+    diesel::insert_into(staff::table)
+        .values(&NewStaff {
+            id: Some(staff_id),
+            user_id,
+        })
+        .execute(&mut conn)
+        .await
+        .expect("Failed to insert staff");
+
+    diesel::insert_into(user_roles::table)
+        .values(&NewUserRole {
+            user_id,
+            role_name: "admin",
+        })
+        .execute(&mut conn)
+        .await
+        .expect("Failed to grant role");
+
+    (user_id, staff_id)
+}
+
+/// Keeps sent one-time codes in memory so tests can read them back.
+#[derive(Default)]
+pub struct CapturingSender {
+    codes: Mutex<Vec<String>>,
+}
+
+impl CapturingSender {
+    pub fn codes(&self) -> Vec<String> {
+        self.codes.lock().unwrap().clone()
+    }
+}
+
+impl NotificationSender for CapturingSender {
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        let (Notification::Otp { code, .. } | Notification::PasswordReset { code, .. }) =
+            notification;
+        self.codes.lock().unwrap().push(code.clone());
+        Box::pin(async { Ok(()) })
+    }
+}
@@ -0,0 +1,146 @@
+use actix_web::web;
+
+pub mod routes;
+
+use crate::auth::AuthMiddleware;
+use routes::*;
+
+pub fn configure_api_keys_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api-keys")
+            .route("", web::post().to(create_api_key).wrap(AuthMiddleware))
+            .route("", web::get().to(list_api_keys).wrap(AuthMiddleware))
+            .route(
+                "/{id}/revoke",
+                web::post().to(revoke_api_key).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{get_test_config, get_test_pool, setup_test_staff};
+    use crate::v1::users::configure_users_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use serde_json::{Value, json};
+
+    #[actix_web::test]
+    async fn test_api_key_lifecycle() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_api_keys_routes)
+                .configure(configure_users_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            staff_id: None,
+            permissions: Vec::new(),
+            ..staff.clone()
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/api-keys")
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(json!({ "name": "Kiosk", "scopes": ["users:read"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/api-keys")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "name": "Kiosk", "scopes": ["auth:write"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["errors"]["scopes"].is_array());
+
+        let req = test::TestRequest::post()
+            .uri("/api-keys")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "name": "Kiosk", "scopes": ["users:read"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let key = body["key"].as_str().unwrap().to_string();
+        let key_id = body["apiKey"]["id"].as_str().unwrap().to_string();
+        assert!(key.starts_with(body["apiKey"]["prefix"].as_str().unwrap()));
+
+        // The key reads users on behalf of the staff member who created it
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", user_id))
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // ...but is not scoped to write them, nor to manage keys
+        let req = test::TestRequest::post()
+            .uri("/users/invite")
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .set_json(json!({ "email": "someone@test.com" }))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(err.as_response_error().status_code(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get()
+            .uri("/api-keys")
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(err.as_response_error().status_code(), StatusCode::FORBIDDEN);
+
+        // Session tokens work as bearer tokens too
+        let session_token = token_engine.create_token(staff.clone()).unwrap();
+        let req = test::TestRequest::get()
+            .uri("/api-keys")
+            .insert_header(("Authorization", format!("Bearer {}", session_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let listed = body["apiKeys"].as_array().unwrap();
+        assert!(listed.iter().any(|listed| listed["id"] == key_id.as_str()));
+        assert!(listed.iter().all(|listed| listed.get("key").is_none()));
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api-keys/{}/revoke", key_id))
+            .insert_header(("Authorization", format!("Bearer {}", session_token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["apiKey"]["revokedAt"].is_string());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", user_id))
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManageApiKeys};
+use app::api::ValidationErrors;
+use app::api_keys::create::*;
+use app::api_keys::list::*;
+use app::api_keys::revoke::*;
+use infra::domains::api_key;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created; the secret is only returned here", body = CreateApiKeySuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid name, scopes or expiry", body = ValidationErrors)
+    )
+)]
+pub async fn create_api_key(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageApiKeys>,
+    web::Json(req): web::Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse, CreateApiKeyError> {
+    api_key::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/api-keys",
+    responses(
+        (status = 200, description = "API keys, newest first", body = ListApiKeysSuccess),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_api_keys(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageApiKeys>,
+) -> Result<HttpResponse, ListApiKeysError> {
+    api_key::list(&pool, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-keys/{id}/revoke",
+    params(
+        ("id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key revoked", body = RevokeApiKeySuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "API key not found")
+    )
+)]
+pub async fn revoke_api_key(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageApiKeys>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, RevokeApiKeyError> {
+    let options = RevokeApiKeyOptions {
+        id: path.into_inner(),
+    };
+
+    api_key::revoke(&pool, options, &user).await.into()
+}
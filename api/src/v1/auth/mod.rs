@@ -2,12 +2,726 @@ use actix_web::web;
 
 pub mod routes;
 
-use routes::{login, onboard};
+use routes::{
+    change_password, confirm_totp, disable_totp, enroll_totp, forgot_password, issue_otp, login,
+    logout, onboard, reset_password, signup, verify_login_totp,
+};
+
+use crate::auth::AuthMiddleware;
 
 pub fn configure_auth_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
             .route("/login", web::post().to(login))
-            .route("/onboard", web::post().to(onboard)),
+            .route("/login/totp", web::post().to(verify_login_totp))
+            .route("/logout", web::post().to(logout).wrap(AuthMiddleware))
+            .route("/onboard", web::post().to(onboard))
+            .route("/otp", web::post().to(issue_otp))
+            .route("/signup", web::post().to(signup))
+            .route("/password/forgot", web::post().to(forgot_password))
+            .route("/password/reset", web::post().to(reset_password))
+            .route(
+                "/password/change",
+                web::post().to(change_password).wrap(AuthMiddleware),
+            )
+            .route(
+                "/totp/enroll",
+                web::post().to(enroll_totp).wrap(AuthMiddleware),
+            )
+            .route(
+                "/totp/confirm",
+                web::post().to(confirm_totp).wrap(AuthMiddleware),
+            )
+            .route(
+                "/totp/disable",
+                web::post().to(disable_totp).wrap(AuthMiddleware),
+            ),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{RevocationCache, TokenEngine};
+    use crate::test_utils::{CapturingSender, get_test_config, get_test_pool, setup_test_user};
+    use crate::v1::users::configure_users_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::login::account_subject;
+    use app::auth::totp::{totp_code, totp_step};
+    use app::notifications::NotificationSender;
+    use chrono::Utc;
+    use diesel::prelude::*;
+    use diesel_async::RunQueryDsl;
+    use infra::models::{NewOtp, OtpPurpose};
+    use infra::schema::{login_throttles, otps, role_permissions, roles, user_roles, users};
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    async fn test_issue_otp_and_onboard() {
+        let mut config = get_test_config();
+        config.otp.cooldown = 0;
+        config.otp.hourly_limit = 2;
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let user_id = setup_test_user(&pool).await;
+        let sender = Arc::new(CapturingSender::default());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::<dyn NotificationSender>::from(
+                    sender.clone() as Arc<dyn NotificationSender>
+                ))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        for _ in 0..2 {
+            let req = test::TestRequest::post()
+                .uri("/auth/otp")
+                .set_json(json!({ "userId": user_id }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        }
+
+        // The hourly limit is reached
+        let req = test::TestRequest::post()
+            .uri("/auth/otp")
+            .set_json(json!({ "userId": user_id }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers().contains_key("Retry-After"));
+
+        let codes = sender.codes();
+        assert_eq!(codes.len(), 2);
+        assert!(codes.iter().all(|c| c.len() == 6));
+
+        // Issuing a new code invalidated the first one
+        let req = test::TestRequest::post()
+            .uri("/auth/onboard")
+            .set_json(json!({ "userId": user_id, "otp": codes[0], "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/onboard")
+            .set_json(json!({ "userId": user_id, "otp": codes[1], "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Onboarded and unknown users get the same answer, and no code
+        for user_id in [user_id, uuid::Uuid::new_v4()] {
+            let req = test::TestRequest::post()
+                .uri("/auth/otp")
+                .set_json(json!({ "userId": user_id }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        }
+        assert_eq!(sender.codes().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_onboard_locks_out_guessing() {
+        let mut config = get_test_config();
+        config.login.max_failures = 3;
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let user_id = setup_test_user(&pool).await;
+        let sender = Arc::new(CapturingSender::default());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::<dyn NotificationSender>::from(
+                    sender.clone() as Arc<dyn NotificationSender>
+                ))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/otp")
+            .set_json(json!({ "userId": user_id }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        let code = sender.codes()[0].clone();
+
+        let onboard = |otp: &str| {
+            test::TestRequest::post()
+                .uri("/auth/onboard")
+                .set_json(json!({ "userId": user_id, "otp": otp, "password": "secret-pass" }))
+                .to_request()
+        };
+
+        for _ in 0..2 {
+            let resp = test::call_service(&app, onboard("wrong")).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let resp = test::call_service(&app, onboard("wrong")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers().contains_key("Retry-After"));
+
+        let resp = test::call_service(&app, onboard(&code)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // The code was voided along with the lockout, so lifting it doesn't bring the code back
+        let mut conn = pool.get().await.unwrap();
+        let email: String = users::table
+            .find(user_id)
+            .select(users::email)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        diesel::delete(
+            login_throttles::table.filter(login_throttles::subject.eq(account_subject(&email))),
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let resp = test::call_service(&app, onboard(&code)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_signup() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let email = format!("{}@test.com", uuid::Uuid::new_v4());
+
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert!(resp.response().cookies().next().is_some());
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["user"]["email"], json!(email));
+
+        // The new account can sign in straight away
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "email": email, "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body,
+            json!({ "errors": { "email": ["has already been taken"] } })
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": "not-an-email", "password": "short" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["errors"]["email"].is_array());
+        assert!(body["errors"]["password"].is_array());
+    }
+
+    #[actix_web::test]
+    async fn test_password_reset_and_change() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+        let sender = Arc::new(CapturingSender::default());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::<dyn NotificationSender>::from(
+                    sender.clone() as Arc<dyn NotificationSender>
+                ))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let email = format!("{}@test.com", uuid::Uuid::new_v4());
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "first-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let signup_cookie = resp.response().cookies().next().unwrap().into_owned();
+        let body: Value = test::read_body_json(resp).await;
+        let user_id = body["user"]["id"].clone();
+
+        // Unknown addresses look the same as known ones
+        let req = test::TestRequest::post()
+            .uri("/auth/password/forgot")
+            .set_json(json!({ "email": "nobody@test.com" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        assert!(sender.codes().is_empty());
+
+        // Onboarding codes are limited on their own, so they can't hold up a reset
+        let onboarding: Vec<NewOtp> = (0..config.otp.hourly_limit)
+            .map(|_| NewOtp {
+                user_id: user_id.as_str().unwrap().parse().unwrap(),
+                code: "000000",
+                expires_at: Utc::now(),
+                purpose: OtpPurpose::Onboarding,
+            })
+            .collect();
+        diesel::insert_into(otps::table)
+            .values(&onboarding)
+            .execute(&mut pool.get().await.unwrap())
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/auth/password/forgot")
+            .set_json(json!({ "email": email }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        let code = sender.codes()[0].clone();
+
+        // A reset code cannot be spent on onboarding
+        let req = test::TestRequest::post()
+            .uri("/auth/onboard")
+            .set_json(json!({ "userId": user_id, "otp": code, "password": "other-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/password/reset")
+            .set_json(json!({ "email": email, "otp": code, "password": "second-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let reset_cookie = resp.response().cookies().next().unwrap().into_owned();
+
+        // The session from before the reset is gone
+        let req = test::TestRequest::post()
+            .uri("/auth/password/change")
+            .cookie(signup_cookie)
+            .set_json(json!({ "currentPassword": "second-pass", "newPassword": "third-pass" }))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/auth/password/change")
+            .cookie(reset_cookie.clone())
+            .set_json(json!({ "currentPassword": "wrong-pass", "newPassword": "third-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/password/change")
+            .cookie(reset_cookie.clone())
+            .set_json(json!({ "currentPassword": "second-pass", "newPassword": "third-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let change_cookie = resp.response().cookies().next().unwrap().into_owned();
+
+        let req = test::TestRequest::post()
+            .uri("/auth/password/change")
+            .cookie(reset_cookie)
+            .set_json(json!({ "currentPassword": "third-pass", "newPassword": "short" }))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        // The cookie handed out with the change still works
+        let req = test::TestRequest::post()
+            .uri("/auth/password/change")
+            .cookie(change_cookie)
+            .set_json(json!({ "currentPassword": "third-pass", "newPassword": "short" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "email": email, "password": "third-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_password_reset_locks_out_guessing() {
+        let mut config = get_test_config();
+        config.login.max_failures = 3;
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+        let sender = Arc::new(CapturingSender::default());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::<dyn NotificationSender>::from(
+                    sender.clone() as Arc<dyn NotificationSender>
+                ))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let email = format!("{}@test.com", uuid::Uuid::new_v4());
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "first-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/password/forgot")
+            .set_json(json!({ "email": email }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        let code = sender.codes()[0].clone();
+
+        let reset = |otp: &str| {
+            test::TestRequest::post()
+                .uri("/auth/password/reset")
+                .set_json(json!({ "email": email, "otp": otp, "password": "second-pass" }))
+                .to_request()
+        };
+
+        for _ in 0..2 {
+            let resp = test::call_service(&app, reset("wrong")).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // The last miss allowed locks the account out
+        let resp = test::call_service(&app, reset("wrong")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers().contains_key("Retry-After"));
+
+        let resp = test::call_service(&app, reset(&code)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // The code was voided, so it stays dead once the lockout is lifted
+        diesel::delete(
+            login_throttles::table.filter(login_throttles::subject.eq(account_subject(&email))),
+        )
+        .execute(&mut pool.get().await.unwrap())
+        .await
+        .unwrap();
+
+        let resp = test::call_service(&app, reset(&code)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "email": email, "password": "first-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_logout() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(RevocationCache::new(
+                    config.security.revocation_cache_ttl,
+                )))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let email = format!("{}@test.com", uuid::Uuid::new_v4());
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "first-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let signup_cookie = resp.response().cookies().next().unwrap().into_owned();
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "email": email, "password": "first-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let login_cookie = resp.response().cookies().next().unwrap().into_owned();
+
+        let req = test::TestRequest::post()
+            .uri("/auth/logout")
+            .cookie(signup_cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let cleared = resp.response().cookies().next().unwrap();
+        assert_eq!(cleared.value(), "");
+
+        // The token is refused even though it has not expired
+        let req = test::TestRequest::post()
+            .uri("/auth/logout")
+            .cookie(signup_cookie)
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        // Other sessions of the same user are left alone
+        let req = test::TestRequest::post()
+            .uri("/auth/logout")
+            .cookie(login_cookie)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn test_totp_enrollment_and_login() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_auth_routes)
+                .configure(configure_users_routes),
+        )
+        .await;
+
+        let login = |email: &str| {
+            test::TestRequest::post()
+                .uri("/auth/login")
+                .set_json(json!({ "email": email, "password": "secret-pass" }))
+                .to_request()
+        };
+        let verify = |challenge: &Value, code: &str| {
+            test::TestRequest::post()
+                .uri("/auth/login/totp")
+                .set_json(json!({ "challengeToken": challenge["challengeToken"], "code": code }))
+                .to_request()
+        };
+
+        let email = format!("{}@test.com", uuid::Uuid::new_v4());
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let cookie = resp.response().cookies().next().unwrap().into_owned();
+        let body: Value = test::read_body_json(resp).await;
+        let user_id: uuid::Uuid = serde_json::from_value(body["user"]["id"].clone()).unwrap();
+
+        // A role that requires TOTP withholds its permissions until the user enrolls
+        let role = format!("totp_test_{}", user_id.simple());
+        {
+            let mut conn = pool.get().await.unwrap();
+            diesel::insert_into(roles::table)
+                .values((
+                    roles::name.eq(&role),
+                    roles::description.eq("Test role"),
+                    roles::requires_totp.eq(true),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            diesel::insert_into(role_permissions::table)
+                .values((
+                    role_permissions::role_name.eq(&role),
+                    role_permissions::permission_name.eq("users.read"),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            diesel::insert_into(user_roles::table)
+                .values((
+                    user_roles::user_id.eq(user_id),
+                    user_roles::role_name.eq(&role),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        let resp = test::call_service(&app, login(&email)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let cookie_without_permissions = resp.response().cookies().next().unwrap().into_owned();
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["totpEnrollmentRequired"], json!(true));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", uuid::Uuid::new_v4()))
+            .cookie(cookie_without_permissions.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/totp/enroll")
+            .cookie(cookie_without_permissions.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let secret = body["secret"].as_str().unwrap().to_string();
+        assert!(
+            body["provisioningUri"]
+                .as_str()
+                .unwrap()
+                .starts_with("otpauth://totp/")
+        );
+
+        let step = totp_step(Utc::now().timestamp());
+        let req = test::TestRequest::post()
+            .uri("/auth/totp/confirm")
+            .cookie(cookie_without_permissions.clone())
+            .set_json(json!({ "code": "000000x" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/totp/confirm")
+            .cookie(cookie_without_permissions)
+            .set_json(json!({ "code": totp_code(&secret, step).unwrap() }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let full_cookie = resp.response().cookies().next().unwrap().into_owned();
+        let body: Value = test::read_body_json(resp).await;
+        let recovery_codes: Vec<String> =
+            serde_json::from_value(body["recoveryCodes"].clone()).unwrap();
+        assert_eq!(recovery_codes.len(), 10);
+
+        // The new cookie carries the permissions of the role
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", uuid::Uuid::new_v4()))
+            .cookie(full_cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // The password alone now only earns a challenge
+        let resp = test::call_service(&app, login(&email)).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        assert!(resp.response().cookies().next().is_none());
+        let challenge: Value = test::read_body_json(resp).await;
+
+        // The code used to confirm cannot be replayed
+        let resp =
+            test::call_service(&app, verify(&challenge, &totp_code(&secret, step).unwrap())).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = test::call_service(
+            &app,
+            verify(&challenge, &totp_code(&secret, step + 1).unwrap()),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.response().cookies().next().is_some());
+
+        // Challenges are single use
+        let resp = test::call_service(&app, verify(&challenge, &recovery_codes[0])).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = test::call_service(&app, login(&email)).await;
+        let challenge: Value = test::read_body_json(resp).await;
+        let resp = test::call_service(&app, verify(&challenge, &recovery_codes[0])).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(&app, login(&email)).await;
+        let challenge: Value = test::read_body_json(resp).await;
+        let resp = test::call_service(&app, verify(&challenge, &recovery_codes[0])).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // The role keeps TOTP on
+        let req = test::TestRequest::post()
+            .uri("/auth/totp/disable")
+            .cookie(full_cookie.clone())
+            .set_json(json!({ "code": recovery_codes[1] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        diesel::delete(roles::table.find(&role))
+            .execute(&mut pool.get().await.unwrap())
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/auth/totp/disable")
+            .cookie(full_cookie)
+            .set_json(json!({ "code": recovery_codes[1] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/totp/enroll")
+            .cookie(cookie)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // An unconfirmed enrollment does not change how the user signs in
+        let resp = test::call_service(&app, login(&email)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -1,11 +1,54 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, web};
 use infra::db::DbPool;
+use std::rc::Rc;
 
-use crate::auth::{TokenEngine, generate_auth_cookie};
-use app::auth::login::LoginRequest;
+use crate::auth::{
+    RevocationCache, SessionToken, TokenEngine, generate_auth_cookie, removal_auth_cookie,
+};
+use app::AppSettings;
+use app::api::ValidationErrors;
+use app::auth::login::{LoginRequest, SignIn};
 use app::auth::onboard::OnboardRequest;
+use app::auth::otp::{IssueOtpError, IssueOtpRequest, IssueOtpSuccess};
+use app::auth::password::{
+    ChangePasswordError, ChangePasswordRequest, ForgotPasswordError, ForgotPasswordRequest,
+    ResetPasswordError, ResetPasswordRequest,
+};
+use app::auth::signup::{SignupError, SignupRequest, SignupSuccess};
+use app::auth::totp::{
+    ConfirmTotpRequest, ConfirmTotpSuccess, DisableTotpRequest, EnrollTotpSuccess, LoginChallenge,
+    TotpError, VerifyTotpRequest,
+};
 use app::auth::{AuthError, SessionUser};
-use infra::domains::auth;
+use app::notifications::NotificationSender;
+use app::users::details::UserDetails;
+use infra::domains::{auth, totp};
+
+/// Answers an accepted sign-in: a session cookie, or the challenge when a TOTP code is owed.
+fn sign_in_response(
+    token_engine: &TokenEngine,
+    sign_in: SignIn,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (user, enrollment_required) = match sign_in {
+        SignIn::Complete(user) => (user, false),
+        SignIn::EnrollmentRequired(user) => (user, true),
+        SignIn::Challenge { challenge, .. } => {
+            return Ok(HttpResponse::Accepted().json(challenge));
+        }
+    };
+
+    let cookie = generate_auth_cookie(token_engine, user.clone())?;
+
+    let mut body = serde_json::json!({
+        "id": user.id,
+        "email": user.email
+    });
+    if enrollment_required {
+        body["totpEnrollmentRequired"] = serde_json::json!(true);
+    }
+
+    Ok(HttpResponse::Ok().cookie(cookie).json(body))
+}
 
 #[utoipa::path(
     post,
@@ -13,24 +56,48 @@ use infra::domains::auth;
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = SessionUser),
-        (status = 401, description = "Invalid credentials")
+        (status = 202, description = "Password accepted; finish with a TOTP code", body = LoginChallenge),
+        (status = 401, description = "Invalid credentials"),
+        (status = 429, description = "Too many failed sign-ins; see Retry-After")
     )
 )]
 pub async fn login(
     pool: web::Data<DbPool>,
     token_engine: web::Data<TokenEngine>,
+    settings: web::Data<AppSettings>,
+    http: HttpRequest,
     web::Json(req): web::Json<LoginRequest>,
 ) -> Result<HttpResponse, AuthError> {
-    let user = auth::login(&pool, req).await?;
+    // The socket address, since forwarding headers are whatever the client says they are
+    let ip = http.peer_addr().map(|addr| addr.ip().to_string());
 
-    let cookie =
-        generate_auth_cookie(&token_engine, user.clone()).map_err(|_| AuthError::InternalError)?;
+    let sign_in = auth::login(&pool, req, ip.as_deref(), &settings.login).await?;
 
-    // Return simple JSON with id and email as requested
-    Ok(HttpResponse::Ok().cookie(cookie).json(serde_json::json!({
-        "id": user.id,
-        "email": user.email
-    })))
+    sign_in_response(&token_engine, sign_in).map_err(|_| AuthError::InternalError)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login/totp",
+    request_body = VerifyTotpRequest,
+    responses(
+        (status = 200, description = "Login successful", body = SessionUser),
+        (status = 401, description = "Invalid code or expired challenge"),
+        (status = 429, description = "Too many failed sign-ins; see Retry-After")
+    )
+)]
+pub async fn verify_login_totp(
+    pool: web::Data<DbPool>,
+    token_engine: web::Data<TokenEngine>,
+    settings: web::Data<AppSettings>,
+    http: HttpRequest,
+    web::Json(req): web::Json<VerifyTotpRequest>,
+) -> Result<HttpResponse, AuthError> {
+    let ip = http.peer_addr().map(|addr| addr.ip().to_string());
+
+    let sign_in = auth::verify_totp_challenge(&pool, req, ip.as_deref(), &settings.login).await?;
+
+    sign_in_response(&token_engine, sign_in).map_err(|_| AuthError::InternalError)
 }
 
 #[utoipa::path(
@@ -39,21 +106,238 @@ pub async fn login(
     request_body = OnboardRequest,
     responses(
         (status = 200, description = "Onboarding successful", body = SessionUser),
-        (status = 401, description = "Invalid credentials or expired OTP")
+        (status = 401, description = "Invalid credentials or expired OTP"),
+        (status = 429, description = "Too many wrong codes; see Retry-After")
     )
 )]
 pub async fn onboard(
     pool: web::Data<DbPool>,
     token_engine: web::Data<TokenEngine>,
+    settings: web::Data<AppSettings>,
+    http: HttpRequest,
     web::Json(req): web::Json<OnboardRequest>,
 ) -> Result<HttpResponse, AuthError> {
-    let user = auth::onboard(&pool, req).await?;
+    let ip = http.peer_addr().map(|addr| addr.ip().to_string());
 
-    let cookie =
-        generate_auth_cookie(&token_engine, user.clone()).map_err(|_| AuthError::InternalError)?;
+    let sign_in = auth::onboard(&pool, req, ip.as_deref(), &settings.login).await?;
 
-    Ok(HttpResponse::Ok().cookie(cookie).json(serde_json::json!({
-        "id": user.id,
-        "email": user.email
-    })))
+    sign_in_response(&token_engine, sign_in).map_err(|_| AuthError::InternalError)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/otp",
+    request_body = IssueOtpRequest,
+    responses(
+        (status = 202, description = "Code issued and sent if the user has yet to set a password", body = IssueOtpSuccess),
+        (status = 429, description = "Too many codes requested; see Retry-After")
+    )
+)]
+pub async fn issue_otp(
+    pool: web::Data<DbPool>,
+    settings: web::Data<AppSettings>,
+    sender: web::Data<dyn NotificationSender>,
+    web::Json(req): web::Json<IssueOtpRequest>,
+) -> Result<HttpResponse, IssueOtpError> {
+    let issued = auth::issue_otp(&pool, req, &settings.otp, sender.get_ref()).await?;
+
+    Ok(HttpResponse::Accepted().json(issued))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/signup",
+    request_body = SignupRequest,
+    responses(
+        (status = 201, description = "Account created and signed in", body = SignupSuccess),
+        (status = 422, description = "Invalid or already registered details", body = ValidationErrors)
+    )
+)]
+pub async fn signup(
+    pool: web::Data<DbPool>,
+    token_engine: web::Data<TokenEngine>,
+    web::Json(req): web::Json<SignupRequest>,
+) -> Result<HttpResponse, SignupError> {
+    let user = auth::signup(&pool, req).await?;
+
+    let cookie = generate_auth_cookie(&token_engine, user.clone())
+        .map_err(|_| SignupError::InternalError)?;
+
+    Ok(HttpResponse::Created().cookie(cookie).json(SignupSuccess {
+        user: UserDetails {
+            id: user.id,
+            email: user.email,
+        },
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password/forgot",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 202, description = "A reset code was sent if the account exists"),
+        (status = 429, description = "Too many codes requested; see Retry-After")
+    )
+)]
+pub async fn forgot_password(
+    pool: web::Data<DbPool>,
+    settings: web::Data<AppSettings>,
+    sender: web::Data<dyn NotificationSender>,
+    web::Json(req): web::Json<ForgotPasswordRequest>,
+) -> Result<HttpResponse, ForgotPasswordError> {
+    auth::forgot_password(&pool, req, &settings.otp, sender.get_ref()).await?;
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password/reset",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password reset and signed in", body = SessionUser),
+        (status = 202, description = "Password reset; finish signing in with a TOTP code", body = LoginChallenge),
+        (status = 401, description = "Invalid or expired code"),
+        (status = 422, description = "Password too weak", body = ValidationErrors),
+        (status = 429, description = "Too many wrong codes; see Retry-After")
+    )
+)]
+pub async fn reset_password(
+    pool: web::Data<DbPool>,
+    token_engine: web::Data<TokenEngine>,
+    settings: web::Data<AppSettings>,
+    revocations: Option<web::Data<RevocationCache>>,
+    http: HttpRequest,
+    web::Json(req): web::Json<ResetPasswordRequest>,
+) -> Result<HttpResponse, ResetPasswordError> {
+    let ip = http.peer_addr().map(|addr| addr.ip().to_string());
+
+    let sign_in = auth::reset_password(&pool, req, ip.as_deref(), &settings.login).await?;
+
+    if let Some(revocations) = revocations {
+        revocations.revoke_user(sign_in.user_id());
+    }
+
+    sign_in_response(&token_engine, sign_in).map_err(|_| ResetPasswordError::InternalError)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password/change",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Password changed; other sessions are signed out"),
+        (status = 401, description = "Unauthorized or wrong current password"),
+        (status = 422, description = "New password too weak", body = ValidationErrors)
+    )
+)]
+pub async fn change_password(
+    pool: web::Data<DbPool>,
+    token_engine: web::Data<TokenEngine>,
+    revocations: Option<web::Data<RevocationCache>>,
+    user: web::ReqData<Rc<SessionUser>>,
+    web::Json(req): web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse, ChangePasswordError> {
+    let user = auth::change_password(&pool, req, &user).await?;
+
+    if let Some(revocations) = revocations {
+        revocations.revoke_user(user.id);
+    }
+
+    // The old cookie was revoked along with every other session, so hand out a new one
+    let cookie = generate_auth_cookie(&token_engine, user)
+        .map_err(|_| ChangePasswordError::InternalError)?;
+
+    Ok(HttpResponse::NoContent().cookie(cookie).finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    responses(
+        (status = 204, description = "Session revoked and cookie cleared"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn logout(
+    pool: web::Data<DbPool>,
+    revocations: Option<web::Data<RevocationCache>>,
+    user: web::ReqData<Rc<SessionUser>>,
+    token: web::ReqData<SessionToken>,
+) -> Result<HttpResponse, AuthError> {
+    auth::revoke_session(&pool, user.id, token.id, token.expires_at).await?;
+
+    if let Some(revocations) = revocations {
+        revocations.revoke_token(token.id);
+    }
+
+    Ok(HttpResponse::NoContent()
+        .cookie(removal_auth_cookie())
+        .finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/enroll",
+    responses(
+        (status = 200, description = "Secret to add to an authenticator app", body = EnrollTotpSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Two-factor authentication is already enabled")
+    )
+)]
+pub async fn enroll_totp(
+    pool: web::Data<DbPool>,
+    settings: web::Data<AppSettings>,
+    user: web::ReqData<Rc<SessionUser>>,
+) -> Result<HttpResponse, TotpError> {
+    let enrollment = totp::enroll(&pool, &user, &settings.application.name).await?;
+
+    Ok(HttpResponse::Ok().json(enrollment))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/confirm",
+    request_body = ConfirmTotpRequest,
+    responses(
+        (status = 200, description = "Two-factor authentication enabled", body = ConfirmTotpSuccess),
+        (status = 401, description = "Unauthorized or invalid code"),
+        (status = 404, description = "No enrollment to confirm"),
+        (status = 409, description = "Two-factor authentication is already enabled")
+    )
+)]
+pub async fn confirm_totp(
+    pool: web::Data<DbPool>,
+    token_engine: web::Data<TokenEngine>,
+    user: web::ReqData<Rc<SessionUser>>,
+    web::Json(req): web::Json<ConfirmTotpRequest>,
+) -> Result<HttpResponse, TotpError> {
+    let (user, confirmed) = totp::confirm(&pool, req, &user).await?;
+
+    // Permissions withheld until enrollment come with the new cookie
+    let cookie = generate_auth_cookie(&token_engine, user).map_err(|_| TotpError::InternalError)?;
+
+    Ok(HttpResponse::Ok().cookie(cookie).json(confirmed))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/disable",
+    request_body = DisableTotpRequest,
+    responses(
+        (status = 204, description = "Two-factor authentication disabled"),
+        (status = 401, description = "Unauthorized or invalid code"),
+        (status = 404, description = "Two-factor authentication is not enabled"),
+        (status = 409, description = "A role of the user requires two-factor authentication")
+    )
+)]
+pub async fn disable_totp(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    web::Json(req): web::Json<DisableTotpRequest>,
+) -> Result<HttpResponse, TotpError> {
+    totp::disable(&pool, req, &user).await?;
+
+    Ok(HttpResponse::NoContent().finish())
 }
@@ -0,0 +1,621 @@
+use actix_web::web;
+
+pub mod dtos;
+pub mod routes;
+
+use routes::{
+    amend_booking, cancel_booking, check_in_booking, check_out_booking, confirm_booking,
+    create_booking, mark_no_show, move_booking,
+};
+
+use crate::auth::AuthMiddleware;
+
+pub fn configure_bookings_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/bookings")
+            .route("", web::post().to(create_booking).wrap(AuthMiddleware))
+            .route("/{id}", web::patch().to(amend_booking).wrap(AuthMiddleware))
+            .route(
+                "/{id}/cancel",
+                web::post().to(cancel_booking).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/move",
+                web::post().to(move_booking).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/confirm",
+                web::post().to(confirm_booking).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/check-in",
+                web::post().to(check_in_booking).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/check-out",
+                web::post().to(check_out_booking).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/no-show",
+                web::post().to(mark_no_show).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{get_test_config, get_test_pool, setup_test_room, setup_test_user};
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use chrono::{Duration, Utc};
+    use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+    use diesel_async::RunQueryDsl;
+    use infra::models::{Block, BookingStatus};
+    use infra::schema::{blocks, bookings, housekeeping, room_classes};
+    use serde_json::{Value, json};
+    use std::ops::Bound;
+    use uuid::Uuid;
+
+    fn booking_id(body: &Value) -> String {
+        body["booking"]["id"].as_str().unwrap().to_string()
+    }
+
+    #[actix_web::test]
+    async fn test_create_booking() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let user = SessionUser {
+            id: user_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+
+        let start = Utc::now() + Duration::days(30);
+        let end = start + Duration::days(3);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let id = booking_id(&body);
+
+        // Overlapping stay on the same room must be rejected by the exclusion constraint
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": start + Duration::days(1),
+                "end": end + Duration::days(1),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        // The response names the booking that is in the way
+        let body: Value = test::read_body_json(resp).await;
+        let conflicts = body["RoomUnavailable"]["conflicts"].as_array().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0]["id"], json!(id));
+        assert_eq!(conflicts[0]["type"], json!("BOOKING"));
+    }
+
+    #[actix_web::test]
+    async fn test_create_booking_for_other_guest_requires_staff() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let user = SessionUser {
+            id: user_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+
+        let start = Utc::now() + Duration::days(30);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": start,
+                "end": start + Duration::days(1),
+                "guestId": Uuid::new_v4(),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_cancel_frees_timeline() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let user = SessionUser {
+            id: user_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let start = Utc::now() + Duration::days(30);
+        let end = start + Duration::days(2);
+        let payload = json!({ "roomId": room_id, "start": start, "end": end });
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let id = booking_id(&body);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/cancel", id))
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["booking"]["status"], "CANCELLED");
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/cancel", id))
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        // The cancelled stay no longer holds the room
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    #[actix_web::test]
+    async fn test_amend_and_move_booking() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let (other_room_id, _) = setup_test_room(&pool).await;
+        let user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let guest = SessionUser {
+            id: user_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let staff = SessionUser {
+            id: Uuid::new_v4(),
+            staff_id: Some(Uuid::new_v4()),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+
+        let start = Utc::now() + Duration::days(30);
+        let mut ids = Vec::new();
+        for offset in [0, 5] {
+            let req = test::TestRequest::post()
+                .uri("/bookings")
+                .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+                .set_json(json!({
+                    "roomId": room_id,
+                    "start": start + Duration::days(offset),
+                    "end": start + Duration::days(offset + 2),
+                }))
+                .to_request();
+            let body: Value = test::call_and_read_body_json(&app, req).await;
+            ids.push(booking_id(&body));
+        }
+
+        // Extending the first stay into the second one must fail cleanly
+        let req = test::TestRequest::patch()
+            .uri(&format!("/bookings/{}", ids[0]))
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({ "start": start, "end": start + Duration::days(6) }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/bookings/{}", ids[0]))
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({ "start": start, "end": start + Duration::days(4) }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Guests cannot move bookings between rooms
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/move", ids[1]))
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(json!({ "roomId": other_room_id }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/move", ids[1]))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .set_json(json!({ "roomId": other_room_id }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["booking"]["roomId"], other_room_id.to_string());
+    }
+
+    #[actix_web::test]
+    async fn test_booking_lifecycle() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let staff_user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: staff_user_id,
+            staff_id: Some(Uuid::new_v4()),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+
+        // A stay that is already under way, so checking out now is an early check-out
+        let start = Utc::now() - Duration::days(1);
+        let end = start + Duration::days(4);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let id = booking_id(&body);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/check-out", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        for (action, status) in [
+            ("confirm", "CONFIRMED"),
+            ("check-in", "CHECKED_IN"),
+            ("check-out", "CHECKED_OUT"),
+        ] {
+            let req = test::TestRequest::post()
+                .uri(&format!("/bookings/{}/{}", id, action))
+                .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+                .to_request();
+            let body: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(body["booking"]["status"], status);
+            assert_eq!(body["transition"]["to"], status);
+            assert_eq!(body["transition"]["performedBy"], staff_user_id.to_string());
+        }
+
+        // Early check-out handed the rest of the stay back, so the room can be sold again
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": Utc::now() + Duration::hours(1),
+                "end": end,
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    #[actix_web::test]
+    async fn test_check_in_waits_for_the_stay() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let staff_user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: staff_user_id,
+            staff_id: Some(Uuid::new_v4()),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let start = Utc::now() + Duration::days(2);
+        let end = start + Duration::days(2);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let id = booking_id(&body);
+        let period = body["booking"]["period"].clone();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/confirm", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/check-in", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["NotStarted"]["startsAt"].is_string());
+
+        // A guest checked in early before the check was in place keeps their dates on check-out
+        let mut conn = pool.get().await.unwrap();
+        diesel::update(bookings::table.find(Uuid::parse_str(&id).unwrap()))
+            .set(bookings::status.eq(BookingStatus::CheckedIn))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/check-out", id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["booking"]["status"], "CHECKED_OUT");
+        assert_eq!(body["booking"]["period"], period);
+    }
+
+    #[actix_web::test]
+    async fn test_early_check_out_moves_turnover() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+        let staff_user_id = setup_test_user(&pool).await;
+
+        let mut conn = pool.get().await.unwrap();
+        diesel::update(room_classes::table.find(class_id))
+            .set(room_classes::turnover_hours.eq(Some(4)))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: staff_user_id,
+            staff_id: Some(Uuid::new_v4()),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let start = Utc::now() - Duration::days(1);
+        let end = start + Duration::days(4);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let id = booking_id(&body);
+
+        for action in ["confirm", "check-in", "check-out"] {
+            let req = test::TestRequest::post()
+                .uri(&format!("/bookings/{}/{}", id, action))
+                .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        // Cleaning starts when the guest left, and only once
+        let turnovers: Vec<Block> = blocks::table
+            .inner_join(housekeeping::table)
+            .filter(housekeeping::booking_id.eq(Uuid::parse_str(&id).unwrap()))
+            .select(Block::as_select())
+            .load(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(turnovers.len(), 1);
+        let Bound::Included(cleaning_starts) = turnovers[0].interval.0 else {
+            panic!("turnover should start at check-out");
+        };
+        assert!(cleaning_starts < start + Duration::days(2));
+
+        // Nothing is left blocking the room across the old end of the stay
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": end,
+                "end": end + Duration::days(1),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    #[actix_web::test]
+    async fn test_turnover_block_after_stay() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+        let user_id = setup_test_user(&pool).await;
+
+        let mut conn = pool.get().await.unwrap();
+        diesel::update(room_classes::table.find(class_id))
+            .set(room_classes::turnover_hours.eq(Some(4)))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let user = SessionUser {
+            id: user_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let start = Utc::now() + Duration::days(30);
+        let end = start + Duration::days(2);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let id = booking_id(&body);
+
+        // The room is being cleaned for four hours after check-out
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": end + Duration::hours(1),
+                "end": end + Duration::days(1),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        // A stay that fits but leaves no time to clean before the next one is refused
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": start - Duration::days(1),
+                "end": start - Duration::hours(1),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let body: Value = test::read_body_json(resp).await;
+        let conflicts = body["TurnoverUnavailable"]["conflicts"].as_array().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0]["id"], json!(id));
+
+        // Cancelling the stay drops its turnover as well
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/cancel", id))
+            .cookie(generate_auth_cookie(&token_engine, user.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, user).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": end + Duration::hours(1),
+                "end": end + Duration::days(1),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+}
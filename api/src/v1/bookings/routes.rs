@@ -0,0 +1,235 @@
+use actix_web::{HttpResponse, web};
+use app::AppSettings;
+use infra::db::DbPool;
+use std::rc::Rc;
+use uuid::Uuid;
+
+use crate::auth::SessionUser;
+use crate::guard::{Authorized, ManageBookings};
+use crate::v1::bookings::dtos::*;
+use app::bookings::amend::*;
+use app::bookings::cancel::*;
+use app::bookings::create::*;
+use app::bookings::lifecycle::BookingAction;
+use app::bookings::move_room::*;
+use app::bookings::transition::*;
+use infra::domains::booking;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings",
+    request_body = CreateBookingRequest,
+    responses(
+        (status = 201, description = "Booking created", body = CreateBookingSuccess),
+        (status = 400, description = "Invalid date range or guest count"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Room or guest not found"),
+        (status = 409, description = "Room is not available or cannot be turned over after the stay"),
+        (status = 422, description = "The stay breaks a stay rule, or the promo code can't be used for it")
+    )
+)]
+pub async fn create_booking(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    settings: web::Data<AppSettings>,
+    web::Json(req): web::Json<CreateBookingRequest>,
+) -> Result<HttpResponse, CreateBookingError> {
+    booking::create(&pool, req, &user, &settings.bookings, &settings.pricing)
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/{id}/cancel",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    responses(
+        (status = 200, description = "Booking cancelled", body = CancelBookingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking not found"),
+        (status = 409, description = "Illegal transition for the booking's status")
+    )
+)]
+pub async fn cancel_booking(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, CancelBookingError> {
+    let options = CancelBookingOptions {
+        booking_id: path.into_inner(),
+    };
+
+    booking::cancel(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/bookings/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    request_body = AmendBookingBody,
+    responses(
+        (status = 200, description = "Booking dates amended", body = AmendBookingSuccess),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking not found"),
+        (status = 409, description = "Booking is cancelled, or the room is not available or cannot be turned over"),
+        (status = 422, description = "The stay breaks a stay rule, or the promo code can't be used for it")
+    )
+)]
+pub async fn amend_booking(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    settings: web::Data<AppSettings>,
+    path: web::Path<Uuid>,
+    web::Json(body): web::Json<AmendBookingBody>,
+) -> Result<HttpResponse, AmendBookingError> {
+    let options = AmendBookingOptions {
+        booking_id: path.into_inner(),
+        start: body.start,
+        end: body.end,
+    };
+
+    booking::amend(&pool, options, &user, &settings.pricing)
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/{id}/move",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    request_body = MoveBookingBody,
+    responses(
+        (status = 200, description = "Booking moved", body = MoveBookingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking or room not found"),
+        (status = 409, description = "Booking is cancelled, or the room is not available or cannot be turned over"),
+        (status = 422, description = "The stay breaks a stay rule of the room class, or the promo code can't be used for it")
+    )
+)]
+pub async fn move_booking(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageBookings>,
+    settings: web::Data<AppSettings>,
+    path: web::Path<Uuid>,
+    web::Json(body): web::Json<MoveBookingBody>,
+) -> Result<HttpResponse, MoveBookingError> {
+    let options = MoveBookingOptions {
+        booking_id: path.into_inner(),
+        room_id: body.room_id,
+    };
+
+    booking::move_room(&pool, options, &user, &settings.pricing)
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/{id}/confirm",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    responses(
+        (status = 200, description = "Booking confirmed", body = TransitionBookingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking not found"),
+        (status = 409, description = "Illegal transition for the booking's status")
+    )
+)]
+pub async fn confirm_booking(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageBookings>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, TransitionBookingError> {
+    let options = TransitionBookingOptions {
+        booking_id: path.into_inner(),
+        action: BookingAction::Confirm,
+    };
+
+    booking::transition(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/{id}/check-in",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    responses(
+        (status = 200, description = "Guest checked in", body = TransitionBookingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking not found"),
+        (status = 409, description = "Illegal transition for the booking's status, or the stay has not started")
+    )
+)]
+pub async fn check_in_booking(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageBookings>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, TransitionBookingError> {
+    let options = TransitionBookingOptions {
+        booking_id: path.into_inner(),
+        action: BookingAction::CheckIn,
+    };
+
+    booking::transition(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/{id}/check-out",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    responses(
+        (status = 200, description = "Guest checked out", body = TransitionBookingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking not found"),
+        (status = 409, description = "Illegal transition for the booking's status")
+    )
+)]
+pub async fn check_out_booking(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageBookings>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, TransitionBookingError> {
+    let options = TransitionBookingOptions {
+        booking_id: path.into_inner(),
+        action: BookingAction::CheckOut,
+    };
+
+    booking::transition(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/{id}/no-show",
+    params(
+        ("id" = Uuid, Path, description = "Booking ID")
+    ),
+    responses(
+        (status = 200, description = "Booking marked as no-show", body = TransitionBookingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Booking not found"),
+        (status = 409, description = "Illegal transition for the booking's status")
+    )
+)]
+pub async fn mark_no_show(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageBookings>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, TransitionBookingError> {
+    let options = TransitionBookingOptions {
+        booking_id: path.into_inner(),
+        action: BookingAction::NoShow,
+    };
+
+    booking::transition(&pool, options, &user).await.into()
+}
@@ -0,0 +1,15 @@
+use app::housekeeping::HousekeepingStatus;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateHousekeepingBody {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub status: Option<HousekeepingStatus>,
+    pub instructions: Option<String>,
+    pub assignee_id: Option<Uuid>,
+}
@@ -0,0 +1,138 @@
+use actix_web::web;
+
+pub mod dtos;
+pub mod routes;
+
+use routes::{create_housekeeping, delete_housekeeping, get_housekeeping, update_housekeeping};
+
+use crate::auth::AuthMiddleware;
+
+pub fn configure_housekeeping_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/housekeeping")
+            .route("", web::post().to(create_housekeeping).wrap(AuthMiddleware))
+            .route(
+                "/{id}",
+                web::get().to(get_housekeeping).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}",
+                web::patch().to(update_housekeeping).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}",
+                web::delete().to(delete_housekeeping).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{get_test_config, get_test_pool, setup_test_room, setup_test_user};
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    async fn test_housekeeping_crud() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let user_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_housekeeping_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(Uuid::new_v4()),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            staff_id: None,
+            permissions: Vec::new(),
+            ..staff.clone()
+        };
+
+        let start = Utc::now() + Duration::days(30);
+        let end = start + Duration::hours(3);
+        let payload = json!({
+            "roomId": room_id,
+            "start": start,
+            "end": end,
+            "instructions": "Deep clean",
+        });
+
+        let req = test::TestRequest::post()
+            .uri("/housekeeping")
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/housekeeping")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let id = body["housekeeping"]["id"].as_str().unwrap().to_string();
+        assert_eq!(body["housekeeping"]["status"], "PENDING");
+
+        // Housekeeping competes for the same timeline as every other block
+        let req = test::TestRequest::post()
+            .uri("/housekeeping")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/housekeeping/{}", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "status": "IN_PROGRESS" }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["housekeeping"]["status"], "IN_PROGRESS");
+        assert_eq!(body["housekeeping"]["instructions"], "Deep clean");
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/housekeeping/{}", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "start": end }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/housekeeping/{}", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/housekeeping/{}", id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
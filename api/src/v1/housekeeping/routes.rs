@@ -0,0 +1,112 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManageHousekeeping};
+use crate::v1::housekeeping::dtos::*;
+use app::housekeeping::create::*;
+use app::housekeeping::delete::*;
+use app::housekeeping::get::*;
+use app::housekeeping::update::*;
+use infra::domains::housekeeping;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/housekeeping",
+    request_body = CreateHousekeepingRequest,
+    responses(
+        (status = 201, description = "Housekeeping task scheduled", body = CreateHousekeepingSuccess),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Room or assignee not found"),
+        (status = 409, description = "Room is not available for the requested period")
+    )
+)]
+pub async fn create_housekeeping(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageHousekeeping>,
+    web::Json(req): web::Json<CreateHousekeepingRequest>,
+) -> Result<HttpResponse, CreateHousekeepingError> {
+    housekeeping::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/housekeeping/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Housekeeping task ID")
+    ),
+    responses(
+        (status = 200, description = "Housekeeping task details", body = GetHousekeepingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Housekeeping task not found")
+    )
+)]
+pub async fn get_housekeeping(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageHousekeeping>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, GetHousekeepingError> {
+    let options = GetHousekeepingOptions {
+        task_id: path.into_inner(),
+    };
+
+    housekeeping::get(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/housekeeping/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Housekeeping task ID")
+    ),
+    request_body = UpdateHousekeepingBody,
+    responses(
+        (status = 200, description = "Housekeeping task updated", body = UpdateHousekeepingSuccess),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Housekeeping task or assignee not found"),
+        (status = 409, description = "Room is not available for the requested period")
+    )
+)]
+pub async fn update_housekeeping(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageHousekeeping>,
+    path: web::Path<Uuid>,
+    web::Json(body): web::Json<UpdateHousekeepingBody>,
+) -> Result<HttpResponse, UpdateHousekeepingError> {
+    let options = UpdateHousekeepingOptions {
+        task_id: path.into_inner(),
+        start: body.start,
+        end: body.end,
+        status: body.status,
+        instructions: body.instructions,
+        assignee_id: body.assignee_id,
+    };
+
+    housekeeping::update(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/housekeeping/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Housekeeping task ID")
+    ),
+    responses(
+        (status = 200, description = "Housekeeping task removed", body = DeleteHousekeepingSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Housekeeping task not found")
+    )
+)]
+pub async fn delete_housekeeping(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageHousekeeping>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DeleteHousekeepingError> {
+    let options = DeleteHousekeepingOptions {
+        task_id: path.into_inner(),
+    };
+
+    housekeeping::delete(&pool, options, &user).await.into()
+}
@@ -0,0 +1,27 @@
+use app::maintenance::{MaintenanceKind, MaintenanceSeverity};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkOrderListQuery {
+    pub room_id: Option<Uuid>,
+    pub kind: Option<MaintenanceKind>,
+    pub severity: Option<MaintenanceSeverity>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReassignWorkOrderBody {
+    pub assigner_id: Uuid,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendWorkOrderBody {
+    pub end: DateTime<Utc>,
+}
@@ -0,0 +1,199 @@
+use actix_web::web;
+
+pub mod dtos;
+pub mod routes;
+
+use routes::{
+    close_work_order, create_work_order, extend_work_order, list_work_orders, reassign_work_order,
+};
+
+use crate::auth::AuthMiddleware;
+
+pub fn configure_maintenance_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/maintenance")
+            .route("", web::post().to(create_work_order).wrap(AuthMiddleware))
+            .route("", web::get().to(list_work_orders).wrap(AuthMiddleware))
+            .route(
+                "/{id}/reassign",
+                web::post().to(reassign_work_order).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/extend",
+                web::post().to(extend_work_order).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/close",
+                web::post().to(close_work_order).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{
+        get_test_config, get_test_pool, setup_test_room, setup_test_staff, setup_test_user,
+    };
+    use crate::v1::bookings::configure_bookings_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    async fn test_work_order_lifecycle() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let guest_id = setup_test_user(&pool).await;
+        let (staff_user_id, staff_id) = setup_test_staff(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes)
+                .configure(configure_maintenance_routes),
+        )
+        .await;
+
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let staff = SessionUser {
+            id: staff_user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+
+        let start = Utc::now() + Duration::days(30);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": start + Duration::days(2),
+                "end": start + Duration::days(4),
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let payload = json!({
+            "roomId": room_id,
+            "start": start,
+            "end": start + Duration::days(1),
+            "kind": "PLUMBING",
+            "severity": "HIGH",
+        });
+
+        let req = test::TestRequest::post()
+            .uri("/maintenance")
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/maintenance")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let id = body["workOrder"]["id"].as_str().unwrap().to_string();
+
+        // Maintenance can never run over a live booking
+        let req = test::TestRequest::post()
+            .uri(&format!("/maintenance/{}/extend", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "end": start + Duration::days(3) }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/maintenance/{}/extend", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "end": start + Duration::days(2) }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/maintenance?roomId={}&kind=PLUMBING", room_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["total"], 1);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/maintenance?roomId={}&kind=HVAC", room_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["total"], 0);
+
+        // Out of range paging is clamped rather than sent to the database
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/maintenance?roomId={}&page={}&perPage=-5",
+                room_id,
+                i64::MIN
+            ))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["workOrders"].as_array().unwrap().len(), 1);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/maintenance/{}/reassign", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "assignerId": Uuid::new_v4() }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/maintenance/{}/reassign", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "assignerId": staff_id }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["workOrder"]["assignerId"], json!(staff_id));
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/maintenance/{}/close", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/maintenance/{}/close", id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/maintenance?roomId={}", room_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["total"], 0);
+    }
+}
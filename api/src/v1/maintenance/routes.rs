@@ -0,0 +1,141 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManageMaintenance};
+use crate::v1::maintenance::dtos::*;
+use app::maintenance::close::*;
+use app::maintenance::create::*;
+use app::maintenance::extend::*;
+use app::maintenance::list::*;
+use app::maintenance::reassign::*;
+use infra::domains::maintenance;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance",
+    request_body = CreateWorkOrderRequest,
+    responses(
+        (status = 201, description = "Work order opened", body = CreateWorkOrderSuccess),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Room or staff member not found"),
+        (status = 409, description = "Room is not available for the requested period")
+    )
+)]
+pub async fn create_work_order(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageMaintenance>,
+    web::Json(req): web::Json<CreateWorkOrderRequest>,
+) -> Result<HttpResponse, CreateWorkOrderError> {
+    maintenance::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/maintenance",
+    params(
+        WorkOrderListQuery
+    ),
+    responses(
+        (status = 200, description = "Open work orders", body = ListWorkOrdersSuccess),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_work_orders(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageMaintenance>,
+    web::Query(query): web::Query<WorkOrderListQuery>,
+) -> Result<HttpResponse, ListWorkOrdersError> {
+    let options = ListWorkOrdersOptions {
+        room_id: query.room_id,
+        kind: query.kind,
+        severity: query.severity,
+        page: query.page.unwrap_or(1),
+        per_page: query.per_page.unwrap_or(10),
+    };
+
+    maintenance::list(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/{id}/reassign",
+    params(
+        ("id" = Uuid, Path, description = "Work order ID")
+    ),
+    request_body = ReassignWorkOrderBody,
+    responses(
+        (status = 200, description = "Work order reassigned", body = ReassignWorkOrderSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Work order or staff member not found"),
+        (status = 409, description = "Work order is already closed")
+    )
+)]
+pub async fn reassign_work_order(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageMaintenance>,
+    path: web::Path<Uuid>,
+    web::Json(body): web::Json<ReassignWorkOrderBody>,
+) -> Result<HttpResponse, ReassignWorkOrderError> {
+    let options = ReassignWorkOrderOptions {
+        work_order_id: path.into_inner(),
+        assigner_id: body.assigner_id,
+    };
+
+    maintenance::reassign(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/{id}/extend",
+    params(
+        ("id" = Uuid, Path, description = "Work order ID")
+    ),
+    request_body = ExtendWorkOrderBody,
+    responses(
+        (status = 200, description = "Work order extended", body = ExtendWorkOrderSuccess),
+        (status = 400, description = "New end is not after the current end"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Work order not found"),
+        (status = 409, description = "Work order is closed or the room is not available")
+    )
+)]
+pub async fn extend_work_order(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageMaintenance>,
+    path: web::Path<Uuid>,
+    web::Json(body): web::Json<ExtendWorkOrderBody>,
+) -> Result<HttpResponse, ExtendWorkOrderError> {
+    let options = ExtendWorkOrderOptions {
+        work_order_id: path.into_inner(),
+        end: body.end,
+    };
+
+    maintenance::extend(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/{id}/close",
+    params(
+        ("id" = Uuid, Path, description = "Work order ID")
+    ),
+    responses(
+        (status = 200, description = "Work order closed", body = CloseWorkOrderSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Work order not found"),
+        (status = 409, description = "Work order is already closed")
+    )
+)]
+pub async fn close_work_order(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageMaintenance>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, CloseWorkOrderError> {
+    let options = CloseWorkOrderOptions {
+        work_order_id: path.into_inner(),
+    };
+
+    maintenance::close(&pool, options, &user).await.into()
+}
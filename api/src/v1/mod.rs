@@ -1,18 +1,44 @@
 use actix_web::web;
 
+pub mod api_keys;
 pub mod auth;
+pub mod bookings;
+pub mod housekeeping;
+pub mod maintenance;
+pub mod pricing;
+pub mod promo_codes;
+pub mod quotes;
+pub mod rate_plans;
+pub mod reports;
 pub mod rooms;
+pub mod stay_rules;
 pub mod users;
 
 use crate::v1::{
-    auth::configure_auth_routes, rooms::configure_rooms_routes, users::configure_users_routes,
+    api_keys::configure_api_keys_routes, auth::configure_auth_routes,
+    bookings::configure_bookings_routes, housekeeping::configure_housekeeping_routes,
+    maintenance::configure_maintenance_routes, pricing::configure_pricing_routes,
+    promo_codes::configure_promo_codes_routes, quotes::configure_quotes_routes,
+    rate_plans::configure_rate_plans_routes, reports::configure_reports_routes,
+    rooms::configure_rooms_routes, stay_rules::configure_stay_rules_routes,
+    users::configure_users_routes,
 };
 
 pub fn configure_v1_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/v1")
+            .configure(configure_api_keys_routes)
             .configure(configure_auth_routes)
+            .configure(configure_bookings_routes)
+            .configure(configure_housekeeping_routes)
+            .configure(configure_maintenance_routes)
+            .configure(configure_pricing_routes)
+            .configure(configure_promo_codes_routes)
+            .configure(configure_quotes_routes)
+            .configure(configure_rate_plans_routes)
+            .configure(configure_reports_routes)
             .configure(configure_rooms_routes)
+            .configure(configure_stay_rules_routes)
             .configure(configure_users_routes),
     );
 }
@@ -0,0 +1,249 @@
+use actix_web::web;
+
+pub mod routes;
+
+use crate::auth::AuthMiddleware;
+use routes::*;
+
+pub fn configure_pricing_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/pricing")
+            .route(
+                "/occupancy-bands/{class_id}",
+                web::get().to(list_occupancy_bands).wrap(AuthMiddleware),
+            )
+            .route(
+                "/occupancy-bands/{class_id}",
+                web::put().to(set_occupancy_bands).wrap(AuthMiddleware),
+            )
+            .route("/curve", web::get().to(price_curve).wrap(AuthMiddleware)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{
+        get_test_config, get_test_pool, setup_test_room, setup_test_staff, setup_test_user,
+    };
+    use crate::v1::bookings::configure_bookings_routes;
+    use crate::v1::promo_codes::configure_promo_codes_routes;
+    use crate::v1::quotes::configure_quotes_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use bigdecimal::BigDecimal;
+    use chrono::{Days, Utc};
+    use serde_json::{Value, json};
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn money(value: &Value) -> BigDecimal {
+        BigDecimal::from_str(value.as_str().unwrap()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_occupancy_bands_raise_prices() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+        let guest_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_pricing_routes)
+                .configure(configure_quotes_routes)
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let bands_uri = format!("/pricing/occupancy-bands/{}", class_id);
+
+        let req = test::TestRequest::put()
+            .uri(&bands_uri)
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({ "bands": [] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::put()
+            .uri(&bands_uri)
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "bands": [{ "minOccupancy": 0, "adjustment": "10" }] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let req = test::TestRequest::put()
+            .uri(&bands_uri)
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "bands": [
+                { "minOccupancy": 100, "adjustment": "25" },
+                { "minOccupancy": 50, "adjustment": "10" }
+            ] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["bands"][0]["minOccupancy"], 50);
+
+        // Sell the only room of the class for the night two days out
+        let check_in = Utc::now().date_naive() + Days::new(2);
+        let start = check_in.and_hms_opt(14, 0, 0).unwrap().and_utc();
+        let end = (check_in + Days::new(1))
+            .and_hms_opt(11, 0, 0)
+            .unwrap()
+            .and_utc();
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/pricing/curve?classId={}&days=0", class_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/pricing/curve?classId={}&days=4", class_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["rooms"], 1);
+        let points = body["points"].as_array().unwrap();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[1]["sold"], 0);
+        assert_eq!(money(&points[1]["price"]), BigDecimal::from(100));
+        assert_eq!(points[2]["sold"], 1);
+        assert_eq!(points[2]["band"]["minOccupancy"], 100);
+        assert_eq!(money(&points[2]["price"]), BigDecimal::from(125));
+        // The guest checks out on the morning of the fourth day
+        assert_eq!(points[3]["sold"], 0);
+
+        // Quotes move with the curve, night by night
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&start={}&end={}",
+                class_id,
+                (check_in - Days::new(1)).format("%Y-%m-%dT14:00:00Z"),
+                (check_in + Days::new(1)).format("%Y-%m-%dT11:00:00Z")
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(money(&body["subtotal"]), BigDecimal::from(225));
+        assert_eq!(body["nights"][0]["source"]["kind"], "base");
+        assert_eq!(body["nights"][1]["source"]["kind"], "occupancy");
+    }
+
+    #[actix_web::test]
+    async fn test_amend_does_not_count_its_own_stay() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+        let guest_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_pricing_routes)
+                .configure(configure_promo_codes_routes)
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/pricing/occupancy-bands/{}", class_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "bands": [{ "minOccupancy": 100, "adjustment": "25" }] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let code = format!("stay-{}", &Uuid::new_v4().simple().to_string()[..8]);
+        let req = test::TestRequest::post()
+            .uri("/promo-codes")
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .set_json(json!({
+                "code": code,
+                "campaign": "Long stays",
+                "percentOff": "10",
+                "classIds": [class_id]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": "2032-05-03T14:00:00Z",
+                "end": "2032-05-05T11:00:00Z",
+                "promoCode": code
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(money(&body["booking"]["discount"]), BigDecimal::from(20));
+        let booking_id = body["booking"]["id"].as_str().unwrap().to_string();
+
+        // The booking fills the only room, but mustn't raise the price of its own nights
+        let req = test::TestRequest::patch()
+            .uri(&format!("/bookings/{}", booking_id))
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(json!({ "start": "2032-05-03T14:00:00Z", "end": "2032-05-06T11:00:00Z" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(money(&body["booking"]["discount"]), BigDecimal::from(30));
+    }
+}
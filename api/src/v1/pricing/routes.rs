@@ -0,0 +1,83 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManageRates};
+use app::api::ValidationErrors;
+use app::pricing::curve::*;
+use app::pricing::occupancy::*;
+use infra::domains::occupancy;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/pricing/occupancy-bands/{class_id}",
+    params(
+        ("class_id" = Uuid, Path, description = "Room class ID")
+    ),
+    responses(
+        (status = 200, description = "Occupancy bands of the room class", body = OccupancyBands),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Room class not found")
+    )
+)]
+pub async fn list_occupancy_bands(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ListOccupancyBandsError> {
+    let options = ListOccupancyBandsOptions {
+        class_id: path.into_inner(),
+    };
+
+    occupancy::list_bands(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/pricing/occupancy-bands/{class_id}",
+    params(
+        ("class_id" = Uuid, Path, description = "Room class ID")
+    ),
+    request_body = SetOccupancyBandsRequest,
+    responses(
+        (status = 200, description = "Occupancy bands replaced", body = OccupancyBands),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Room class not found"),
+        (status = 422, description = "Invalid thresholds or adjustments", body = ValidationErrors)
+    )
+)]
+pub async fn set_occupancy_bands(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<Uuid>,
+    web::Json(req): web::Json<SetOccupancyBandsRequest>,
+) -> Result<HttpResponse, SetOccupancyBandsError> {
+    let options = SetOccupancyBandsOptions {
+        class_id: path.into_inner(),
+    };
+
+    occupancy::set_bands(&pool, options, req, &user)
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/pricing/curve",
+    params(
+        PriceCurveOptions
+    ),
+    responses(
+        (status = 200, description = "Base price of the room class for each night from tonight", body = PriceCurve),
+        (status = 400, description = "Invalid number of days"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Room class not found")
+    )
+)]
+pub async fn price_curve(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    web::Query(options): web::Query<PriceCurveOptions>,
+) -> Result<HttpResponse, PriceCurveError> {
+    occupancy::price_curve(&pool, options, &user).await.into()
+}
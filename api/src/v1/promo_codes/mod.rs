@@ -0,0 +1,251 @@
+use actix_web::web;
+
+pub mod routes;
+
+use crate::auth::AuthMiddleware;
+use routes::*;
+
+pub fn configure_promo_codes_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/promo-codes")
+            .route("", web::post().to(create_promo_code).wrap(AuthMiddleware))
+            .route("", web::get().to(list_promo_codes).wrap(AuthMiddleware))
+            .route(
+                "/{id}/disable",
+                web::post().to(disable_promo_code).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{
+        get_test_config, get_test_pool, setup_test_room, setup_test_staff, setup_test_user,
+    };
+    use crate::v1::bookings::configure_bookings_routes;
+    use crate::v1::quotes::configure_quotes_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use bigdecimal::BigDecimal;
+    use serde_json::{Value, json};
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn money(value: &Value) -> BigDecimal {
+        BigDecimal::from_str(value.as_str().unwrap()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_promo_code_quote_and_booking() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+        let guest_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_promo_codes_routes)
+                .configure(configure_quotes_routes)
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        // Codes are unique across runs of the suite
+        let code = format!("spring-{}", &Uuid::new_v4().simple().to_string()[..8]);
+        let promo = json!({
+            "code": code,
+            "campaign": "Spring sale",
+            "percentOff": "20",
+            "maxUsesPerGuest": 1,
+            "minNights": 2,
+            "classIds": [class_id]
+        });
+
+        let req = test::TestRequest::post()
+            .uri("/promo-codes")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(&promo)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/promo-codes")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({
+                "code": "BOTH",
+                "campaign": "Broken",
+                "percentOff": "10",
+                "amountOff": "10.00"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let req = test::TestRequest::post()
+            .uri("/promo-codes")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&promo)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let promo_id = body["id"].as_str().unwrap().to_string();
+        assert_eq!(body["code"], code.to_uppercase());
+
+        // Codes are matched whatever the case they are typed in
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&start=2031-04-07T14:00:00Z&end=2031-04-09T11:00:00Z&promoCode={}",
+                class_id, code
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(money(&body["subtotal"]), BigDecimal::from(200));
+        assert_eq!(money(&body["discount"]["amount"]), BigDecimal::from(40));
+        assert_eq!(body["discount"]["campaign"], "Spring sale");
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&start=2031-04-07T14:00:00Z&end=2031-04-08T11:00:00Z&promoCode={}",
+                class_id, code
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["PromoRejected"]["rejection"]["kind"], "minNights");
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": "2031-04-07T14:00:00Z",
+                "end": "2031-04-09T11:00:00Z",
+                "guests": 2,
+                "promoCode": code
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["booking"]["promoCodeId"], promo_id.as_str());
+        assert_eq!(money(&body["booking"]["discount"]), BigDecimal::from(40));
+        assert_eq!(body["booking"]["guests"], 2);
+        let booking_id = body["booking"]["id"].as_str().unwrap().to_string();
+
+        // New dates are held to the code's conditions and discounted again
+        let amend = |start: &str, end: &str| {
+            test::TestRequest::patch()
+                .uri(&format!("/bookings/{}", booking_id))
+                .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+                .set_json(json!({ "start": start, "end": end }))
+                .to_request()
+        };
+
+        let resp =
+            test::call_service(&app, amend("2031-04-07T14:00:00Z", "2031-04-08T11:00:00Z")).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["PromoRejected"]["rejection"]["kind"], "minNights");
+
+        let resp =
+            test::call_service(&app, amend("2031-04-07T14:00:00Z", "2031-04-10T11:00:00Z")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(money(&body["booking"]["discount"]), BigDecimal::from(60));
+        assert_eq!(body["booking"]["guests"], 2);
+
+        // A room of another class is held to the code's classes too
+        let (other_room_id, other_class_id) = setup_test_room(&pool).await;
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/move", booking_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "roomId": other_room_id }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["PromoRejected"]["rejection"]["kind"],
+            "classNotEligible"
+        );
+        assert_eq!(
+            body["PromoRejected"]["rejection"]["classId"],
+            other_class_id.to_string()
+        );
+
+        // One use per guest
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": "2031-04-20T14:00:00Z",
+                "end": "2031-04-23T11:00:00Z",
+                "promoCode": code
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["PromoRejected"]["rejection"]["kind"],
+            "guestLimitReached"
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/promo-codes")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        let listed = body["promoCodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|listed| listed["id"] == promo_id.as_str())
+            .unwrap();
+        assert_eq!(listed["uses"], 1);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/promo-codes/{}/disable", promo_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&start=2031-04-07T14:00:00Z&end=2031-04-09T11:00:00Z&promoCode={}",
+                class_id, code
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["PromoRejected"]["rejection"]["kind"], "disabled");
+    }
+}
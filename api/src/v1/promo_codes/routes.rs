@@ -0,0 +1,68 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManagePromotions};
+use app::api::ValidationErrors;
+use app::promotions::PromoCodeDetails;
+use app::promotions::create::*;
+use app::promotions::disable::*;
+use app::promotions::list::*;
+use infra::domains::promotion;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/promo-codes",
+    request_body = CreatePromoCodeRequest,
+    responses(
+        (status = 201, description = "Promo code created", body = PromoCodeDetails),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid code, discount, window or limits", body = ValidationErrors)
+    )
+)]
+pub async fn create_promo_code(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManagePromotions>,
+    web::Json(req): web::Json<CreatePromoCodeRequest>,
+) -> Result<HttpResponse, CreatePromoCodeError> {
+    promotion::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/promo-codes",
+    responses(
+        (status = 200, description = "Promo codes with their uses, newest first", body = ListPromoCodesSuccess),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn list_promo_codes(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManagePromotions>,
+) -> Result<HttpResponse, ListPromoCodesError> {
+    promotion::list(&pool, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/promo-codes/{id}/disable",
+    params(
+        ("id" = Uuid, Path, description = "Promo code ID")
+    ),
+    responses(
+        (status = 200, description = "Promo code disabled", body = PromoCodeDetails),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Promo code not found")
+    )
+)]
+pub async fn disable_promo_code(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManagePromotions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DisablePromoCodeError> {
+    let options = DisablePromoCodeOptions {
+        id: path.into_inner(),
+    };
+
+    promotion::disable(&pool, options, &user).await.into()
+}
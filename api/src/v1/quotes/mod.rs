@@ -0,0 +1,164 @@
+use actix_web::web;
+
+pub mod routes;
+
+use routes::*;
+
+pub fn configure_quotes_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/quotes").route("", web::get().to(get_quote)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{get_test_config, get_test_pool, setup_test_room};
+    use crate::v1::rooms::configure_rooms_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::settings::TaxSettings;
+    use bigdecimal::BigDecimal;
+    use diesel_async::RunQueryDsl;
+    use infra::models::NewRatePlan;
+    use infra::schema::rate_plans;
+    use serde_json::Value;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn money(value: &Value) -> BigDecimal {
+        BigDecimal::from_str(value.as_str().unwrap()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_quote_stay() {
+        let mut config = get_test_config();
+        config.pricing.taxes = vec![
+            TaxSettings::Percentage {
+                name: "VAT".to_string(),
+                rate: BigDecimal::from(10),
+            },
+            TaxSettings::PerNight {
+                name: "City tax".to_string(),
+                amount: BigDecimal::from(2),
+                per_guest: true,
+            },
+        ];
+        let pool = get_test_pool(&config).await;
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_quotes_routes)
+                .configure(configure_rooms_routes),
+        )
+        .await;
+
+        let stay = "start=2031-03-03T14:00:00Z&end=2031-03-05T11:00:00Z&guests=2";
+
+        // Without rate plans the class base price of 100 applies
+        let req = test::TestRequest::get()
+            .uri(&format!("/quotes?classId={}&{}", class_id, stay))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["ratePlanId"].is_null());
+        assert_eq!(body["nights"].as_array().unwrap().len(), 2);
+        assert_eq!(money(&body["subtotal"]), BigDecimal::from(200));
+        assert_eq!(money(&body["taxes"][0]["amount"]), BigDecimal::from(20));
+        assert_eq!(money(&body["taxes"][1]["amount"]), BigDecimal::from(8));
+        assert_eq!(money(&body["total"]), BigDecimal::from(228));
+
+        let mut conn = pool.get().await.unwrap();
+        let flexible: Uuid = diesel::insert_into(rate_plans::table)
+            .values(&NewRatePlan {
+                class_id,
+                name: "Flexible",
+                base_price: None,
+                refundable: true,
+                includes_breakfast: false,
+            })
+            .returning(rate_plans::id)
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        let saver: Uuid = diesel::insert_into(rate_plans::table)
+            .values(&NewRatePlan {
+                class_id,
+                name: "Saver",
+                base_price: Some(BigDecimal::from(90)),
+                refundable: false,
+                includes_breakfast: false,
+            })
+            .returning(rate_plans::id)
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+
+        // The cheapest plan on sale is quoted unless one is asked for
+        let req = test::TestRequest::get()
+            .uri(&format!("/quotes?classId={}&{}", class_id, stay))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["ratePlanId"], saver.to_string());
+        assert_eq!(money(&body["total"]), BigDecimal::from(206));
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&ratePlanId={}&{}",
+                class_id, flexible, stay
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["ratePlanId"], flexible.to_string());
+        assert_eq!(money(&body["total"]), BigDecimal::from(228));
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&ratePlanId={}&{}",
+                class_id,
+                Uuid::new_v4(),
+                stay
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&start=2031-03-03T14:00:00Z&end=2031-03-03T18:00:00Z",
+                class_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/quotes?classId={}&start=2031-03-03T14:00:00Z&end=2031-03-05T11:00:00Z&guests=0",
+                class_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // Search results carry the same amount
+        let req = test::TestRequest::get()
+            .uri(&format!("/rooms/find?classId={}&{}", class_id, stay))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let room = body["rooms"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|room| room["id"] == room_id.to_string())
+            .unwrap();
+        assert_eq!(room["ratePlanId"], saver.to_string());
+        assert_eq!(money(&room["quotedTotal"]), BigDecimal::from(206));
+    }
+}
@@ -0,0 +1,27 @@
+use actix_web::{HttpResponse, web};
+use app::AppSettings;
+use infra::db::DbPool;
+
+use app::pricing::quote::*;
+use infra::domains::quote;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quotes",
+    params(
+        QuoteOptions
+    ),
+    responses(
+        (status = 200, description = "Nightly breakdown, taxes and total for the stay", body = Quote),
+        (status = 400, description = "Invalid date range or guest count"),
+        (status = 404, description = "Room class or rate plan not found"),
+        (status = 422, description = "The promo code can't be used for the stay")
+    )
+)]
+pub async fn get_quote(
+    pool: web::Data<DbPool>,
+    settings: web::Data<AppSettings>,
+    web::Query(options): web::Query<QuoteOptions>,
+) -> Result<HttpResponse, QuoteError> {
+    quote::get(&pool, options, &settings.pricing).await.into()
+}
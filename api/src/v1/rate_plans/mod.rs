@@ -0,0 +1,202 @@
+use actix_web::web;
+
+pub mod routes;
+
+use crate::auth::AuthMiddleware;
+use routes::*;
+
+pub fn configure_rate_plans_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/rate-plans")
+            .route("", web::get().to(list_rate_plans))
+            .route("", web::post().to(create_rate_plan).wrap(AuthMiddleware))
+            .route(
+                "/{id}/archive",
+                web::post().to(archive_rate_plan).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/seasons",
+                web::post().to(add_season).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/seasons/{season_id}",
+                web::delete().to(remove_season).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/weekdays",
+                web::put().to(set_weekday_rules).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{get_test_config, get_test_pool, setup_test_room, setup_test_staff};
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use serde_json::{Value, json};
+
+    #[actix_web::test]
+    async fn test_rate_plan_lifecycle() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (_, class_id) = setup_test_room(&pool).await;
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_rate_plans_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            staff_id: None,
+            permissions: Vec::new(),
+            ..staff.clone()
+        };
+        let plan = json!({ "classId": class_id, "name": "Flexible", "basePrice": "120.00" });
+
+        let req = test::TestRequest::post()
+            .uri("/rate-plans")
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(&plan)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/rate-plans")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&plan)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let plan_id = body["id"].as_str().unwrap().to_string();
+        assert_eq!(body["refundable"], true);
+
+        let req = test::TestRequest::post()
+            .uri("/rate-plans")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&plan)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["errors"]["name"].is_array());
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/rate-plans/{}/seasons", plan_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({
+                "name": "Summer",
+                "startsOn": "2031-06-01",
+                "endsOn": "2031-08-31",
+                "nightlyPrice": "180.00"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let season_id = body["id"].as_str().unwrap().to_string();
+
+        // Seasons of one plan may not overlap, even by a single night
+        let req = test::TestRequest::post()
+            .uri(&format!("/rate-plans/{}/seasons", plan_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({
+                "name": "Festival",
+                "startsOn": "2031-08-31",
+                "endsOn": "2031-09-07",
+                "nightlyPrice": "220.00"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["errors"]["startsOn"].is_array());
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/rate-plans/{}/weekdays", plan_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "rules": [
+                { "weekday": "friday", "nightlyPrice": "140.00" },
+                { "weekday": "friday", "nightlyPrice": "150.00" }
+            ] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/rate-plans/{}/weekdays", plan_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "rules": [
+                { "weekday": "saturday", "nightlyPrice": "150.00" },
+                { "weekday": "friday", "nightlyPrice": "140.00" }
+            ] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["weekdayRules"][0]["weekday"], "friday");
+        assert_eq!(body["weekdayRules"][1]["weekday"], "saturday");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/rate-plans?classId={}", class_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let listed = body["ratePlans"].as_array().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0]["seasons"][0]["id"], season_id.as_str());
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/rate-plans/{}/seasons/{}", plan_id, season_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/rate-plans/{}/archive", plan_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["archivedAt"].is_string());
+        assert!(body["seasons"].as_array().unwrap().is_empty());
+
+        // Archived plans drop out of the default listing
+        let req = test::TestRequest::get()
+            .uri(&format!("/rate-plans?classId={}", class_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["ratePlans"].as_array().unwrap().is_empty());
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/rate-plans?classId={}&includeArchived=true",
+                class_id
+            ))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["ratePlans"].as_array().unwrap().len(), 1);
+    }
+}
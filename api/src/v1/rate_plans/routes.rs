@@ -0,0 +1,152 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManageRates};
+use app::api::ValidationErrors;
+use app::pricing::Season;
+use app::pricing::plans::*;
+use app::pricing::seasons::*;
+use app::pricing::weekdays::*;
+use infra::domains::rate_plan;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/rate-plans",
+    params(
+        ListRatePlansOptions
+    ),
+    responses(
+        (status = 200, description = "Rate plans with their seasons and weekday rules", body = ListRatePlansSuccess)
+    )
+)]
+pub async fn list_rate_plans(
+    pool: web::Data<DbPool>,
+    web::Query(options): web::Query<ListRatePlansOptions>,
+) -> Result<HttpResponse, ListRatePlansError> {
+    rate_plan::list(&pool, options).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/rate-plans",
+    request_body = CreateRatePlanRequest,
+    responses(
+        (status = 201, description = "Rate plan created", body = RatePlanDetails),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid room class, name or price", body = ValidationErrors)
+    )
+)]
+pub async fn create_rate_plan(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    web::Json(req): web::Json<CreateRatePlanRequest>,
+) -> Result<HttpResponse, CreateRatePlanError> {
+    rate_plan::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/rate-plans/{id}/archive",
+    params(
+        ("id" = Uuid, Path, description = "Rate plan ID")
+    ),
+    responses(
+        (status = 200, description = "Rate plan archived", body = RatePlanDetails),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Rate plan not found")
+    )
+)]
+pub async fn archive_rate_plan(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ArchiveRatePlanError> {
+    let options = ArchiveRatePlanOptions {
+        plan_id: path.into_inner(),
+    };
+
+    rate_plan::archive(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/rate-plans/{id}/seasons",
+    params(
+        ("id" = Uuid, Path, description = "Rate plan ID")
+    ),
+    request_body = AddSeasonRequest,
+    responses(
+        (status = 201, description = "Season added", body = Season),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Rate plan not found"),
+        (status = 422, description = "Invalid dates or price, or the season overlaps another", body = ValidationErrors)
+    )
+)]
+pub async fn add_season(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<Uuid>,
+    web::Json(req): web::Json<AddSeasonRequest>,
+) -> Result<HttpResponse, AddSeasonError> {
+    let options = AddSeasonOptions {
+        plan_id: path.into_inner(),
+    };
+
+    rate_plan::add_season(&pool, options, req, &user)
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/rate-plans/{id}/seasons/{season_id}",
+    params(
+        ("id" = Uuid, Path, description = "Rate plan ID"),
+        ("season_id" = Uuid, Path, description = "Season ID")
+    ),
+    responses(
+        (status = 204, description = "Season removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Season not found")
+    )
+)]
+pub async fn remove_season(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, RemoveSeasonError> {
+    let (plan_id, season_id) = path.into_inner();
+    let options = RemoveSeasonOptions { plan_id, season_id };
+
+    rate_plan::remove_season(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/rate-plans/{id}/weekdays",
+    params(
+        ("id" = Uuid, Path, description = "Rate plan ID")
+    ),
+    request_body = SetWeekdayRulesRequest,
+    responses(
+        (status = 200, description = "Weekday rules replaced", body = RatePlanDetails),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Rate plan not found"),
+        (status = 422, description = "Repeated weekday or invalid price", body = ValidationErrors)
+    )
+)]
+pub async fn set_weekday_rules(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<Uuid>,
+    web::Json(req): web::Json<SetWeekdayRulesRequest>,
+) -> Result<HttpResponse, SetWeekdayRulesError> {
+    let options = SetWeekdayRulesOptions {
+        plan_id: path.into_inner(),
+    };
+
+    rate_plan::set_weekday_rules(&pool, options, req, &user)
+        .await
+        .into()
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportListQuery {
+    pub block_id: Uuid,
+    /// Staff only: also return soft-deleted reports
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateReportBody {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
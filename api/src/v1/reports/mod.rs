@@ -0,0 +1,185 @@
+use actix_web::web;
+
+pub mod dtos;
+pub mod routes;
+
+use routes::{create_report, delete_report, list_reports, restore_report, update_report};
+
+use crate::auth::AuthMiddleware;
+
+pub fn configure_reports_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/reports")
+            .route("", web::post().to(create_report).wrap(AuthMiddleware))
+            .route("", web::get().to(list_reports).wrap(AuthMiddleware))
+            .route("/{id}", web::patch().to(update_report).wrap(AuthMiddleware))
+            .route(
+                "/{id}",
+                web::delete().to(delete_report).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/restore",
+                web::post().to(restore_report).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{get_test_config, get_test_pool, setup_test_room, setup_test_user};
+    use crate::v1::bookings::configure_bookings_routes;
+    use crate::v1::rooms::configure_rooms_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    async fn test_report_soft_delete() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, _) = setup_test_room(&pool).await;
+        let guest_id = setup_test_user(&pool).await;
+        let other_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_bookings_routes)
+                .configure(configure_reports_routes)
+                .configure(configure_rooms_routes),
+        )
+        .await;
+
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let other = SessionUser {
+            id: other_id,
+            staff_id: None,
+            email: "other@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let staff = SessionUser {
+            id: guest_id,
+            staff_id: Some(Uuid::new_v4()),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+
+        let start = Utc::now() + Duration::days(30);
+        let end = start + Duration::days(2);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({ "roomId": room_id, "start": start, "end": end }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let block_id = body["booking"]["id"].as_str().unwrap().to_string();
+
+        // Guests can only report on their own stays
+        let payload = json!({ "blockId": block_id, "title": "Leak", "description": "Sink" });
+        let req = test::TestRequest::post()
+            .uri("/reports")
+            .cookie(generate_auth_cookie(&token_engine, other).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/reports")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let report_id = body["report"]["id"].as_str().unwrap().to_string();
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/reports/{}", report_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "title": "Kitchen leak" }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["report"]["title"], "Kitchen leak");
+        assert_eq!(body["report"]["description"], "Sink");
+
+        let start_str = start.to_rfc3339().replace("+", "%2B");
+        let end_str = end.to_rfc3339().replace("+", "%2B");
+        let availability_uri = format!(
+            "/rooms/{}/availability?start={}&end={}",
+            room_id, start_str, end_str
+        );
+
+        let req = test::TestRequest::get()
+            .uri(&availability_uri)
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["blocks"][0]["reportCount"], 1);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/reports/{}", report_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let list_uri = format!("/reports?blockId={}", block_id);
+        let req = test::TestRequest::get()
+            .uri(&list_uri)
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["reports"].as_array().unwrap().len(), 0);
+
+        // Only staff may look at deleted reports
+        let req = test::TestRequest::get()
+            .uri(&format!("{}&includeDeleted=true", list_uri))
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("{}&includeDeleted=true", list_uri))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["reports"].as_array().unwrap().len(), 1);
+
+        let req = test::TestRequest::get()
+            .uri(&availability_uri)
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["blocks"][0]["reportCount"], 0);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/reports/{}/restore", report_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/reports/{}/restore", report_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+    }
+}
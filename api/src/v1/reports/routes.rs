@@ -0,0 +1,134 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use std::rc::Rc;
+use uuid::Uuid;
+
+use crate::auth::SessionUser;
+use crate::guard::{Authorized, ModerateReports};
+use crate::v1::reports::dtos::*;
+use app::reports::create::*;
+use app::reports::delete::*;
+use app::reports::list::*;
+use app::reports::restore::*;
+use app::reports::update::*;
+use infra::domains::report;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/reports",
+    request_body = CreateReportRequest,
+    responses(
+        (status = 201, description = "Report filed", body = CreateReportSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Block not found")
+    )
+)]
+pub async fn create_report(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    web::Json(req): web::Json<CreateReportRequest>,
+) -> Result<HttpResponse, CreateReportError> {
+    report::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports",
+    params(
+        ReportListQuery
+    ),
+    responses(
+        (status = 200, description = "Reports filed against the block", body = ListReportsSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Block not found")
+    )
+)]
+pub async fn list_reports(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    web::Query(query): web::Query<ReportListQuery>,
+) -> Result<HttpResponse, ListReportsError> {
+    let options = ListReportsOptions {
+        block_id: query.block_id,
+        include_deleted: query.include_deleted,
+    };
+
+    report::list(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/reports/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    request_body = UpdateReportBody,
+    responses(
+        (status = 200, description = "Report updated", body = UpdateReportSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Report not found")
+    )
+)]
+pub async fn update_report(
+    pool: web::Data<DbPool>,
+    user: Authorized<ModerateReports>,
+    path: web::Path<Uuid>,
+    web::Json(body): web::Json<UpdateReportBody>,
+) -> Result<HttpResponse, UpdateReportError> {
+    let options = UpdateReportOptions {
+        report_id: path.into_inner(),
+        title: body.title,
+        description: body.description,
+    };
+
+    report::update(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/reports/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Report deleted", body = DeleteReportSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Report not found")
+    )
+)]
+pub async fn delete_report(
+    pool: web::Data<DbPool>,
+    user: Authorized<ModerateReports>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DeleteReportError> {
+    let options = DeleteReportOptions {
+        report_id: path.into_inner(),
+    };
+
+    report::delete(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/reports/{id}/restore",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Report restored", body = RestoreReportSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Report not found"),
+        (status = 409, description = "Report is not deleted")
+    )
+)]
+pub async fn restore_report(
+    pool: web::Data<DbPool>,
+    user: Authorized<ModerateReports>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, RestoreReportError> {
+    let options = RestoreReportOptions {
+        report_id: path.into_inner(),
+    };
+
+    report::restore(&pool, options, &user).await.into()
+}
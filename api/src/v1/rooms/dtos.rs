@@ -29,12 +29,15 @@ pub struct CalendarBlock {
     #[serde(rename = "type")]
     pub kind: BlockKind,
     pub label: Option<String>,
+    /// Incident reports filed against the block, excluding deleted ones
+    pub report_count: i64,
 }
 
 #[derive(Serialize, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum BlockKind {
     Booking,
+    Housekeeping,
     Maintenance,
     Unknown,
 }
@@ -61,6 +64,8 @@ pub struct FindRoomQuery {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
     pub class_id: Option<Uuid>,
+    /// Guests to quote the stay for; one when unset
+    pub guests: Option<u32>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -69,6 +74,9 @@ pub struct RoomSummary {
     pub id: Uuid,
     pub label: String,
     pub class_id: Uuid,
+    pub rate_plan_id: Option<Uuid>,
+    #[schema(value_type = Option<String>)]
+    pub quoted_total: Option<bigdecimal::BigDecimal>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -30,6 +30,7 @@ mod tests {
     use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
     use actix_web::{App, test, web};
     use app::AppSettings;
+    use app::auth::Permission;
     use bigdecimal::BigDecimal;
     use config::{Config, File};
     use diesel_async::RunQueryDsl;
@@ -66,6 +67,7 @@ mod tests {
             id: Some(class_id),
             name: "Test Class",
             base_price: BigDecimal::from(100),
+            turnover_hours: None,
         };
 
         diesel::insert_into(room_classes::table)
@@ -100,6 +102,7 @@ mod tests {
             App::new()
                 .app_data(web::Data::new(pool.clone()))
                 .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
                 .configure(configure_rooms_routes),
         )
         .await;
@@ -122,6 +125,7 @@ mod tests {
             App::new()
                 .app_data(web::Data::new(pool.clone()))
                 .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
                 .configure(configure_rooms_routes),
         )
         .await;
@@ -151,6 +155,7 @@ mod tests {
             App::new()
                 .app_data(web::Data::new(pool.clone()))
                 .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
                 .configure(configure_rooms_routes),
         )
         .await;
@@ -159,6 +164,7 @@ mod tests {
             id: Uuid::new_v4(),
             staff_id: Some(Uuid::new_v4()), // Staff access might be needed? Routes don't check role yet, just auth.
             email: "test@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
         };
         let cookie = generate_auth_cookie(&token_engine, user).unwrap();
 
@@ -182,6 +188,7 @@ mod tests {
             App::new()
                 .app_data(web::Data::new(pool.clone()))
                 .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
                 .configure(configure_rooms_routes),
         )
         .await;
@@ -190,6 +197,7 @@ mod tests {
             id: Uuid::new_v4(),
             staff_id: Some(Uuid::new_v4()), // Availability check requires staff_id in routes.rs:22
             email: "test@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
         };
         let cookie = generate_auth_cookie(&token_engine, user).unwrap();
 
@@ -219,6 +227,7 @@ mod tests {
             App::new()
                 .app_data(web::Data::new(pool.clone()))
                 .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
                 .configure(configure_rooms_routes),
         )
         .await;
@@ -227,6 +236,7 @@ mod tests {
             id: Uuid::new_v4(),
             staff_id: Some(Uuid::new_v4()),
             email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
         };
         let cookie = generate_auth_cookie(&token_engine, user).unwrap();
 
@@ -247,6 +257,7 @@ mod tests {
                         id: Uuid::new_v4(),
                         staff_id: Some(Uuid::new_v4()),
                         email: "staff@test.com".to_string(),
+                        permissions: Permission::ALL.to_vec(),
                     },
                 )
                 .unwrap(),
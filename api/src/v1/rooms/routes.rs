@@ -1,10 +1,9 @@
 use actix_web::{HttpResponse, web};
 use app::AppSettings;
 use infra::db::DbPool;
-use std::rc::Rc;
 use uuid::Uuid;
 
-use crate::auth::SessionUser;
+use crate::guard::{Authorized, ReadRooms};
 use crate::v1::rooms::dtos::*;
 use app::rooms::availability::*;
 use app::rooms::classes::*;
@@ -28,7 +27,7 @@ use infra::domains::room;
 )]
 pub async fn get_room_availability(
     pool: web::Data<DbPool>,
-    user: web::ReqData<Rc<SessionUser>>,
+    user: Authorized<ReadRooms>,
     path: web::Path<Uuid>,
     query: web::Query<RoomAvailabilityQuery>,
 ) -> Result<HttpResponse, GetAvailabilityError> {
@@ -54,7 +53,7 @@ pub async fn get_room_availability(
 )]
 pub async fn get_room_details(
     pool: web::Data<DbPool>,
-    _user: web::ReqData<Rc<SessionUser>>,
+    _user: Authorized<ReadRooms>,
     path: web::Path<Uuid>,
     settings: web::Data<AppSettings>,
 ) -> Result<HttpResponse, GetDetailsError> {
@@ -89,20 +88,23 @@ pub async fn get_room_classes(
     ),
     responses(
         (status = 200, description = "List of available rooms", body = FindRoomSuccess),
-        (status = 400, description = "Invalid date range")
+        (status = 400, description = "Invalid date range or guest count"),
+        (status = 422, description = "The stay breaks a stay rule of the room class")
     )
 )]
 pub async fn find_room(
     pool: web::Data<DbPool>,
+    settings: web::Data<AppSettings>,
     web::Query(query): web::Query<FindRoomQuery>,
 ) -> Result<HttpResponse, FindRoomError> {
     let options = FindRoomOptions {
         start: query.start,
         end: query.end,
         class_id: query.class_id,
+        guests: query.guests.unwrap_or(1),
     };
 
-    room::find(&pool, options).await.into()
+    room::find(&pool, options, &settings.pricing).await.into()
 }
 
 #[utoipa::path(
@@ -118,7 +120,7 @@ pub async fn find_room(
 )]
 pub async fn list_rooms(
     pool: web::Data<DbPool>,
-    user: web::ReqData<Rc<SessionUser>>,
+    user: Authorized<ReadRooms>,
     settings: web::Data<AppSettings>,
     web::Query(query): web::Query<RoomListQuery>,
 ) -> Result<HttpResponse, ListRoomError> {
@@ -0,0 +1,241 @@
+use actix_web::web;
+
+pub mod routes;
+
+use crate::auth::AuthMiddleware;
+use routes::*;
+
+pub fn configure_stay_rules_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/stay-rules")
+            .route("", web::get().to(list_stay_rules))
+            .route("", web::post().to(create_stay_rule).wrap(AuthMiddleware))
+            .route(
+                "/{id}",
+                web::delete().to(delete_stay_rule).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{
+        get_test_config, get_test_pool, setup_test_room, setup_test_staff, setup_test_user,
+    };
+    use crate::v1::bookings::configure_bookings_routes;
+    use crate::v1::rooms::configure_rooms_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use serde_json::{Value, json};
+
+    #[actix_web::test]
+    async fn test_stay_rules_restrict_search_and_booking() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (room_id, class_id) = setup_test_room(&pool).await;
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+        let guest_id = setup_test_user(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_stay_rules_routes)
+                .configure(configure_rooms_routes)
+                .configure(configure_bookings_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email: "guest@test.com".to_string(),
+            permissions: Vec::new(),
+        };
+        let minimum = json!({
+            "classId": class_id,
+            "startsOn": "2031-05-01",
+            "endsOn": "2031-05-31",
+            "minNights": 3
+        });
+
+        let req = test::TestRequest::post()
+            .uri("/stay-rules")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(&minimum)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/stay-rules")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({
+                "classId": class_id,
+                "startsOn": "2031-05-01",
+                "endsOn": "2031-05-31",
+                "minNights": 3,
+                "maxNights": 2
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["errors"]["maxNights"].is_array());
+
+        let req = test::TestRequest::post()
+            .uri("/stay-rules")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(&minimum)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let minimum_id = body["id"].as_str().unwrap().to_string();
+
+        let req = test::TestRequest::post()
+            .uri("/stay-rules")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({
+                "classId": class_id,
+                "startsOn": "2031-05-10",
+                "endsOn": "2031-05-10",
+                "closedToArrival": true
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let search = |start: &str, end: &str| {
+            test::TestRequest::get()
+                .uri(&format!(
+                    "/rooms/find?classId={}&start={}T14:00:00Z&end={}T11:00:00Z",
+                    class_id, start, end
+                ))
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, search("2031-05-04", "2031-05-06")).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        let restriction = &body["StayRestricted"]["restriction"];
+        assert_eq!(restriction["kind"], "minNights");
+        assert_eq!(restriction["ruleId"], minimum_id.as_str());
+        assert_eq!(restriction["minNights"], 3);
+
+        let resp = test::call_service(&app, search("2031-05-10", "2031-05-14")).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["StayRestricted"]["restriction"]["kind"],
+            "closedToArrival"
+        );
+
+        let resp = test::call_service(&app, search("2031-05-04", "2031-05-07")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(
+            body["rooms"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|room| room["id"] == room_id.to_string())
+        );
+
+        // Bookings are held to the same rules
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": "2031-05-04T14:00:00Z",
+                "end": "2031-05-06T11:00:00Z"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["StayRestricted"]["restriction"]["kind"], "minNights");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/stay-rules?classId={}", class_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["stayRules"].as_array().unwrap().len(), 2);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/stay-rules/{}", minimum_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = test::TestRequest::post()
+            .uri("/bookings")
+            .cookie(generate_auth_cookie(&token_engine, guest.clone()).unwrap())
+            .set_json(json!({
+                "roomId": room_id,
+                "start": "2031-05-04T14:00:00Z",
+                "end": "2031-05-06T11:00:00Z"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let booking_id = body["booking"]["id"].as_str().unwrap().to_string();
+
+        // Changing the dates can't land the stay on a closed arrival
+        let req = test::TestRequest::patch()
+            .uri(&format!("/bookings/{}", booking_id))
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(json!({
+                "start": "2031-05-10T14:00:00Z",
+                "end": "2031-05-12T11:00:00Z"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["StayRestricted"]["restriction"]["kind"],
+            "closedToArrival"
+        );
+
+        // Nor can moving it to a class with stricter rules
+        let (other_room_id, other_class_id) = setup_test_room(&pool).await;
+        let req = test::TestRequest::post()
+            .uri("/stay-rules")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({
+                "classId": other_class_id,
+                "startsOn": "2031-05-01",
+                "endsOn": "2031-05-31",
+                "minNights": 3
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/bookings/{}/move", booking_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .set_json(json!({ "roomId": other_room_id }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["StayRestricted"]["restriction"]["kind"], "minNights");
+    }
+}
@@ -0,0 +1,70 @@
+use actix_web::{HttpResponse, web};
+use infra::db::DbPool;
+use uuid::Uuid;
+
+use crate::guard::{Authorized, ManageRates};
+use app::api::ValidationErrors;
+use app::stay_rules::StayRule;
+use app::stay_rules::create::*;
+use app::stay_rules::delete::*;
+use app::stay_rules::list::*;
+use infra::domains::stay_rule;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/stay-rules",
+    params(
+        ListStayRulesOptions
+    ),
+    responses(
+        (status = 200, description = "Stay rules in date order", body = ListStayRulesSuccess)
+    )
+)]
+pub async fn list_stay_rules(
+    pool: web::Data<DbPool>,
+    web::Query(options): web::Query<ListStayRulesOptions>,
+) -> Result<HttpResponse, ListStayRulesError> {
+    stay_rule::list(&pool, options).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/stay-rules",
+    request_body = CreateStayRuleRequest,
+    responses(
+        (status = 201, description = "Stay rule created", body = StayRule),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid room class, dates or limits", body = ValidationErrors)
+    )
+)]
+pub async fn create_stay_rule(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    web::Json(req): web::Json<CreateStayRuleRequest>,
+) -> Result<HttpResponse, CreateStayRuleError> {
+    stay_rule::create(&pool, req, &user).await.into()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/stay-rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Stay rule ID")
+    ),
+    responses(
+        (status = 204, description = "Stay rule deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Stay rule not found")
+    )
+)]
+pub async fn delete_stay_rule(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageRates>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DeleteStayRuleError> {
+    let options = DeleteStayRuleOptions {
+        id: path.into_inner(),
+    };
+
+    stay_rule::delete(&pool, options, &user).await.into()
+}
@@ -6,5 +6,459 @@ use crate::auth::AuthMiddleware;
 use routes::*;
 
 pub fn configure_users_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/users").route("/{id}", web::get().to(get_user).wrap(AuthMiddleware)));
+    cfg.service(
+        web::scope("/users")
+            .route("/invite", web::post().to(invite_user).wrap(AuthMiddleware))
+            .route("/{id}", web::get().to(get_user).wrap(AuthMiddleware))
+            .route(
+                "/{id}/sessions/revoke",
+                web::post().to(revoke_user_sessions).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/unlock",
+                web::post().to(unlock_user).wrap(AuthMiddleware),
+            )
+            .route(
+                "/{id}/roles",
+                web::put().to(assign_user_roles).wrap(AuthMiddleware),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{RevocationCache, SessionUser, TokenEngine, generate_auth_cookie};
+    use crate::test_utils::{CapturingSender, get_test_config, get_test_pool, setup_test_staff};
+    use crate::v1::auth::configure_auth_routes;
+    use actix_web::{App, http::StatusCode, test, web};
+    use app::auth::Permission;
+    use app::notifications::NotificationSender;
+    use diesel::prelude::*;
+    use diesel_async::RunQueryDsl;
+    use infra::schema::login_failures;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[actix_web::test]
+    async fn test_invite_staff_and_onboard() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+        let sender = Arc::new(CapturingSender::default());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::<dyn NotificationSender>::from(
+                    sender.clone() as Arc<dyn NotificationSender>
+                ))
+                .configure(configure_users_routes)
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            staff_id: None,
+            permissions: Vec::new(),
+            ..staff.clone()
+        };
+        let email = format!("{}@test.com", Uuid::new_v4());
+
+        let req = test::TestRequest::post()
+            .uri("/users/invite")
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .set_json(json!({ "email": email, "staff": true }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/users/invite")
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .set_json(json!({ "email": email, "staff": true }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let invitee_id = body["user"]["id"].as_str().unwrap().to_string();
+        assert!(body["staffId"].is_string());
+
+        let req = test::TestRequest::post()
+            .uri("/users/invite")
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .set_json(json!({ "email": email }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // The invitee sets a password with the code they were sent
+        let codes = sender.codes();
+        assert_eq!(codes.len(), 1);
+        let req = test::TestRequest::post()
+            .uri("/auth/onboard")
+            .set_json(json!({ "userId": invitee_id, "otp": codes[0], "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "email": email, "password": "secret-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_user_sessions() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (user_id, staff_id) = setup_test_staff(&pool).await;
+        let (fired_id, fired_staff_id) = setup_test_staff(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(RevocationCache::new(
+                    config.security.revocation_cache_ttl,
+                )))
+                .configure(configure_users_routes),
+        )
+        .await;
+
+        let staff = SessionUser {
+            id: user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let fired = SessionUser {
+            id: fired_id,
+            staff_id: Some(fired_staff_id),
+            email: "fired@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let fired_cookie = generate_auth_cookie(&token_engine, fired.clone()).unwrap();
+
+        // Revoking is a staff action, and guests are not staff
+        let guest = SessionUser {
+            staff_id: None,
+            permissions: Vec::new(),
+            ..staff.clone()
+        };
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/sessions/revoke", fired_id))
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/sessions/revoke", Uuid::new_v4()))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", fired_id))
+            .cookie(fired_cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/sessions/revoke", fired_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["revokedAt"].is_string());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", fired_id))
+            .cookie(fired_cookie)
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_roles_and_permissions() {
+        let config = get_test_config();
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (admin_id, admin_staff_id) = setup_test_staff(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::<dyn NotificationSender>::from(Arc::new(
+                    CapturingSender::default(),
+                )
+                    as Arc<dyn NotificationSender>))
+                .configure(configure_users_routes)
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let admin = SessionUser {
+            id: admin_id,
+            staff_id: Some(admin_staff_id),
+            email: "admin@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+
+        let email = format!("{}@test.com", Uuid::new_v4());
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "guest-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let guest_cookie = resp.response().cookies().next().unwrap().into_owned();
+        let body: Value = test::read_body_json(resp).await;
+        let guest_id = body["user"]["id"].as_str().unwrap().to_string();
+
+        // Guests read their own record and nobody else's
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", guest_id))
+            .cookie(guest_cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", admin_id))
+            .cookie(guest_cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/roles", guest_id))
+            .cookie(guest_cookie.clone())
+            .set_json(json!({ "roles": ["admin"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let admin_cookie = generate_auth_cookie(&token_engine, admin).unwrap();
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/roles", guest_id))
+            .cookie(admin_cookie.clone())
+            .set_json(json!({ "roles": ["concierge"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}/roles", guest_id))
+            .cookie(admin_cookie)
+            .set_json(json!({ "roles": ["front_desk"] }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["permissions"],
+            json!(["bookings.manage", "rooms.read", "users.read"])
+        );
+
+        // The new roles apply from the next sign-in
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", guest_id))
+            .cookie(guest_cookie)
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .set_json(json!({ "email": email, "password": "guest-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let desk_cookie = resp.response().cookies().next().unwrap().into_owned();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/users/{}", admin_id))
+            .cookie(desk_cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Front desk reads users but does not invite them
+        let req = test::TestRequest::post()
+            .uri("/users/invite")
+            .cookie(desk_cookie)
+            .set_json(json!({ "email": format!("{}@test.com", Uuid::new_v4()) }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_login_lockout_and_unlock() {
+        let mut config = get_test_config();
+        config.login.max_failures = 3;
+        config.login.max_ip_failures = 5;
+        let pool = get_test_pool(&config).await;
+        let token_engine = TokenEngine::new(&config.security);
+
+        let (staff_user_id, staff_id) = setup_test_staff(&pool).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(token_engine.clone()))
+                .app_data(web::Data::new(config.clone()))
+                .configure(configure_auth_routes)
+                .configure(configure_users_routes),
+        )
+        .await;
+
+        let login = |email: &str, password: &str, ip: &str| {
+            test::TestRequest::post()
+                .uri("/auth/login")
+                .peer_addr(format!("{}:4000", ip).parse().unwrap())
+                .set_json(json!({ "email": email, "password": password }))
+                .to_request()
+        };
+        let octet = || rand::random::<u8>();
+        let ip = format!("10.{}.{}.{}", octet(), octet(), octet());
+
+        let email = format!("{}@test.com", Uuid::new_v4());
+        let req = test::TestRequest::post()
+            .uri("/auth/signup")
+            .set_json(json!({ "email": email, "password": "right-pass" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let user_id = body["user"]["id"].as_str().unwrap().to_string();
+
+        for _ in 0..3 {
+            let resp = test::call_service(&app, login(&email, "wrong-pass", &ip)).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // Even the right password is refused until the lockout ends, whatever the case
+        let resp = test::call_service(&app, login(&email.to_uppercase(), "right-pass", &ip)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = resp
+            .headers()
+            .get("Retry-After")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(retry_after > 0 && retry_after <= config.login.lockout);
+        let locked: Value = test::read_body_json(resp).await;
+
+        // An unknown email locks the same way, so the response says nothing about the account
+        let unknown = format!("{}@test.com", Uuid::new_v4());
+        let other_ip = format!("10.{}.{}.{}", octet(), octet(), octet());
+        for _ in 0..3 {
+            let resp = test::call_service(&app, login(&unknown, "any-pass", &other_ip)).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+        let resp = test::call_service(&app, login(&unknown, "any-pass", &other_ip)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let body: Value = test::read_body_json(resp).await;
+        // The countdown may have ticked between the two responses
+        let without_countdown = |body: &Value| {
+            body.as_str()
+                .unwrap()
+                .replace(|c: char| c.is_ascii_digit(), "")
+        };
+        assert_eq!(without_countdown(&body), without_countdown(&locked));
+
+        let audited: i64 = login_failures::table
+            .filter(login_failures::email.eq_any([email.clone(), email.to_uppercase()]))
+            .count()
+            .get_result(&mut pool.get().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(audited, 4);
+
+        // Unlocking is a staff action
+        let staff = SessionUser {
+            id: staff_user_id,
+            staff_id: Some(staff_id),
+            email: "staff@test.com".to_string(),
+            permissions: Permission::ALL.to_vec(),
+        };
+        let guest = SessionUser {
+            staff_id: None,
+            permissions: Vec::new(),
+            ..staff.clone()
+        };
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/unlock", user_id))
+            .cookie(generate_auth_cookie(&token_engine, guest).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/unlock", user_id))
+            .cookie(generate_auth_cookie(&token_engine, staff.clone()).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body, json!({ "hadFailures": true }));
+
+        let resp = test::call_service(&app, login(&email, "right-pass", &ip)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // The address has its own, higher limit that outlives the account unlock
+        for _ in 0..2 {
+            let resp = test::call_service(&app, login(&email, "wrong-pass", &ip)).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+        let resp = test::call_service(&app, login(&email, "right-pass", &ip)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/users/{}/unlock", user_id))
+            .cookie(generate_auth_cookie(&token_engine, staff).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body, json!({ "hadFailures": true }));
+
+        let resp = test::call_service(&app, login(&email, "right-pass", &ip)).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let resp = test::call_service(&app, login(&email, "right-pass", &other_ip)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
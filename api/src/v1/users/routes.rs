@@ -1,8 +1,18 @@
 use actix_web::{HttpResponse, web};
 use infra::db::DbPool;
+use std::rc::Rc;
 use uuid::Uuid;
 
+use crate::auth::{RevocationCache, SessionUser};
+use crate::guard::{Authorized, ManageRoles, ManageUsers};
+use app::AppSettings;
+use app::api::ValidationErrors;
+use app::notifications::NotificationSender;
 use app::users::details::*;
+use app::users::invite::*;
+use app::users::lockout::*;
+use app::users::roles::*;
+use app::users::sessions::*;
 use infra::domains::user;
 
 #[utoipa::path(
@@ -13,13 +23,131 @@ use infra::domains::user;
     ),
     responses(
         (status = 200, description = "User found", body = GetUserDetailsSuccess),
+        (status = 401, description = "Guests can only read their own record"),
         (status = 404, description = "User not found")
     )
 )]
-pub async fn get_user(pool: web::Data<DbPool>, path: web::Path<Uuid>) -> HttpResponse {
+pub async fn get_user(
+    pool: web::Data<DbPool>,
+    user: web::ReqData<Rc<SessionUser>>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let options = GetUserDetailsOptions {
         user_id: path.into_inner(),
     };
 
-    user::get_details(&pool, options).await.into()
+    user::get_details(&pool, options, &user).await.into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/invite",
+    request_body = InviteUserRequest,
+    responses(
+        (status = 201, description = "User invited and sent an onboarding code", body = InviteUserSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 422, description = "Invalid or already registered email", body = ValidationErrors)
+    )
+)]
+pub async fn invite_user(
+    pool: web::Data<DbPool>,
+    settings: web::Data<AppSettings>,
+    sender: web::Data<dyn NotificationSender>,
+    user: Authorized<ManageUsers>,
+    web::Json(req): web::Json<InviteUserRequest>,
+) -> Result<HttpResponse, InviteUserError> {
+    user::invite(&pool, req, &user, &settings.otp, sender.get_ref())
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/sessions/revoke",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Every session of the user was signed out", body = RevokeSessionsSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn revoke_user_sessions(
+    pool: web::Data<DbPool>,
+    revocations: Option<web::Data<RevocationCache>>,
+    user: Authorized<ManageUsers>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, RevokeSessionsError> {
+    let user_id = path.into_inner();
+
+    let result: Result<HttpResponse, RevokeSessionsError> =
+        user::revoke_sessions(&pool, RevokeSessionsOptions { user_id }, &user)
+            .await
+            .into();
+
+    if let (Ok(_), Some(revocations)) = (&result, revocations) {
+        revocations.revoke_user(user_id);
+    }
+
+    result
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/unlock",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Failed sign-ins were cleared and the account may sign in", body = UnlockAccountSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn unlock_user(
+    pool: web::Data<DbPool>,
+    user: Authorized<ManageUsers>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, UnlockAccountError> {
+    let user_id = path.into_inner();
+
+    user::unlock(&pool, UnlockAccountOptions { user_id }, &user)
+        .await
+        .into()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}/roles",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = AssignRolesRequest,
+    responses(
+        (status = 200, description = "Roles replaced and the user signed out", body = AssignRolesSuccess),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Unknown role", body = ValidationErrors)
+    )
+)]
+pub async fn assign_user_roles(
+    pool: web::Data<DbPool>,
+    revocations: Option<web::Data<RevocationCache>>,
+    user: Authorized<ManageRoles>,
+    path: web::Path<Uuid>,
+    web::Json(req): web::Json<AssignRolesRequest>,
+) -> Result<HttpResponse, AssignRolesError> {
+    let user_id = path.into_inner();
+
+    let result: Result<HttpResponse, AssignRolesError> =
+        user::assign_roles(&pool, AssignRolesOptions { user_id }, req, &user)
+            .await
+            .into();
+
+    if let (Ok(_), Some(revocations)) = (&result, revocations) {
+        revocations.revoke_user(user_id);
+    }
+
+    result
 }
@@ -1,5 +1,7 @@
 use actix_web::{HttpResponse, ResponseError, body::BoxBody};
 use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
 
 pub enum ApiResponse<S, E> {
     Success(HttpResponse<S>),
@@ -55,3 +57,29 @@ impl<S: Serialize, E: ResponseError> From<ApiResponse<S, E>> for Result<HttpResp
         }
     }
 }
+
+/// Field-level validation messages, rendered as `{"errors": {"field": ["message"]}}`.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ValidationErrors {
+    pub errors: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, message: &str) {
+        self.errors
+            .entry(field.to_string())
+            .or_default()
+            .push(message.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// A single message for one field.
+    pub fn field(field: &str, message: &str) -> Self {
+        let mut errors = Self::default();
+        errors.add(field, message);
+        errors
+    }
+}
@@ -0,0 +1,87 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+
+use crate::api::ValidationErrors;
+use crate::api_keys::{ApiKeyDetails, is_valid_scope};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyRequest {
+    /// What the key is for, e.g. the integration using it
+    pub name: String,
+    /// `resource:read` or `resource:write` entries
+    pub scopes: Vec<String>,
+    /// Keys without an expiry last until revoked
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CreateApiKeyRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        if self.name.trim().is_empty() {
+            errors.add("name", "can't be blank");
+        }
+
+        if self.scopes.is_empty() {
+            errors.add("scopes", "can't be empty");
+        }
+        for scope in self.scopes.iter().filter(|scope| !is_valid_scope(scope)) {
+            errors.add("scopes", &format!("{scope} is not a valid scope"));
+        }
+
+        if self.expires_at.is_some_and(|at| at <= Utc::now()) {
+            errors.add("expiresAt", "must be in the future");
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeySuccess {
+    pub api_key: ApiKeyDetails,
+    /// The secret, sent as `Authorization: Bearer <key>`; it is not shown again
+    pub key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateApiKeyError {
+    Unauthorized,
+    InternalError,
+    Invalid(ValidationErrors),
+}
+
+impl Display for CreateApiKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateApiKeyError::Unauthorized => write!(f, "Unauthorized"),
+            CreateApiKeyError::InternalError => write!(f, "Internal Server Error"),
+            CreateApiKeyError::Invalid(_) => write!(f, "Invalid API key details"),
+        }
+    }
+}
+
+impl ResponseError for CreateApiKeyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateApiKeyError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateApiKeyError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateApiKeyError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            CreateApiKeyError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
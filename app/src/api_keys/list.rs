@@ -0,0 +1,41 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+
+use crate::api_keys::ApiKeyDetails;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListApiKeysSuccess {
+    /// Newest first, revoked keys included
+    pub api_keys: Vec<ApiKeyDetails>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListApiKeysError {
+    Unauthorized,
+    InternalError,
+}
+
+impl Display for ListApiKeysError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListApiKeysError::Unauthorized => write!(f, "Unauthorized"),
+            ListApiKeysError::InternalError => write!(f, "Internal Server Error"),
+        }
+    }
+}
+
+impl ResponseError for ListApiKeysError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListApiKeysError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ListApiKeysError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::SessionUser;
+
+pub mod create;
+pub mod list;
+pub mod revoke;
+
+/// Marks a bearer credential as an API key rather than a session token.
+pub const API_KEY_PREFIX: &str = "hsk_";
+
+/// Resources a key can be scoped to. Sessions and keys themselves are managed by people only.
+pub const SCOPED_RESOURCES: [&str; 10] = [
+    "bookings",
+    "housekeeping",
+    "maintenance",
+    "pricing",
+    "promo-codes",
+    "rate-plans",
+    "reports",
+    "rooms",
+    "stay-rules",
+    "users",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    fn as_str(self) -> &'static str {
+        match self {
+            Access::Read => "read",
+            Access::Write => "write",
+        }
+    }
+}
+
+/// Checks that `scope` reads `resource:read` or `resource:write` for a scoped resource.
+pub fn is_valid_scope(scope: &str) -> bool {
+    scope.split_once(':').is_some_and(|(resource, access)| {
+        SCOPED_RESOURCES.contains(&resource) && matches!(access, "read" | "write")
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyDetails {
+    pub id: Uuid,
+    pub name: String,
+    /// Leading characters of the secret, to tell keys apart
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    /// Staff member the key acts on behalf of
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// The caller behind a request authenticated with an API key.
+#[derive(Debug, Clone)]
+pub struct ApiKeyPrincipal {
+    pub key_id: Uuid,
+    pub user: SessionUser,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKeyPrincipal {
+    /// Whether the key may act on `resource`; write access implies read access.
+    pub fn grants(&self, resource: &str, access: Access) -> bool {
+        self.scopes.iter().any(|scope| {
+            scope.split_once(':').is_some_and(|(scoped, granted)| {
+                scoped == resource && (granted == access.as_str() || granted == "write")
+            })
+        })
+    }
+}
+
+/// Generates a new secret; only its hash is stored.
+pub fn generate_api_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+
+    format!("{API_KEY_PREFIX}{}", to_hex(&bytes))
+}
+
+/// Hashes a secret for storage and lookup. Keys are random, so a fast hash is enough.
+pub fn hash_api_key(key: &str) -> String {
+    to_hex(&Sha256::digest(key.as_bytes()))
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn principal(scopes: &[&str]) -> ApiKeyPrincipal {
+        ApiKeyPrincipal {
+            key_id: Uuid::new_v4(),
+            user: SessionUser {
+                id: Uuid::new_v4(),
+                staff_id: Some(Uuid::new_v4()),
+                email: "kiosk@test.com".to_string(),
+                permissions: Vec::new(),
+            },
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_scopes() {
+        assert!(is_valid_scope("bookings:read"));
+        assert!(!is_valid_scope("bookings:admin"));
+        assert!(!is_valid_scope("auth:write"));
+
+        let key = principal(&["rooms:read", "bookings:write"]);
+        assert!(key.grants("rooms", Access::Read));
+        assert!(!key.grants("rooms", Access::Write));
+        assert!(key.grants("bookings", Access::Read));
+        assert!(!key.grants("users", Access::Read));
+    }
+
+    #[test]
+    fn test_generated_keys() {
+        let key = generate_api_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_ne!(key, generate_api_key());
+        assert_eq!(hash_api_key(&key), hash_api_key(&key));
+        assert_eq!(hash_api_key(&key).len(), 64);
+    }
+}
@@ -0,0 +1,49 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api_keys::ApiKeyDetails;
+
+#[derive(Debug, Clone)]
+pub struct RevokeApiKeyOptions {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiKeySuccess {
+    pub api_key: ApiKeyDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum RevokeApiKeyError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for RevokeApiKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevokeApiKeyError::Unauthorized => write!(f, "Unauthorized"),
+            RevokeApiKeyError::InternalError => write!(f, "Internal Server Error"),
+            RevokeApiKeyError::NotFound => write!(f, "API key not found"),
+        }
+    }
+}
+
+impl ResponseError for RevokeApiKeyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RevokeApiKeyError::Unauthorized => StatusCode::UNAUTHORIZED,
+            RevokeApiKeyError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            RevokeApiKeyError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -1,8 +1,140 @@
 use serde::Deserialize;
 use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::SessionUser;
+use crate::auth::totp::LoginChallenge;
+use crate::settings::LoginSettings;
 
 #[derive(Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
 }
+
+/// Where a sign-in stands once the password or one-time code has been accepted.
+pub enum SignIn {
+    Complete(SessionUser),
+    /// Signed in without permissions, since a role requires TOTP and the user has not enrolled
+    EnrollmentRequired(SessionUser),
+    /// A TOTP code is still owed and must be sent along with the challenge token
+    Challenge {
+        user_id: Uuid,
+        challenge: LoginChallenge,
+    },
+}
+
+impl SignIn {
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            SignIn::Complete(user) | SignIn::EnrollmentRequired(user) => user.id,
+            SignIn::Challenge { user_id, .. } => *user_id,
+        }
+    }
+}
+
+/// What a run of failed sign-ins is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleScope {
+    /// The submitted email, whether or not an account uses it
+    Account,
+    /// The address the attempts come from
+    Ip,
+}
+
+impl ThrottleScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleScope::Account => "account",
+            ThrottleScope::Ip => "ip",
+        }
+    }
+
+    fn max_failures(&self, settings: &LoginSettings) -> u32 {
+        match self {
+            ThrottleScope::Account => settings.max_failures,
+            ThrottleScope::Ip => settings.max_ip_failures,
+        }
+    }
+}
+
+/// Seconds a scope stays locked after its `failures`-th consecutive failure, if at all.
+///
+/// Reaching the limit locks it for `lockout`; every failure past that doubles the wait,
+/// up to `max_lockout`.
+pub fn lockout_seconds(
+    scope: ThrottleScope,
+    failures: u32,
+    settings: &LoginSettings,
+) -> Option<u64> {
+    let excess = failures.checked_sub(scope.max_failures(settings))?;
+    let factor = 1u64.checked_shl(excess).unwrap_or(u64::MAX);
+
+    Some(
+        settings
+            .lockout
+            .saturating_mul(factor)
+            .min(settings.max_lockout),
+    )
+}
+
+/// The key failed sign-ins for `email` are counted under, so that case does not matter.
+pub fn account_subject(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> LoginSettings {
+        LoginSettings {
+            max_failures: 5,
+            max_ip_failures: 50,
+            lockout: 60,
+            max_lockout: 3600,
+            reset_after: 900,
+            challenge_ttl: 300,
+        }
+    }
+
+    #[test]
+    fn test_lockout_backs_off_exponentially() {
+        let settings = settings();
+
+        assert_eq!(lockout_seconds(ThrottleScope::Account, 4, &settings), None);
+        assert_eq!(
+            lockout_seconds(ThrottleScope::Account, 5, &settings),
+            Some(60)
+        );
+        assert_eq!(
+            lockout_seconds(ThrottleScope::Account, 6, &settings),
+            Some(120)
+        );
+        assert_eq!(
+            lockout_seconds(ThrottleScope::Account, 8, &settings),
+            Some(480)
+        );
+        assert_eq!(
+            lockout_seconds(ThrottleScope::Account, 11, &settings),
+            Some(3600)
+        );
+        assert_eq!(
+            lockout_seconds(ThrottleScope::Account, 200, &settings),
+            Some(3600)
+        );
+    }
+
+    #[test]
+    fn test_ip_scope_has_its_own_limit() {
+        let settings = settings();
+
+        assert_eq!(lockout_seconds(ThrottleScope::Ip, 49, &settings), None);
+        assert_eq!(lockout_seconds(ThrottleScope::Ip, 50, &settings), Some(60));
+    }
+
+    #[test]
+    fn test_account_subject_ignores_case() {
+        assert_eq!(account_subject(" Guest@Example.com"), "guest@example.com");
+    }
+}
@@ -3,6 +3,7 @@ use argon2::{
     Argon2,
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
 };
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use uuid::Uuid;
@@ -11,18 +12,37 @@ use utoipa::ToSchema;
 
 pub mod login;
 pub mod onboard;
+pub mod otp;
+pub mod password;
+pub mod permissions;
+pub mod signup;
+pub mod totp;
+
+pub use permissions::Permission;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema, bitcode::Encode, bitcode::Decode)]
 pub struct SessionUser {
     pub id: Uuid,
     pub staff_id: Option<Uuid>,
     pub email: String,
+    /// Everything the user's roles allow; empty for guests
+    pub permissions: Vec<Permission>,
+}
+
+impl SessionUser {
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
 }
 
 #[derive(Debug)]
 pub enum AuthError {
     InternalError,
     InvalidCredentials,
+    /// Too many failed sign-ins; the next attempt is accepted after `retry_after` seconds
+    Locked {
+        retry_after: u64,
+    },
 }
 
 impl Display for AuthError {
@@ -30,6 +50,11 @@ impl Display for AuthError {
         match self {
             AuthError::InternalError => write!(f, "Internal Server Error"),
             AuthError::InvalidCredentials => write!(f, "Invalid credentials"),
+            AuthError::Locked { retry_after } => write!(
+                f,
+                "Too many failed sign-ins, retry in {} seconds",
+                retry_after
+            ),
         }
     }
 }
@@ -39,11 +64,16 @@ impl ResponseError for AuthError {
         match self {
             AuthError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
             AuthError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            AuthError::Locked { .. } => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code()).json(self.to_string())
+        let mut response = HttpResponse::build(self.status_code());
+        if let AuthError::Locked { retry_after } = self {
+            response.insert_header(("Retry-After", retry_after.to_string()));
+        }
+        response.json(self.to_string())
     }
 }
 
@@ -69,3 +99,9 @@ pub fn verify_password(plain_password: &str, stored_hash: &str) -> Result<bool,
 
     Ok(is_valid)
 }
+
+/// Generates a six digit one-time code.
+pub fn generate_otp() -> String {
+    // ThreadRng is a CSPRNG reseeded from the operating system
+    format!("{:06}", rand::rng().random_range(0..1_000_000))
+}
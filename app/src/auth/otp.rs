@@ -0,0 +1,59 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueOtpRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueOtpSuccess {
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum IssueOtpError {
+    InternalError,
+    /// Too many codes were requested; another may be issued after `retry_after` seconds
+    RateLimited {
+        retry_after: u64,
+    },
+}
+
+impl Display for IssueOtpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueOtpError::InternalError => write!(f, "Internal Server Error"),
+            IssueOtpError::RateLimited { retry_after } => {
+                write!(
+                    f,
+                    "Too many codes requested, retry in {} seconds",
+                    retry_after
+                )
+            }
+        }
+    }
+}
+
+impl ResponseError for IssueOtpError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            IssueOtpError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            IssueOtpError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        if let IssueOtpError::RateLimited { retry_after } = self {
+            response.insert_header(("Retry-After", retry_after.to_string()));
+        }
+        response.json(self)
+    }
+}
@@ -0,0 +1,180 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+
+use crate::api::ValidationErrors;
+use crate::auth::signup::MIN_PASSWORD_LENGTH;
+
+/// Adds a message to `errors` if `password` is too weak to accept.
+pub(crate) fn check_password(errors: &mut ValidationErrors, field: &str, password: &str) {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        errors.add(
+            field,
+            &format!("must be at least {MIN_PASSWORD_LENGTH} characters"),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ForgotPasswordError {
+    InternalError,
+    /// Too many codes were requested; another may be issued after `retry_after` seconds
+    RateLimited {
+        retry_after: u64,
+    },
+}
+
+impl Display for ForgotPasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForgotPasswordError::InternalError => write!(f, "Internal Server Error"),
+            ForgotPasswordError::RateLimited { retry_after } => {
+                write!(
+                    f,
+                    "Too many codes requested, retry in {} seconds",
+                    retry_after
+                )
+            }
+        }
+    }
+}
+
+impl ResponseError for ForgotPasswordError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ForgotPasswordError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ForgotPasswordError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        if let ForgotPasswordError::RateLimited { retry_after } = self {
+            response.insert_header(("Retry-After", retry_after.to_string()));
+        }
+        response.json(self)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordRequest {
+    pub email: String,
+    pub otp: String,
+    pub password: String,
+}
+
+impl ResetPasswordRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+        check_password(&mut errors, "password", &self.password);
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum ResetPasswordError {
+    InternalError,
+    InvalidCode,
+    Invalid(ValidationErrors),
+    /// Too many wrong codes; the next attempt is accepted after `retry_after` seconds
+    Locked {
+        retry_after: u64,
+    },
+}
+
+impl Display for ResetPasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResetPasswordError::InternalError => write!(f, "Internal Server Error"),
+            ResetPasswordError::InvalidCode => write!(f, "Invalid or expired code"),
+            ResetPasswordError::Invalid(_) => write!(f, "Invalid password"),
+            ResetPasswordError::Locked { retry_after } => {
+                write!(f, "Too many wrong codes, retry in {} seconds", retry_after)
+            }
+        }
+    }
+}
+
+impl ResponseError for ResetPasswordError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ResetPasswordError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ResetPasswordError::InvalidCode => StatusCode::UNAUTHORIZED,
+            ResetPasswordError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ResetPasswordError::Locked { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            ResetPasswordError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            ResetPasswordError::Locked { retry_after } => HttpResponse::build(self.status_code())
+                .insert_header(("Retry-After", retry_after.to_string()))
+                .json(self),
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+impl ChangePasswordRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+        check_password(&mut errors, "newPassword", &self.new_password);
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum ChangePasswordError {
+    InternalError,
+    InvalidCredentials,
+    Invalid(ValidationErrors),
+}
+
+impl Display for ChangePasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangePasswordError::InternalError => write!(f, "Internal Server Error"),
+            ChangePasswordError::InvalidCredentials => write!(f, "Invalid credentials"),
+            ChangePasswordError::Invalid(_) => write!(f, "Invalid password"),
+        }
+    }
+}
+
+impl ResponseError for ChangePasswordError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ChangePasswordError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ChangePasswordError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            ChangePasswordError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            ChangePasswordError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a role allows, named after the rows of the `permissions` table.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    bitcode::Encode,
+    bitcode::Decode,
+)]
+pub enum Permission {
+    #[serde(rename = "rooms.read")]
+    ReadRooms,
+    #[serde(rename = "bookings.manage")]
+    ManageBookings,
+    #[serde(rename = "housekeeping.manage")]
+    ManageHousekeeping,
+    #[serde(rename = "maintenance.manage")]
+    ManageMaintenance,
+    #[serde(rename = "reports.moderate")]
+    ModerateReports,
+    #[serde(rename = "users.read")]
+    ReadUsers,
+    #[serde(rename = "users.manage")]
+    ManageUsers,
+    #[serde(rename = "api_keys.manage")]
+    ManageApiKeys,
+    #[serde(rename = "roles.manage")]
+    ManageRoles,
+    #[serde(rename = "rates.manage")]
+    ManageRates,
+    #[serde(rename = "promotions.manage")]
+    ManagePromotions,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 11] = [
+        Permission::ReadRooms,
+        Permission::ManageBookings,
+        Permission::ManageHousekeeping,
+        Permission::ManageMaintenance,
+        Permission::ModerateReports,
+        Permission::ReadUsers,
+        Permission::ManageUsers,
+        Permission::ManageApiKeys,
+        Permission::ManageRoles,
+        Permission::ManageRates,
+        Permission::ManagePromotions,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::ReadRooms => "rooms.read",
+            Permission::ManageBookings => "bookings.manage",
+            Permission::ManageHousekeeping => "housekeeping.manage",
+            Permission::ManageMaintenance => "maintenance.manage",
+            Permission::ModerateReports => "reports.moderate",
+            Permission::ReadUsers => "users.read",
+            Permission::ManageUsers => "users.manage",
+            Permission::ManageApiKeys => "api_keys.manage",
+            Permission::ManageRoles => "roles.manage",
+            Permission::ManageRates => "rates.manage",
+            Permission::ManagePromotions => "promotions.manage",
+        }
+    }
+
+    /// Looks up a permission by its database name; unknown names are `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == name)
+    }
+}
@@ -0,0 +1,70 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+
+use crate::api::ValidationErrors;
+use crate::auth::password::check_password;
+use crate::users::{details::UserDetails, is_valid_email};
+
+/// Shortest password accepted at sign-up.
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignupRequest {
+    pub email: String,
+    pub password: String,
+}
+
+impl SignupRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        if !is_valid_email(self.email.trim()) {
+            errors.add("email", "is not a valid email address");
+        }
+
+        check_password(&mut errors, "password", &self.password);
+
+        errors
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignupSuccess {
+    pub user: UserDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum SignupError {
+    InternalError,
+    Invalid(ValidationErrors),
+}
+
+impl Display for SignupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignupError::InternalError => write!(f, "Internal Server Error"),
+            SignupError::Invalid(_) => write!(f, "Invalid sign-up details"),
+        }
+    }
+}
+
+impl ResponseError for SignupError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SignupError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            SignupError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            SignupError::Invalid(errors) => HttpResponse::build(self.status_code()).json(errors),
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
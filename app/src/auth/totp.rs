@@ -0,0 +1,276 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fmt::Display;
+use utoipa::ToSchema;
+
+use crate::api_keys::to_hex;
+
+/// Seconds each code is valid for
+pub const TOTP_PERIOD: u64 = 30;
+pub const TOTP_DIGITS: u32 = 6;
+/// Steps either side of the current one that are still accepted, to allow for clock drift
+pub const TOTP_SKEW: u64 = 1;
+/// Recovery codes handed out when TOTP is enabled
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Generates a 160-bit shared secret, base32 encoded as authenticator apps expect.
+pub fn generate_totp_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::rng().fill_bytes(&mut bytes);
+
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// Builds the `otpauth://` URI that authenticator apps scan from a QR code.
+pub fn provisioning_uri(secret: &str, issuer: &str, account: &str) -> String {
+    let issuer = utf8_percent_encode(issuer, NON_ALPHANUMERIC);
+    let account = utf8_percent_encode(account, NON_ALPHANUMERIC);
+
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}\
+         &algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD}"
+    )
+}
+
+/// The time step `timestamp` (in Unix seconds) falls in.
+pub fn totp_step(timestamp: i64) -> u64 {
+    timestamp.max(0) as u64 / TOTP_PERIOD
+}
+
+/// Computes the code for `step` (RFC 6238), or `None` if the secret is not valid base32.
+pub fn totp_code(secret: &str, step: u64) -> Option<String> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation, RFC 4226 section 5.3
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    Some(format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    ))
+}
+
+/// Returns the step `code` was generated for, if it is valid around `step` and newer than
+/// `last_used_step`, so that a code is only accepted once.
+pub fn verify_totp(
+    secret: &str,
+    code: &str,
+    step: u64,
+    last_used_step: Option<u64>,
+) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize {
+        return None;
+    }
+
+    (step.saturating_sub(TOTP_SKEW)..=step + TOTP_SKEW)
+        .filter(|candidate| last_used_step.is_none_or(|used| *candidate > used))
+        .find(|candidate| totp_code(secret, *candidate).is_some_and(|expected| expected == code))
+}
+
+/// Generates single-use codes that stand in for a TOTP code when the device is lost.
+pub fn generate_recovery_codes() -> Vec<String> {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    let mut rng = rand::rng();
+
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut code: String = (0..10)
+                .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+                .collect();
+            code.insert(5, '-');
+            code
+        })
+        .collect()
+}
+
+/// Hashes a recovery code for storage, ignoring case, spaces and dashes.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    to_hex(&Sha256::digest(normalized.as_bytes()))
+}
+
+/// Generates the token a client presents with its TOTP code to finish signing in.
+pub fn generate_challenge_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+
+    to_hex(&bytes)
+}
+
+/// Hashes a challenge token for storage and lookup.
+pub fn hash_challenge_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+/// Issued in place of a session when the password is right but a TOTP code is still owed.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginChallenge {
+    pub challenge_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyTotpRequest {
+    pub challenge_token: String,
+    /// A code from the authenticator app, or an unused recovery code
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrollTotpSuccess {
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub provisioning_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmTotpSuccess {
+    /// Shown only once; each can be used a single time instead of a TOTP code
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DisableTotpRequest {
+    /// A current TOTP code or an unused recovery code
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub enum TotpError {
+    InternalError,
+    /// TOTP is already enabled for the account
+    AlreadyEnabled,
+    /// No enrollment is waiting for confirmation, or TOTP is not enabled
+    NotEnrolled,
+    InvalidCode,
+    /// A role of the user requires TOTP, so it cannot be turned off
+    Required,
+}
+
+impl Display for TotpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TotpError::InternalError => write!(f, "Internal Server Error"),
+            TotpError::AlreadyEnabled => write!(f, "Two-factor authentication is already enabled"),
+            TotpError::NotEnrolled => write!(f, "Two-factor authentication is not set up"),
+            TotpError::InvalidCode => write!(f, "Invalid code"),
+            TotpError::Required => {
+                write!(f, "Two-factor authentication is required for your role")
+            }
+        }
+    }
+}
+
+impl ResponseError for TotpError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TotpError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            TotpError::AlreadyEnabled => StatusCode::CONFLICT,
+            TotpError::NotEnrolled => StatusCode::NOT_FOUND,
+            TotpError::InvalidCode => StatusCode::UNAUTHORIZED,
+            TotpError::Required => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The RFC 6238 SHA-1 seed, "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_totp_matches_rfc_vectors() {
+        assert_eq!(totp_code(RFC_SECRET, totp_step(59)).unwrap(), "287082");
+        assert_eq!(
+            totp_code(RFC_SECRET, totp_step(1111111109)).unwrap(),
+            "081804"
+        );
+        assert_eq!(
+            totp_code(RFC_SECRET, totp_step(1234567890)).unwrap(),
+            "005924"
+        );
+        assert_eq!(
+            totp_code(RFC_SECRET, totp_step(20000000000)).unwrap(),
+            "353130"
+        );
+    }
+
+    #[test]
+    fn test_verify_allows_skew_but_not_replay() {
+        let step = totp_step(1234567890);
+        let previous = totp_code(RFC_SECRET, step - 1).unwrap();
+
+        assert_eq!(
+            verify_totp(RFC_SECRET, &previous, step, None),
+            Some(step - 1)
+        );
+        assert_eq!(
+            verify_totp(RFC_SECRET, &previous, step, Some(step - 1)),
+            None
+        );
+        assert_eq!(verify_totp(RFC_SECRET, &previous, step + 2, None), None);
+        assert_eq!(verify_totp(RFC_SECRET, "12345", step, None), None);
+    }
+
+    #[test]
+    fn test_recovery_code_hash_ignores_formatting() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+
+        let code = &codes[0];
+        assert_eq!(
+            hash_recovery_code(code),
+            hash_recovery_code(&format!(" {} ", code.replace('-', "").to_uppercase()))
+        );
+    }
+
+    #[test]
+    fn test_provisioning_uri_escapes_labels() {
+        let uri = provisioning_uri("ABC", "Hotel Plus", "a@b.com");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Hotel%20Plus:a%40b%2Ecom?secret=ABC&issuer=Hotel%20Plus\
+             &algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::bookings::BookingDetails;
+use crate::promotions::PromoRejection;
+use crate::rooms::availability::BlockConflict;
+use crate::stay_rules::StayRestriction;
+
+#[derive(Debug, Clone)]
+pub struct AmendBookingOptions {
+    pub booking_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendBookingSuccess {
+    pub booking: BookingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum AmendBookingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    InvalidDateRange,
+    InvalidStatus,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+    TurnoverUnavailable { conflicts: Vec<BlockConflict> },
+    StayRestricted { restriction: StayRestriction },
+    PromoRejected { rejection: PromoRejection },
+}
+
+impl Display for AmendBookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AmendBookingError::Unauthorized => write!(f, "Unauthorized"),
+            AmendBookingError::InternalError => write!(f, "Internal Server Error"),
+            AmendBookingError::NotFound => write!(f, "Booking not found"),
+            AmendBookingError::InvalidDateRange => write!(f, "Invalid date range"),
+            AmendBookingError::InvalidStatus => {
+                write!(f, "Booking can no longer be changed")
+            }
+            AmendBookingError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the requested period")
+            }
+            AmendBookingError::TurnoverUnavailable { .. } => {
+                write!(f, "Room cannot be turned over before its next reservation")
+            }
+            AmendBookingError::StayRestricted { .. } => {
+                write!(f, "The stay is not allowed by the room class's stay rules")
+            }
+            AmendBookingError::PromoRejected { .. } => {
+                write!(
+                    f,
+                    "The booking's promo code can't be used for the new dates"
+                )
+            }
+        }
+    }
+}
+
+impl ResponseError for AmendBookingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AmendBookingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AmendBookingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            AmendBookingError::NotFound => StatusCode::NOT_FOUND,
+            AmendBookingError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            AmendBookingError::InvalidStatus => StatusCode::CONFLICT,
+            AmendBookingError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+            AmendBookingError::TurnoverUnavailable { .. } => StatusCode::CONFLICT,
+            AmendBookingError::StayRestricted { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AmendBookingError::PromoRejected { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
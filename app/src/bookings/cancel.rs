@@ -0,0 +1,54 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::bookings::BookingDetails;
+
+#[derive(Debug, Clone)]
+pub struct CancelBookingOptions {
+    pub booking_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelBookingSuccess {
+    pub booking: BookingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CancelBookingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    InvalidStatus,
+}
+
+impl Display for CancelBookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancelBookingError::Unauthorized => write!(f, "Unauthorized"),
+            CancelBookingError::InternalError => write!(f, "Internal Server Error"),
+            CancelBookingError::NotFound => write!(f, "Booking not found"),
+            CancelBookingError::InvalidStatus => {
+                write!(f, "Booking cannot be cancelled in its current status")
+            }
+        }
+    }
+}
+
+impl ResponseError for CancelBookingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CancelBookingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CancelBookingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CancelBookingError::NotFound => StatusCode::NOT_FOUND,
+            CancelBookingError::InvalidStatus => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,94 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::bookings::BookingDetails;
+use crate::promotions::PromoRejection;
+use crate::rooms::availability::BlockConflict;
+use crate::stay_rules::StayRestriction;
+
+fn one_guest() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBookingRequest {
+    pub room_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Staff may book on behalf of another user; guests always book for themselves.
+    pub guest_id: Option<Uuid>,
+    #[serde(default = "one_guest")]
+    pub guests: u32,
+    pub promo_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBookingSuccess {
+    pub booking: BookingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateBookingError {
+    Unauthorized,
+    InternalError,
+    InvalidDateRange,
+    InvalidGuests,
+    RoomNotFound,
+    GuestNotFound,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+    TurnoverUnavailable { conflicts: Vec<BlockConflict> },
+    StayRestricted { restriction: StayRestriction },
+    PromoRejected { rejection: PromoRejection },
+}
+
+impl Display for CreateBookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateBookingError::Unauthorized => write!(f, "Unauthorized"),
+            CreateBookingError::InternalError => write!(f, "Internal Server Error"),
+            CreateBookingError::InvalidDateRange => write!(f, "Invalid date range"),
+            CreateBookingError::InvalidGuests => write!(f, "At least one guest is required"),
+            CreateBookingError::RoomNotFound => write!(f, "Room not found"),
+            CreateBookingError::GuestNotFound => write!(f, "Guest not found"),
+            CreateBookingError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the requested period")
+            }
+            CreateBookingError::TurnoverUnavailable { .. } => {
+                write!(f, "Room cannot be turned over before its next reservation")
+            }
+            CreateBookingError::StayRestricted { .. } => {
+                write!(f, "The stay is not allowed by the room class's stay rules")
+            }
+            CreateBookingError::PromoRejected { .. } => {
+                write!(f, "Promo code can't be used for this stay")
+            }
+        }
+    }
+}
+
+impl ResponseError for CreateBookingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateBookingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateBookingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateBookingError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            CreateBookingError::InvalidGuests => StatusCode::BAD_REQUEST,
+            CreateBookingError::RoomNotFound => StatusCode::NOT_FOUND,
+            CreateBookingError::GuestNotFound => StatusCode::NOT_FOUND,
+            CreateBookingError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+            CreateBookingError::TurnoverUnavailable { .. } => StatusCode::CONFLICT,
+            CreateBookingError::StayRestricted { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            CreateBookingError::PromoRejected { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
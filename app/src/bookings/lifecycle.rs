@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::bookings::BookingStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BookingAction {
+    Confirm,
+    CheckIn,
+    CheckOut,
+    NoShow,
+    Cancel,
+}
+
+impl BookingStatus {
+    /// Status reached by applying `action`, or `None` if the lifecycle does not allow it.
+    ///
+    /// ```text
+    /// Pending ──confirm──▶ Confirmed ──check-in──▶ CheckedIn ──check-out──▶ CheckedOut
+    ///    │                    │    └───no-show───▶ NoShow
+    ///    └──────cancel────────┴──▶ Cancelled
+    /// ```
+    pub fn apply(self, action: BookingAction) -> Option<BookingStatus> {
+        match (self, action) {
+            (BookingStatus::Pending, BookingAction::Confirm) => Some(BookingStatus::Confirmed),
+            (BookingStatus::Pending | BookingStatus::Confirmed, BookingAction::Cancel) => {
+                Some(BookingStatus::Cancelled)
+            }
+            (BookingStatus::Confirmed, BookingAction::CheckIn) => Some(BookingStatus::CheckedIn),
+            (BookingStatus::Confirmed, BookingAction::NoShow) => Some(BookingStatus::NoShow),
+            (BookingStatus::CheckedIn, BookingAction::CheckOut) => Some(BookingStatus::CheckedOut),
+            _ => None,
+        }
+    }
+
+    /// Whether the stay can still have its dates or room changed.
+    pub fn is_modifiable(self) -> bool {
+        matches!(self, BookingStatus::Pending | BookingStatus::Confirmed)
+    }
+
+    /// Whether the booking's block should keep holding the room.
+    pub fn holds_room(self) -> bool {
+        !matches!(self, BookingStatus::Cancelled | BookingStatus::NoShow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_STATUSES: [BookingStatus; 6] = [
+        BookingStatus::Pending,
+        BookingStatus::Confirmed,
+        BookingStatus::CheckedIn,
+        BookingStatus::CheckedOut,
+        BookingStatus::NoShow,
+        BookingStatus::Cancelled,
+    ];
+
+    const ALL_ACTIONS: [BookingAction; 5] = [
+        BookingAction::Confirm,
+        BookingAction::CheckIn,
+        BookingAction::CheckOut,
+        BookingAction::NoShow,
+        BookingAction::Cancel,
+    ];
+
+    #[test]
+    fn test_happy_path() {
+        let status = BookingStatus::Pending;
+        let status = status.apply(BookingAction::Confirm).unwrap();
+        let status = status.apply(BookingAction::CheckIn).unwrap();
+        let status = status.apply(BookingAction::CheckOut).unwrap();
+        assert_eq!(status, BookingStatus::CheckedOut);
+    }
+
+    #[test]
+    fn test_illegal_transitions_are_rejected() {
+        assert_eq!(BookingStatus::Pending.apply(BookingAction::CheckOut), None);
+        assert_eq!(BookingStatus::Pending.apply(BookingAction::CheckIn), None);
+        assert_eq!(BookingStatus::CheckedIn.apply(BookingAction::Cancel), None);
+        assert_eq!(BookingStatus::Confirmed.apply(BookingAction::Confirm), None);
+    }
+
+    #[test]
+    fn test_final_states_have_no_transitions() {
+        for status in [
+            BookingStatus::CheckedOut,
+            BookingStatus::NoShow,
+            BookingStatus::Cancelled,
+        ] {
+            for action in ALL_ACTIONS {
+                assert_eq!(status.apply(action), None, "{:?} -> {:?}", status, action);
+            }
+        }
+    }
+
+    #[test]
+    fn test_only_released_states_free_the_room() {
+        let released: Vec<_> = ALL_STATUSES
+            .into_iter()
+            .filter(|status| !status.holds_room())
+            .collect();
+        assert_eq!(released, [BookingStatus::NoShow, BookingStatus::Cancelled]);
+    }
+}
@@ -0,0 +1,43 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod amend;
+pub mod cancel;
+pub mod create;
+pub mod lifecycle;
+pub mod move_room;
+pub mod transition;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BookingStatus {
+    Pending,
+    Confirmed,
+    CheckedIn,
+    CheckedOut,
+    NoShow,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingDetails {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub guest_id: Uuid,
+    pub status: BookingStatus,
+    #[schema(value_type = Vec<String>, example = json!(["2023-01-01T14:00:00Z", "2023-01-03T10:00:00Z"]))]
+    pub period: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+    /// When a pending booking stops holding the room
+    pub hold_expires_at: Option<DateTime<Utc>>,
+    /// The promo code the booking was made with
+    pub promo_code_id: Option<Uuid>,
+    /// What the promo code took off the room subtotal
+    #[schema(value_type = Option<String>)]
+    pub discount: Option<BigDecimal>,
+    pub guests: u32,
+}
@@ -0,0 +1,81 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::bookings::BookingDetails;
+use crate::promotions::PromoRejection;
+use crate::rooms::availability::BlockConflict;
+use crate::stay_rules::StayRestriction;
+
+#[derive(Debug, Clone)]
+pub struct MoveBookingOptions {
+    pub booking_id: Uuid,
+    pub room_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveBookingSuccess {
+    pub booking: BookingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum MoveBookingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    RoomNotFound,
+    InvalidStatus,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+    TurnoverUnavailable { conflicts: Vec<BlockConflict> },
+    StayRestricted { restriction: StayRestriction },
+    PromoRejected { rejection: PromoRejection },
+}
+
+impl Display for MoveBookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveBookingError::Unauthorized => write!(f, "Unauthorized"),
+            MoveBookingError::InternalError => write!(f, "Internal Server Error"),
+            MoveBookingError::NotFound => write!(f, "Booking not found"),
+            MoveBookingError::RoomNotFound => write!(f, "Room not found"),
+            MoveBookingError::InvalidStatus => {
+                write!(f, "Booking can no longer be changed")
+            }
+            MoveBookingError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the booked period")
+            }
+            MoveBookingError::TurnoverUnavailable { .. } => {
+                write!(f, "Room cannot be turned over before its next reservation")
+            }
+            MoveBookingError::StayRestricted { .. } => {
+                write!(f, "The stay is not allowed by the room class's stay rules")
+            }
+            MoveBookingError::PromoRejected { .. } => {
+                write!(f, "The booking's promo code can't be used for the new room")
+            }
+        }
+    }
+}
+
+impl ResponseError for MoveBookingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            MoveBookingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            MoveBookingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            MoveBookingError::NotFound => StatusCode::NOT_FOUND,
+            MoveBookingError::RoomNotFound => StatusCode::NOT_FOUND,
+            MoveBookingError::InvalidStatus => StatusCode::CONFLICT,
+            MoveBookingError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+            MoveBookingError::TurnoverUnavailable { .. } => StatusCode::CONFLICT,
+            MoveBookingError::StayRestricted { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            MoveBookingError::PromoRejected { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
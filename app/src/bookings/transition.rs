@@ -0,0 +1,76 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::bookings::{BookingDetails, BookingStatus, lifecycle::BookingAction};
+
+#[derive(Debug, Clone)]
+pub struct TransitionBookingOptions {
+    pub booking_id: Uuid,
+    pub action: BookingAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionBookingSuccess {
+    pub booking: BookingDetails,
+    pub transition: BookingTransition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingTransition {
+    pub from: BookingStatus,
+    pub to: BookingStatus,
+    pub performed_by: Option<Uuid>,
+    pub performed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum TransitionBookingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    IllegalTransition {
+        from: BookingStatus,
+        action: BookingAction,
+    },
+    /// Guests can't be checked in before their stay starts
+    #[serde(rename_all = "camelCase")]
+    NotStarted {
+        starts_at: DateTime<Utc>,
+    },
+}
+
+impl Display for TransitionBookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionBookingError::Unauthorized => write!(f, "Unauthorized"),
+            TransitionBookingError::InternalError => write!(f, "Internal Server Error"),
+            TransitionBookingError::NotFound => write!(f, "Booking not found"),
+            TransitionBookingError::IllegalTransition { from, action } => {
+                write!(f, "Cannot apply {:?} to a {:?} booking", action, from)
+            }
+            TransitionBookingError::NotStarted { .. } => write!(f, "The stay has not started yet"),
+        }
+    }
+}
+
+impl ResponseError for TransitionBookingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TransitionBookingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            TransitionBookingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            TransitionBookingError::NotFound => StatusCode::NOT_FOUND,
+            TransitionBookingError::IllegalTransition { .. } => StatusCode::CONFLICT,
+            TransitionBookingError::NotStarted { .. } => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
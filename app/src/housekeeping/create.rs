@@ -0,0 +1,69 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::housekeeping::HousekeepingDetails;
+use crate::rooms::availability::BlockConflict;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHousekeepingRequest {
+    pub room_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub instructions: Option<String>,
+    pub assignee_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateHousekeepingSuccess {
+    pub housekeeping: HousekeepingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateHousekeepingError {
+    Unauthorized,
+    InternalError,
+    InvalidDateRange,
+    RoomNotFound,
+    AssigneeNotFound,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+}
+
+impl Display for CreateHousekeepingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateHousekeepingError::Unauthorized => write!(f, "Unauthorized"),
+            CreateHousekeepingError::InternalError => write!(f, "Internal Server Error"),
+            CreateHousekeepingError::InvalidDateRange => {
+                write!(f, "Start date must be before end date")
+            }
+            CreateHousekeepingError::RoomNotFound => write!(f, "Room not found"),
+            CreateHousekeepingError::AssigneeNotFound => write!(f, "Assignee not found"),
+            CreateHousekeepingError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the requested period")
+            }
+        }
+    }
+}
+
+impl ResponseError for CreateHousekeepingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateHousekeepingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateHousekeepingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateHousekeepingError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            CreateHousekeepingError::RoomNotFound => StatusCode::NOT_FOUND,
+            CreateHousekeepingError::AssigneeNotFound => StatusCode::NOT_FOUND,
+            CreateHousekeepingError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
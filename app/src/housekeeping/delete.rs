@@ -0,0 +1,49 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::housekeeping::HousekeepingDetails;
+
+#[derive(Debug, Clone)]
+pub struct DeleteHousekeepingOptions {
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteHousekeepingSuccess {
+    pub housekeeping: HousekeepingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum DeleteHousekeepingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for DeleteHousekeepingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeleteHousekeepingError::Unauthorized => write!(f, "Unauthorized"),
+            DeleteHousekeepingError::InternalError => write!(f, "Internal Server Error"),
+            DeleteHousekeepingError::NotFound => write!(f, "Housekeeping task not found"),
+        }
+    }
+}
+
+impl ResponseError for DeleteHousekeepingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeleteHousekeepingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            DeleteHousekeepingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteHousekeepingError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,49 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::housekeeping::HousekeepingDetails;
+
+#[derive(Debug, Clone)]
+pub struct GetHousekeepingOptions {
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHousekeepingSuccess {
+    pub housekeeping: HousekeepingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum GetHousekeepingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for GetHousekeepingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GetHousekeepingError::Unauthorized => write!(f, "Unauthorized"),
+            GetHousekeepingError::InternalError => write!(f, "Internal Server Error"),
+            GetHousekeepingError::NotFound => write!(f, "Housekeeping task not found"),
+        }
+    }
+}
+
+impl ResponseError for GetHousekeepingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            GetHousekeepingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            GetHousekeepingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            GetHousekeepingError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
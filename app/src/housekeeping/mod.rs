@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod create;
+pub mod delete;
+pub mod get;
+pub mod update;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HousekeepingStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HousekeepingDetails {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub status: HousekeepingStatus,
+    #[schema(value_type = Vec<String>, example = json!(["2023-01-03T10:00:00Z", "2023-01-03T13:00:00Z"]))]
+    pub period: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+    pub instructions: Option<String>,
+    /// Staff member responsible for the task
+    pub assignee_id: Option<Uuid>,
+}
@@ -0,0 +1,73 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::housekeeping::{HousekeepingDetails, HousekeepingStatus};
+use crate::rooms::availability::BlockConflict;
+
+/// Fields left as `None` are not changed. `start` and `end` must be given together.
+#[derive(Debug, Clone)]
+pub struct UpdateHousekeepingOptions {
+    pub task_id: Uuid,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub status: Option<HousekeepingStatus>,
+    pub instructions: Option<String>,
+    pub assignee_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateHousekeepingSuccess {
+    pub housekeeping: HousekeepingDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum UpdateHousekeepingError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    InvalidDateRange,
+    AssigneeNotFound,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+}
+
+impl Display for UpdateHousekeepingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateHousekeepingError::Unauthorized => write!(f, "Unauthorized"),
+            UpdateHousekeepingError::InternalError => write!(f, "Internal Server Error"),
+            UpdateHousekeepingError::NotFound => write!(f, "Housekeeping task not found"),
+            UpdateHousekeepingError::InvalidDateRange => {
+                write!(
+                    f,
+                    "Start and end must be given together, with start before end"
+                )
+            }
+            UpdateHousekeepingError::AssigneeNotFound => write!(f, "Assignee not found"),
+            UpdateHousekeepingError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the requested period")
+            }
+        }
+    }
+}
+
+impl ResponseError for UpdateHousekeepingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UpdateHousekeepingError::Unauthorized => StatusCode::UNAUTHORIZED,
+            UpdateHousekeepingError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            UpdateHousekeepingError::NotFound => StatusCode::NOT_FOUND,
+            UpdateHousekeepingError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            UpdateHousekeepingError::AssigneeNotFound => StatusCode::NOT_FOUND,
+            UpdateHousekeepingError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -2,9 +2,18 @@ pub mod settings;
 pub use settings::*;
 
 pub mod api;
+pub mod api_keys;
 pub mod auth;
+pub mod bookings;
+pub mod housekeeping;
 pub mod interval;
+pub mod maintenance;
+pub mod notifications;
+pub mod pricing;
+pub mod promotions;
+pub mod reports;
 pub mod rooms;
+pub mod stay_rules;
 pub mod users;
 
 pub use actix_web;
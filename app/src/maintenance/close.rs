@@ -0,0 +1,52 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::maintenance::WorkOrder;
+
+#[derive(Debug, Clone)]
+pub struct CloseWorkOrderOptions {
+    pub work_order_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseWorkOrderSuccess {
+    pub work_order: WorkOrder,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CloseWorkOrderError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    AlreadyClosed,
+}
+
+impl Display for CloseWorkOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseWorkOrderError::Unauthorized => write!(f, "Unauthorized"),
+            CloseWorkOrderError::InternalError => write!(f, "Internal Server Error"),
+            CloseWorkOrderError::NotFound => write!(f, "Work order not found"),
+            CloseWorkOrderError::AlreadyClosed => write!(f, "Work order is already closed"),
+        }
+    }
+}
+
+impl ResponseError for CloseWorkOrderError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CloseWorkOrderError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CloseWorkOrderError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CloseWorkOrderError::NotFound => StatusCode::NOT_FOUND,
+            CloseWorkOrderError::AlreadyClosed => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,70 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::maintenance::{MaintenanceKind, MaintenanceSeverity, WorkOrder};
+use crate::rooms::availability::BlockConflict;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkOrderRequest {
+    pub room_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub kind: MaintenanceKind,
+    pub severity: MaintenanceSeverity,
+    pub assigner_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkOrderSuccess {
+    pub work_order: WorkOrder,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateWorkOrderError {
+    Unauthorized,
+    InternalError,
+    InvalidDateRange,
+    RoomNotFound,
+    StaffNotFound,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+}
+
+impl Display for CreateWorkOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateWorkOrderError::Unauthorized => write!(f, "Unauthorized"),
+            CreateWorkOrderError::InternalError => write!(f, "Internal Server Error"),
+            CreateWorkOrderError::InvalidDateRange => {
+                write!(f, "Start date must be before end date")
+            }
+            CreateWorkOrderError::RoomNotFound => write!(f, "Room not found"),
+            CreateWorkOrderError::StaffNotFound => write!(f, "Staff member not found"),
+            CreateWorkOrderError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the requested period")
+            }
+        }
+    }
+}
+
+impl ResponseError for CreateWorkOrderError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateWorkOrderError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateWorkOrderError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateWorkOrderError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            CreateWorkOrderError::RoomNotFound => StatusCode::NOT_FOUND,
+            CreateWorkOrderError::StaffNotFound => StatusCode::NOT_FOUND,
+            CreateWorkOrderError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,66 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::maintenance::WorkOrder;
+use crate::rooms::availability::BlockConflict;
+
+#[derive(Debug, Clone)]
+pub struct ExtendWorkOrderOptions {
+    pub work_order_id: Uuid,
+    /// Must fall after the current end of the work order
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendWorkOrderSuccess {
+    pub work_order: WorkOrder,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ExtendWorkOrderError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    InvalidDateRange,
+    AlreadyClosed,
+    RoomUnavailable { conflicts: Vec<BlockConflict> },
+}
+
+impl Display for ExtendWorkOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtendWorkOrderError::Unauthorized => write!(f, "Unauthorized"),
+            ExtendWorkOrderError::InternalError => write!(f, "Internal Server Error"),
+            ExtendWorkOrderError::NotFound => write!(f, "Work order not found"),
+            ExtendWorkOrderError::InvalidDateRange => {
+                write!(f, "New end must be after the current end")
+            }
+            ExtendWorkOrderError::AlreadyClosed => write!(f, "Work order is already closed"),
+            ExtendWorkOrderError::RoomUnavailable { .. } => {
+                write!(f, "Room is not available for the requested period")
+            }
+        }
+    }
+}
+
+impl ResponseError for ExtendWorkOrderError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ExtendWorkOrderError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ExtendWorkOrderError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ExtendWorkOrderError::NotFound => StatusCode::NOT_FOUND,
+            ExtendWorkOrderError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            ExtendWorkOrderError::AlreadyClosed => StatusCode::CONFLICT,
+            ExtendWorkOrderError::RoomUnavailable { .. } => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,52 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::maintenance::{MaintenanceKind, MaintenanceSeverity, WorkOrder};
+
+/// Filters for open work orders; unset fields match everything.
+#[derive(Debug, Clone)]
+pub struct ListWorkOrdersOptions {
+    pub room_id: Option<Uuid>,
+    pub kind: Option<MaintenanceKind>,
+    pub severity: Option<MaintenanceSeverity>,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkOrdersSuccess {
+    pub work_orders: Vec<WorkOrder>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListWorkOrdersError {
+    Unauthorized,
+    InternalError,
+}
+
+impl Display for ListWorkOrdersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListWorkOrdersError::Unauthorized => write!(f, "Unauthorized"),
+            ListWorkOrdersError::InternalError => write!(f, "Internal Server Error"),
+        }
+    }
+}
+
+impl ResponseError for ListWorkOrdersError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListWorkOrdersError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ListWorkOrdersError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
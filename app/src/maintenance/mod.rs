@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod close;
+pub mod create;
+pub mod extend;
+pub mod list;
+pub mod reassign;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MaintenanceKind {
+    Electrical,
+    Plumbing,
+    Structural,
+    Hvac,
+    FireSafety,
+    SecuritySystems,
+    Groundskeeping,
+    Janitorial,
+    PestControl,
+    ItNetwork,
+    Painting,
+    Appliances,
+    OutOfService,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MaintenanceSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkOrder {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub kind: MaintenanceKind,
+    pub severity: MaintenanceSeverity,
+    #[schema(value_type = Vec<String>, example = json!(["2023-01-01T08:00:00Z", "2023-01-01T17:00:00Z"]))]
+    pub period: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+    /// Staff member the work order is assigned to
+    pub assigner_id: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
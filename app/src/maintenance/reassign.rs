@@ -0,0 +1,56 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::maintenance::WorkOrder;
+
+#[derive(Debug, Clone)]
+pub struct ReassignWorkOrderOptions {
+    pub work_order_id: Uuid,
+    pub assigner_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReassignWorkOrderSuccess {
+    pub work_order: WorkOrder,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ReassignWorkOrderError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    StaffNotFound,
+    AlreadyClosed,
+}
+
+impl Display for ReassignWorkOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReassignWorkOrderError::Unauthorized => write!(f, "Unauthorized"),
+            ReassignWorkOrderError::InternalError => write!(f, "Internal Server Error"),
+            ReassignWorkOrderError::NotFound => write!(f, "Work order not found"),
+            ReassignWorkOrderError::StaffNotFound => write!(f, "Staff member not found"),
+            ReassignWorkOrderError::AlreadyClosed => write!(f, "Work order is already closed"),
+        }
+    }
+}
+
+impl ResponseError for ReassignWorkOrderError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ReassignWorkOrderError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ReassignWorkOrderError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ReassignWorkOrderError::NotFound => StatusCode::NOT_FOUND,
+            ReassignWorkOrderError::StaffNotFound => StatusCode::NOT_FOUND,
+            ReassignWorkOrderError::AlreadyClosed => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use std::{future::Future, pin::Pin};
+
+/// A message for a user that leaves the system through a [`NotificationSender`].
+#[derive(Debug, Clone)]
+pub enum Notification {
+    /// A one-time code for completing onboarding
+    Otp {
+        email: String,
+        code: String,
+        expires_at: DateTime<Utc>,
+    },
+    /// A one-time code for choosing a new password
+    PasswordReset {
+        email: String,
+        code: String,
+        expires_at: DateTime<Utc>,
+    },
+}
+
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Delivers notifications to users, e.g. by email or SMS.
+pub trait NotificationSender: Send + Sync {
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a>;
+}
@@ -0,0 +1,85 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::pricing::occupancy::OccupancyBand;
+
+/// Longest preview that can be asked for, in days.
+pub const MAX_CURVE_DAYS: u32 = 366;
+
+fn thirty_days() -> u32 {
+    30
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceCurveOptions {
+    pub class_id: Uuid,
+    /// Nights to preview, starting tonight; 30 when unset
+    #[serde(default = "thirty_days")]
+    pub days: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PricePoint {
+    /// The night starting on this date
+    pub date: NaiveDate,
+    /// Rooms of the class sold for the night
+    pub sold: i64,
+    /// The band reached, if any
+    pub band: Option<OccupancyBand>,
+    #[schema(value_type = String)]
+    pub price: BigDecimal,
+}
+
+/// What the base price of a room class comes to night by night as it fills up.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceCurve {
+    pub class_id: Uuid,
+    #[schema(value_type = String)]
+    pub base_price: BigDecimal,
+    pub rooms: i64,
+    pub points: Vec<PricePoint>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum PriceCurveError {
+    Unauthorized,
+    InternalError,
+    InvalidDays,
+    NotFound,
+}
+
+impl Display for PriceCurveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceCurveError::Unauthorized => write!(f, "Unauthorized"),
+            PriceCurveError::InternalError => write!(f, "Internal Server Error"),
+            PriceCurveError::InvalidDays => {
+                write!(f, "Days must be from 1 to {MAX_CURVE_DAYS}")
+            }
+            PriceCurveError::NotFound => write!(f, "Room class not found"),
+        }
+    }
+}
+
+impl ResponseError for PriceCurveError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PriceCurveError::Unauthorized => StatusCode::UNAUTHORIZED,
+            PriceCurveError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            PriceCurveError::InvalidDays => StatusCode::BAD_REQUEST,
+            PriceCurveError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
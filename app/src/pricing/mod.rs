@@ -0,0 +1,348 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod curve;
+pub mod occupancy;
+pub mod plans;
+pub mod quote;
+pub mod seasons;
+pub mod weekdays;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// ISO 8601 number of the day, as stored: 1 is Monday, 7 is Sunday.
+    pub fn number(self) -> i16 {
+        Self::ALL.iter().position(|day| *day == self).unwrap_or(0) as i16 + 1
+    }
+
+    pub fn from_number(number: i16) -> Option<Self> {
+        Self::ALL.get(usize::try_from(number - 1).ok()?).copied()
+    }
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        Self::ALL[day.num_days_from_monday() as usize]
+    }
+}
+
+/// The nights a stay is charged for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stay {
+    /// First night of the stay
+    pub check_in: NaiveDate,
+    /// Morning of departure; the night starting on it is not charged
+    pub check_out: NaiveDate,
+    pub guests: u32,
+}
+
+impl Stay {
+    /// Charges one night for every date, in UTC, the interval moves past.
+    ///
+    /// Returns `None` when `start` and `end` fall on the same date, as nothing can be charged.
+    pub fn from_interval(start: DateTime<Utc>, end: DateTime<Utc>, guests: u32) -> Option<Self> {
+        let check_in = start.date_naive();
+        let check_out = end.date_naive();
+
+        (check_in < check_out).then_some(Stay {
+            check_in,
+            check_out,
+            guests,
+        })
+    }
+
+    pub fn nights(&self) -> i64 {
+        (self.check_out - self.check_in).num_days()
+    }
+}
+
+/// A price for every night within a date range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    pub id: Uuid,
+    pub name: String,
+    /// First night of the season
+    pub starts_on: NaiveDate,
+    /// Last night of the season, included
+    pub ends_on: NaiveDate,
+    #[schema(value_type = String)]
+    pub nightly_price: BigDecimal,
+}
+
+impl Season {
+    pub fn covers(&self, night: NaiveDate) -> bool {
+        self.starts_on <= night && night <= self.ends_on
+    }
+}
+
+/// A price for the nights starting on one day of the week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekdayRule {
+    pub weekday: Weekday,
+    #[schema(value_type = String)]
+    pub nightly_price: BigDecimal,
+}
+
+/// Which rule set the price of a night.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PriceSource {
+    Base,
+    Weekday {
+        weekday: Weekday,
+    },
+    #[serde(rename_all = "camelCase")]
+    Season {
+        season_id: Uuid,
+        name: String,
+    },
+    /// The base price, adjusted for how full the room class is that night
+    #[serde(rename_all = "camelCase")]
+    Occupancy {
+        min_occupancy: i16,
+        #[schema(value_type = String)]
+        adjustment: BigDecimal,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NightlyPrice {
+    /// The night starting on this date
+    pub date: NaiveDate,
+    #[schema(value_type = String)]
+    pub price: BigDecimal,
+    pub source: PriceSource,
+}
+
+/// The rules of one rate plan, enough to price any night without the database.
+///
+/// A night takes the price of the season covering it, else the rule for its day of the week,
+/// else the base price. Seasons of a plan may not overlap; should they anyway, the one that
+/// starts last wins, then the one listed first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanPricing {
+    /// The plan's own base price, or the room class's when it has none
+    pub base_price: BigDecimal,
+    pub seasons: Vec<Season>,
+    pub weekday_rules: Vec<WeekdayRule>,
+}
+
+impl PlanPricing {
+    /// Prices the night starting on `night`.
+    pub fn price_night(&self, night: NaiveDate) -> NightlyPrice {
+        let season = self
+            .seasons
+            .iter()
+            .filter(|season| season.covers(night))
+            .reduce(|chosen, season| {
+                if season.starts_on > chosen.starts_on {
+                    season
+                } else {
+                    chosen
+                }
+            });
+
+        if let Some(season) = season {
+            return NightlyPrice {
+                date: night,
+                price: season.nightly_price.clone(),
+                source: PriceSource::Season {
+                    season_id: season.id,
+                    name: season.name.clone(),
+                },
+            };
+        }
+
+        let weekday = Weekday::from(night.weekday());
+        if let Some(rule) = self
+            .weekday_rules
+            .iter()
+            .find(|rule| rule.weekday == weekday)
+        {
+            return NightlyPrice {
+                date: night,
+                price: rule.nightly_price.clone(),
+                source: PriceSource::Weekday { weekday },
+            };
+        }
+
+        NightlyPrice {
+            date: night,
+            price: self.base_price.clone(),
+            source: PriceSource::Base,
+        }
+    }
+
+    /// Prices every night from `check_in` up to, but not including, `check_out`.
+    pub fn price_stay(&self, check_in: NaiveDate, check_out: NaiveDate) -> Vec<NightlyPrice> {
+        check_in
+            .iter_days()
+            .take_while(|night| *night < check_out)
+            .map(|night| self.price_night(night))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn money(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    fn season(name: &str, starts_on: &str, ends_on: &str, price: &str) -> Season {
+        Season {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            starts_on: date(starts_on),
+            ends_on: date(ends_on),
+            nightly_price: money(price),
+        }
+    }
+
+    fn pricing() -> PlanPricing {
+        PlanPricing {
+            base_price: money("100.00"),
+            seasons: vec![season("Summer", "2026-07-01", "2026-08-31", "150.00")],
+            weekday_rules: vec![
+                WeekdayRule {
+                    weekday: Weekday::Friday,
+                    nightly_price: money("120.00"),
+                },
+                WeekdayRule {
+                    weekday: Weekday::Saturday,
+                    nightly_price: money("130.00"),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_season_beats_weekday_beats_base() {
+        let pricing = pricing();
+
+        // Thursday 2026-06-25 to Monday 2026-06-29
+        let nights = pricing.price_stay(date("2026-06-25"), date("2026-06-29"));
+        let prices: Vec<_> = nights.iter().map(|night| night.price.clone()).collect();
+        assert_eq!(
+            prices,
+            [
+                money("100.00"),
+                money("120.00"),
+                money("130.00"),
+                money("100.00")
+            ]
+        );
+        assert_eq!(nights[0].source, PriceSource::Base);
+        assert_eq!(
+            nights[1].source,
+            PriceSource::Weekday {
+                weekday: Weekday::Friday
+            }
+        );
+
+        // Friday 2026-07-03 falls in the season
+        let night = pricing.price_night(date("2026-07-03"));
+        assert_eq!(night.price, money("150.00"));
+        assert!(matches!(night.source, PriceSource::Season { ref name, .. } if name == "Summer"));
+    }
+
+    #[test]
+    fn test_season_bounds_are_inclusive() {
+        let pricing = pricing();
+
+        assert_eq!(
+            pricing.price_night(date("2026-06-30")).price,
+            money("100.00")
+        );
+        assert_eq!(
+            pricing.price_night(date("2026-07-01")).price,
+            money("150.00")
+        );
+        assert_eq!(
+            pricing.price_night(date("2026-08-31")).price,
+            money("150.00")
+        );
+        assert_eq!(
+            pricing.price_night(date("2026-09-01")).price,
+            money("100.00")
+        );
+    }
+
+    #[test]
+    fn test_later_season_wins_an_overlap() {
+        let mut pricing = pricing();
+        pricing
+            .seasons
+            .push(season("Festival", "2026-08-10", "2026-08-12", "300.00"));
+
+        assert_eq!(
+            pricing.price_night(date("2026-08-11")).price,
+            money("300.00")
+        );
+        assert_eq!(
+            pricing.price_night(date("2026-08-13")).price,
+            money("150.00")
+        );
+    }
+
+    #[test]
+    fn test_stay_prices_each_night_once() {
+        let pricing = pricing();
+
+        assert!(
+            pricing
+                .price_stay(date("2026-06-25"), date("2026-06-25"))
+                .is_empty()
+        );
+        assert_eq!(
+            pricing
+                .price_stay(date("2026-06-25"), date("2026-06-26"))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_weekday_numbers_are_iso() {
+        assert_eq!(Weekday::Monday.number(), 1);
+        assert_eq!(Weekday::Sunday.number(), 7);
+        assert_eq!(Weekday::from_number(6), Some(Weekday::Saturday));
+        assert_eq!(Weekday::from_number(0), None);
+        assert_eq!(Weekday::from_number(8), None);
+        assert_eq!(Weekday::from(chrono::Weekday::Wed), Weekday::Wednesday);
+    }
+}
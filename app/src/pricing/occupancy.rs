@@ -0,0 +1,293 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::pricing::{NightlyPrice, PriceSource};
+
+/// A price adjustment that applies once enough of a room class is sold for a night.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OccupancyBand {
+    /// Percentage of the class's rooms sold for the night, from 1 to 100
+    pub min_occupancy: i16,
+    /// Percentage added to the base price; negative to discount
+    #[schema(value_type = String)]
+    pub adjustment: BigDecimal,
+}
+
+impl OccupancyBand {
+    /// Applies the adjustment to `price`, rounded half up to the cent.
+    pub fn adjust(&self, price: &BigDecimal) -> BigDecimal {
+        (price * (BigDecimal::from(100) + &self.adjustment) / BigDecimal::from(100))
+            .with_scale_round(2, RoundingMode::HalfUp)
+    }
+}
+
+/// How full a room class is night by night, with the bands that price it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Occupancy {
+    /// Rooms in the class
+    pub rooms: i64,
+    /// Rooms sold for each night; nights left out have none sold
+    pub sold: HashMap<NaiveDate, i64>,
+    pub bands: Vec<OccupancyBand>,
+}
+
+impl Occupancy {
+    pub fn sold_on(&self, night: NaiveDate) -> i64 {
+        self.sold.get(&night).copied().unwrap_or(0)
+    }
+
+    /// The band with the highest threshold reached on `night`, if any.
+    pub fn band_on(&self, night: NaiveDate) -> Option<&OccupancyBand> {
+        if self.rooms == 0 {
+            return None;
+        }
+
+        let sold = self.sold_on(night);
+        self.bands
+            .iter()
+            .filter(|band| sold * 100 >= i64::from(band.min_occupancy) * self.rooms)
+            .max_by_key(|band| band.min_occupancy)
+    }
+
+    /// Adjusts `night` by the band reached on its date.
+    ///
+    /// Only nights at the base price move; seasons and weekday rules are set by hand and kept.
+    pub fn adjust(&self, night: NightlyPrice) -> NightlyPrice {
+        if night.source != PriceSource::Base {
+            return night;
+        }
+
+        match self.band_on(night.date) {
+            Some(band) => NightlyPrice {
+                price: band.adjust(&night.price),
+                source: PriceSource::Occupancy {
+                    min_occupancy: band.min_occupancy,
+                    adjustment: band.adjustment.clone(),
+                },
+                ..night
+            },
+            None => night,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OccupancyBands {
+    pub class_id: Uuid,
+    /// Lowest threshold first
+    pub bands: Vec<OccupancyBand>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListOccupancyBandsOptions {
+    pub class_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListOccupancyBandsError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for ListOccupancyBandsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListOccupancyBandsError::Unauthorized => write!(f, "Unauthorized"),
+            ListOccupancyBandsError::InternalError => write!(f, "Internal Server Error"),
+            ListOccupancyBandsError::NotFound => write!(f, "Room class not found"),
+        }
+    }
+}
+
+impl ResponseError for ListOccupancyBandsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListOccupancyBandsError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ListOccupancyBandsError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ListOccupancyBandsError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetOccupancyBandsOptions {
+    pub class_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetOccupancyBandsRequest {
+    /// Replaces every band of the class; an empty list turns occupancy pricing off
+    pub bands: Vec<OccupancyBand>,
+}
+
+impl SetOccupancyBandsRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        let (lowest, highest) = (BigDecimal::from(-100), BigDecimal::from(1000));
+        let mut seen = HashSet::new();
+        for band in &self.bands {
+            if !(1..=100).contains(&band.min_occupancy) {
+                errors.add("bands", "minOccupancy must be from 1 to 100");
+            }
+            if !seen.insert(band.min_occupancy) {
+                errors.add("bands", "can't list a minOccupancy more than once");
+            }
+            if band.adjustment <= lowest || band.adjustment >= highest {
+                errors.add(
+                    "bands",
+                    "adjustment must be more than -100 and less than 1000",
+                );
+            }
+            if band.adjustment.fractional_digit_count() > 2 {
+                errors.add(
+                    "bands",
+                    "adjustment can't have more than two decimal places",
+                );
+            }
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum SetOccupancyBandsError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    Invalid(ValidationErrors),
+}
+
+impl Display for SetOccupancyBandsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOccupancyBandsError::Unauthorized => write!(f, "Unauthorized"),
+            SetOccupancyBandsError::InternalError => write!(f, "Internal Server Error"),
+            SetOccupancyBandsError::NotFound => write!(f, "Room class not found"),
+            SetOccupancyBandsError::Invalid(_) => write!(f, "Invalid occupancy bands"),
+        }
+    }
+}
+
+impl ResponseError for SetOccupancyBandsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SetOccupancyBandsError::Unauthorized => StatusCode::UNAUTHORIZED,
+            SetOccupancyBandsError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            SetOccupancyBandsError::NotFound => StatusCode::NOT_FOUND,
+            SetOccupancyBandsError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            SetOccupancyBandsError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn money(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    fn occupancy() -> Occupancy {
+        Occupancy {
+            rooms: 10,
+            sold: HashMap::from([
+                (date("2026-06-01"), 6),
+                (date("2026-06-02"), 7),
+                (date("2026-06-03"), 10),
+            ]),
+            bands: vec![
+                OccupancyBand {
+                    min_occupancy: 90,
+                    adjustment: money("25"),
+                },
+                OccupancyBand {
+                    min_occupancy: 70,
+                    adjustment: money("10"),
+                },
+            ],
+        }
+    }
+
+    fn base_night(date: NaiveDate) -> NightlyPrice {
+        NightlyPrice {
+            date,
+            price: money("99.99"),
+            source: PriceSource::Base,
+        }
+    }
+
+    #[test]
+    fn test_highest_band_reached_applies() {
+        let occupancy = occupancy();
+
+        assert_eq!(occupancy.band_on(date("2026-05-31")), None);
+        assert_eq!(occupancy.band_on(date("2026-06-01")), None);
+        assert_eq!(
+            occupancy.band_on(date("2026-06-02")).unwrap().min_occupancy,
+            70
+        );
+        assert_eq!(
+            occupancy.band_on(date("2026-06-03")).unwrap().min_occupancy,
+            90
+        );
+    }
+
+    #[test]
+    fn test_only_base_nights_are_adjusted() {
+        let occupancy = occupancy();
+
+        // 109.989 rounds half up
+        let night = occupancy.adjust(base_night(date("2026-06-02")));
+        assert_eq!(night.price, money("109.99"));
+        assert_eq!(
+            night.source,
+            PriceSource::Occupancy {
+                min_occupancy: 70,
+                adjustment: money("10"),
+            }
+        );
+
+        let unsold = occupancy.adjust(base_night(date("2026-06-01")));
+        assert_eq!(unsold.price, money("99.99"));
+
+        let weekday = NightlyPrice {
+            source: PriceSource::Weekday {
+                weekday: crate::pricing::Weekday::Wednesday,
+            },
+            ..base_night(date("2026-06-03"))
+        };
+        assert_eq!(occupancy.adjust(weekday.clone()), weekday);
+    }
+}
@@ -0,0 +1,185 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::pricing::{Season, WeekdayRule};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RatePlanDetails {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub name: String,
+    /// Falls back to the base price of the room class when unset
+    #[schema(value_type = Option<String>)]
+    pub base_price: Option<BigDecimal>,
+    pub refundable: bool,
+    pub includes_breakfast: bool,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// In date order
+    pub seasons: Vec<Season>,
+    /// Monday first
+    pub weekday_rules: Vec<WeekdayRule>,
+}
+
+/// Adds a message to `errors` if `price` is not a valid amount of money.
+pub(crate) fn check_price(errors: &mut ValidationErrors, field: &str, price: &BigDecimal) {
+    if *price < BigDecimal::zero() {
+        errors.add(field, "can't be negative");
+    }
+    if price.fractional_digit_count() > 2 {
+        errors.add(field, "can't have more than two decimal places");
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRatePlansOptions {
+    pub class_id: Option<Uuid>,
+    /// Archived plans are only listed when asked for
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRatePlansSuccess {
+    pub rate_plans: Vec<RatePlanDetails>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListRatePlansError {
+    InternalError,
+}
+
+impl Display for ListRatePlansError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListRatePlansError::InternalError => write!(f, "Internal Server Error"),
+        }
+    }
+}
+
+impl ResponseError for ListRatePlansError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListRatePlansError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRatePlanRequest {
+    pub class_id: Uuid,
+    /// e.g. "Flexible", "Non-refundable" or "Bed & Breakfast"
+    pub name: String,
+    /// Leave out to follow the base price of the room class
+    #[schema(value_type = Option<String>)]
+    pub base_price: Option<BigDecimal>,
+    #[serde(default = "default_refundable")]
+    pub refundable: bool,
+    #[serde(default)]
+    pub includes_breakfast: bool,
+}
+
+fn default_refundable() -> bool {
+    true
+}
+
+impl CreateRatePlanRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        if self.name.trim().is_empty() {
+            errors.add("name", "can't be blank");
+        }
+        if let Some(price) = &self.base_price {
+            check_price(&mut errors, "basePrice", price);
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateRatePlanError {
+    Unauthorized,
+    InternalError,
+    Invalid(ValidationErrors),
+}
+
+impl Display for CreateRatePlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateRatePlanError::Unauthorized => write!(f, "Unauthorized"),
+            CreateRatePlanError::InternalError => write!(f, "Internal Server Error"),
+            CreateRatePlanError::Invalid(_) => write!(f, "Invalid rate plan details"),
+        }
+    }
+}
+
+impl ResponseError for CreateRatePlanError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateRatePlanError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateRatePlanError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateRatePlanError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            CreateRatePlanError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveRatePlanOptions {
+    pub plan_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ArchiveRatePlanError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for ArchiveRatePlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveRatePlanError::Unauthorized => write!(f, "Unauthorized"),
+            ArchiveRatePlanError::InternalError => write!(f, "Internal Server Error"),
+            ArchiveRatePlanError::NotFound => write!(f, "Rate plan not found"),
+        }
+    }
+}
+
+impl ResponseError for ArchiveRatePlanError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ArchiveRatePlanError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ArchiveRatePlanError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ArchiveRatePlanError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
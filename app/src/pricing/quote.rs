@@ -0,0 +1,268 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::pricing::occupancy::Occupancy;
+use crate::pricing::{NightlyPrice, PlanPricing, Stay};
+use crate::promotions::{AppliedDiscount, PromoCodeDetails, PromoRejection};
+use crate::settings::TaxSettings;
+
+fn one_guest() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteOptions {
+    pub class_id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default = "one_guest")]
+    pub guests: u32,
+    /// Quotes the cheapest plan on sale for the class when unset
+    pub rate_plan_id: Option<Uuid>,
+    pub promo_code: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxLine {
+    pub name: String,
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+}
+
+/// What a stay costs, night by night.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub class_id: Uuid,
+    /// None when the class has no rate plans and its base price applies
+    pub rate_plan_id: Option<Uuid>,
+    pub check_in: NaiveDate,
+    pub check_out: NaiveDate,
+    pub guests: u32,
+    pub nights: Vec<NightlyPrice>,
+    #[schema(value_type = String)]
+    pub subtotal: BigDecimal,
+    /// Taken off the subtotal before taxes
+    pub discount: Option<AppliedDiscount>,
+    pub taxes: Vec<TaxLine>,
+    #[schema(value_type = String)]
+    pub total: BigDecimal,
+}
+
+impl Quote {
+    /// Prices `stay` under `pricing`, then adds `taxes` in order.
+    pub fn new(
+        class_id: Uuid,
+        rate_plan_id: Option<Uuid>,
+        pricing: &PlanPricing,
+        stay: &Stay,
+        taxes: &[TaxSettings],
+    ) -> Self {
+        let nights = pricing.price_stay(stay.check_in, stay.check_out);
+        let subtotal = nights
+            .iter()
+            .fold(BigDecimal::zero(), |sum, night| sum + &night.price);
+        let taxes = tax_lines(taxes, &subtotal, stay);
+        let total = taxes
+            .iter()
+            .fold(subtotal.clone(), |sum, line| sum + &line.amount);
+
+        Quote {
+            class_id,
+            rate_plan_id,
+            check_in: stay.check_in,
+            check_out: stay.check_out,
+            guests: stay.guests,
+            nights,
+            subtotal,
+            discount: None,
+            taxes,
+            total,
+        }
+    }
+
+    pub fn stay(&self) -> Stay {
+        Stay {
+            check_in: self.check_in,
+            check_out: self.check_out,
+            guests: self.guests,
+        }
+    }
+
+    /// Moves base price nights by the occupancy band reached on them, then works the totals out
+    /// again. Call it before applying a promo code, which is taken off the adjusted subtotal.
+    pub fn apply_occupancy(&mut self, occupancy: &Occupancy, taxes: &[TaxSettings]) {
+        self.nights = std::mem::take(&mut self.nights)
+            .into_iter()
+            .map(|night| occupancy.adjust(night))
+            .collect();
+        self.subtotal = self
+            .nights
+            .iter()
+            .fold(BigDecimal::zero(), |sum, night| sum + &night.price);
+        self.taxes = tax_lines(taxes, &self.subtotal, &self.stay());
+        self.total = self
+            .taxes
+            .iter()
+            .fold(self.subtotal.clone(), |sum, line| sum + &line.amount);
+    }
+
+    /// Takes `promo`'s discount off the subtotal and works the taxes out again on what is left.
+    pub fn apply_promo(&mut self, promo: &PromoCodeDetails, taxes: &[TaxSettings]) {
+        let amount = promo.discount(&self.subtotal);
+        let taxed = &self.subtotal - &amount;
+
+        self.taxes = tax_lines(taxes, &taxed, &self.stay());
+        self.total = self
+            .taxes
+            .iter()
+            .fold(taxed, |sum, line| sum + &line.amount);
+        self.discount = Some(AppliedDiscount {
+            promo_code_id: promo.id,
+            code: promo.code.clone(),
+            campaign: promo.campaign.clone(),
+            amount,
+        });
+    }
+}
+
+/// Works out each configured tax for a stay, rounded half up to the cent.
+pub fn tax_lines(taxes: &[TaxSettings], subtotal: &BigDecimal, stay: &Stay) -> Vec<TaxLine> {
+    taxes
+        .iter()
+        .map(|tax| match tax {
+            TaxSettings::Percentage { name, rate } => TaxLine {
+                name: name.clone(),
+                amount: subtotal * rate / BigDecimal::from(100),
+            },
+            TaxSettings::PerNight {
+                name,
+                amount,
+                per_guest,
+            } => {
+                let units = if *per_guest {
+                    stay.nights() * i64::from(stay.guests)
+                } else {
+                    stay.nights()
+                };
+                TaxLine {
+                    name: name.clone(),
+                    amount: amount * BigDecimal::from(units),
+                }
+            }
+        })
+        .map(|line| TaxLine {
+            amount: line.amount.with_scale_round(2, RoundingMode::HalfUp),
+            ..line
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub enum QuoteError {
+    InternalError,
+    InvalidDateRange,
+    InvalidGuests,
+    NotFound,
+    PromoRejected { rejection: PromoRejection },
+}
+
+impl Display for QuoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuoteError::InternalError => write!(f, "Internal Server Error"),
+            QuoteError::InvalidDateRange => write!(f, "Invalid date range"),
+            QuoteError::InvalidGuests => write!(f, "At least one guest is required"),
+            QuoteError::NotFound => write!(f, "Room class or rate plan not found"),
+            QuoteError::PromoRejected { .. } => write!(f, "Promo code can't be used for this stay"),
+        }
+    }
+}
+
+impl ResponseError for QuoteError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            QuoteError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            QuoteError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            QuoteError::InvalidGuests => StatusCode::BAD_REQUEST,
+            QuoteError::NotFound => StatusCode::NOT_FOUND,
+            QuoteError::PromoRejected { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn money(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    fn stay(guests: u32) -> Stay {
+        Stay {
+            check_in: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            check_out: NaiveDate::from_ymd_opt(2026, 3, 5).unwrap(),
+            guests,
+        }
+    }
+
+    fn taxes() -> Vec<TaxSettings> {
+        vec![
+            TaxSettings::Percentage {
+                name: "VAT".to_string(),
+                rate: money("12.5"),
+            },
+            TaxSettings::PerNight {
+                name: "City tax".to_string(),
+                amount: money("2.50"),
+                per_guest: true,
+            },
+            TaxSettings::PerNight {
+                name: "Resort fee".to_string(),
+                amount: money("10.00"),
+                per_guest: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_quote_adds_taxes_to_the_nightly_subtotal() {
+        let pricing = PlanPricing {
+            base_price: money("99.99"),
+            seasons: Vec::new(),
+            weekday_rules: Vec::new(),
+        };
+
+        let quote = Quote::new(Uuid::new_v4(), None, &pricing, &stay(2), &taxes());
+
+        assert_eq!(quote.nights.len(), 3);
+        assert_eq!(quote.subtotal, money("299.97"));
+        let amounts: Vec<_> = quote.taxes.iter().map(|line| line.amount.clone()).collect();
+        // 12.5% of 299.97 is 37.49625
+        assert_eq!(amounts, [money("37.50"), money("15.00"), money("30.00")]);
+        assert_eq!(quote.total, money("382.47"));
+    }
+
+    #[test]
+    fn test_stay_charges_dates_crossed() {
+        let start = DateTime::from_str("2026-03-02T14:00:00Z").unwrap();
+        let end = DateTime::from_str("2026-03-04T11:00:00Z").unwrap();
+        assert_eq!(Stay::from_interval(start, end, 1).unwrap().nights(), 2);
+
+        let late = DateTime::from_str("2026-03-02T20:00:00Z").unwrap();
+        assert_eq!(Stay::from_interval(start, late, 1), None);
+    }
+}
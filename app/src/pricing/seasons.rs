@@ -0,0 +1,118 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::pricing::plans::check_price;
+
+#[derive(Debug, Clone)]
+pub struct AddSeasonOptions {
+    pub plan_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddSeasonRequest {
+    pub name: String,
+    /// First night of the season
+    pub starts_on: NaiveDate,
+    /// Last night of the season, included
+    pub ends_on: NaiveDate,
+    #[schema(value_type = String)]
+    pub nightly_price: BigDecimal,
+}
+
+impl AddSeasonRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        if self.name.trim().is_empty() {
+            errors.add("name", "can't be blank");
+        }
+        if self.ends_on < self.starts_on {
+            errors.add("endsOn", "can't be before startsOn");
+        }
+        check_price(&mut errors, "nightlyPrice", &self.nightly_price);
+
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum AddSeasonError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    Invalid(ValidationErrors),
+}
+
+impl Display for AddSeasonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddSeasonError::Unauthorized => write!(f, "Unauthorized"),
+            AddSeasonError::InternalError => write!(f, "Internal Server Error"),
+            AddSeasonError::NotFound => write!(f, "Rate plan not found"),
+            AddSeasonError::Invalid(_) => write!(f, "Invalid season"),
+        }
+    }
+}
+
+impl ResponseError for AddSeasonError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AddSeasonError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AddSeasonError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            AddSeasonError::NotFound => StatusCode::NOT_FOUND,
+            AddSeasonError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            AddSeasonError::Invalid(errors) => HttpResponse::build(self.status_code()).json(errors),
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoveSeasonOptions {
+    pub plan_id: Uuid,
+    pub season_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub enum RemoveSeasonError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for RemoveSeasonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoveSeasonError::Unauthorized => write!(f, "Unauthorized"),
+            RemoveSeasonError::InternalError => write!(f, "Internal Server Error"),
+            RemoveSeasonError::NotFound => write!(f, "Season not found"),
+        }
+    }
+}
+
+impl ResponseError for RemoveSeasonError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RemoveSeasonError::Unauthorized => StatusCode::UNAUTHORIZED,
+            RemoveSeasonError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            RemoveSeasonError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,78 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::pricing::WeekdayRule;
+use crate::pricing::plans::check_price;
+
+#[derive(Debug, Clone)]
+pub struct SetWeekdayRulesOptions {
+    pub plan_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWeekdayRulesRequest {
+    /// Replaces every rule of the plan; days left out fall back to the base price
+    pub rules: Vec<WeekdayRule>,
+}
+
+impl SetWeekdayRulesRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        let mut seen = HashSet::new();
+        for rule in &self.rules {
+            if !seen.insert(rule.weekday) {
+                errors.add("rules", "can't list a day more than once");
+            }
+            check_price(&mut errors, "rules", &rule.nightly_price);
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum SetWeekdayRulesError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    Invalid(ValidationErrors),
+}
+
+impl Display for SetWeekdayRulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetWeekdayRulesError::Unauthorized => write!(f, "Unauthorized"),
+            SetWeekdayRulesError::InternalError => write!(f, "Internal Server Error"),
+            SetWeekdayRulesError::NotFound => write!(f, "Rate plan not found"),
+            SetWeekdayRulesError::Invalid(_) => write!(f, "Invalid weekday rules"),
+        }
+    }
+}
+
+impl ResponseError for SetWeekdayRulesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SetWeekdayRulesError::Unauthorized => StatusCode::UNAUTHORIZED,
+            SetWeekdayRulesError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            SetWeekdayRulesError::NotFound => StatusCode::NOT_FOUND,
+            SetWeekdayRulesError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            SetWeekdayRulesError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::pricing::plans::check_price;
+use crate::promotions::normalize_code;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePromoCodeRequest {
+    /// Letters, digits, `-` and `_`; matched regardless of case
+    pub code: String,
+    pub campaign: String,
+    /// Set exactly one of `percentOff` and `amountOff`
+    #[schema(value_type = Option<String>)]
+    pub percent_off: Option<BigDecimal>,
+    #[schema(value_type = Option<String>)]
+    pub amount_off: Option<BigDecimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_guest: Option<i32>,
+    pub min_nights: Option<i32>,
+    /// Leave empty for every room class
+    #[serde(default)]
+    pub class_ids: Vec<Uuid>,
+}
+
+impl CreatePromoCodeRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        let code = normalize_code(&self.code);
+        if code.is_empty() {
+            errors.add("code", "can't be blank");
+        } else if code.len() > 32 {
+            errors.add("code", "can't be longer than 32 characters");
+        } else if !code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            errors.add("code", "can only contain letters, digits, '-' and '_'");
+        }
+        if self.campaign.trim().is_empty() {
+            errors.add("campaign", "can't be blank");
+        }
+
+        match (&self.percent_off, &self.amount_off) {
+            (Some(percent), None) => {
+                let hundred = BigDecimal::from(100);
+                if *percent <= BigDecimal::zero() || percent > &hundred {
+                    errors.add("percentOff", "must be more than 0 and at most 100");
+                }
+                if percent.fractional_digit_count() > 2 {
+                    errors.add("percentOff", "can't have more than two decimal places");
+                }
+            }
+            (None, Some(amount)) => {
+                check_price(&mut errors, "amountOff", amount);
+                if amount.is_zero() {
+                    errors.add("amountOff", "must be more than 0");
+                }
+            }
+            (Some(_), Some(_)) => errors.add("amountOff", "can't be set with percentOff"),
+            (None, None) => errors.add("percentOff", "or amountOff is required"),
+        }
+
+        if let (Some(starts_at), Some(ends_at)) = (self.starts_at, self.ends_at)
+            && ends_at <= starts_at
+        {
+            errors.add("endsAt", "must be after the start");
+        }
+        for (field, value) in [
+            ("maxUses", self.max_uses),
+            ("maxUsesPerGuest", self.max_uses_per_guest),
+            ("minNights", self.min_nights),
+        ] {
+            if value.is_some_and(|value| value < 1) {
+                errors.add(field, "must be at least 1");
+            }
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreatePromoCodeError {
+    Unauthorized,
+    InternalError,
+    Invalid(ValidationErrors),
+}
+
+impl Display for CreatePromoCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreatePromoCodeError::Unauthorized => write!(f, "Unauthorized"),
+            CreatePromoCodeError::InternalError => write!(f, "Internal Server Error"),
+            CreatePromoCodeError::Invalid(_) => write!(f, "Invalid promo code"),
+        }
+    }
+}
+
+impl ResponseError for CreatePromoCodeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreatePromoCodeError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreatePromoCodeError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreatePromoCodeError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            CreatePromoCodeError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
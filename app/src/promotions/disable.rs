@@ -0,0 +1,40 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::Serialize;
+use std::fmt::Display;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct DisablePromoCodeOptions {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub enum DisablePromoCodeError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for DisablePromoCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisablePromoCodeError::Unauthorized => write!(f, "Unauthorized"),
+            DisablePromoCodeError::InternalError => write!(f, "Internal Server Error"),
+            DisablePromoCodeError::NotFound => write!(f, "Promo code not found"),
+        }
+    }
+}
+
+impl ResponseError for DisablePromoCodeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DisablePromoCodeError::Unauthorized => StatusCode::UNAUTHORIZED,
+            DisablePromoCodeError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            DisablePromoCodeError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,41 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+
+use crate::promotions::PromoCodeDetails;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPromoCodesSuccess {
+    /// Newest first, disabled codes included
+    pub promo_codes: Vec<PromoCodeDetails>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListPromoCodesError {
+    Unauthorized,
+    InternalError,
+}
+
+impl Display for ListPromoCodesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListPromoCodesError::Unauthorized => write!(f, "Unauthorized"),
+            ListPromoCodesError::InternalError => write!(f, "Internal Server Error"),
+        }
+    }
+}
+
+impl ResponseError for ListPromoCodesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListPromoCodesError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ListPromoCodesError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
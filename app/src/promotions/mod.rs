@@ -0,0 +1,260 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod create;
+pub mod disable;
+pub mod list;
+
+/// Puts a code in the form it is stored in, so guests can type it in any case.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PromoCodeDetails {
+    pub id: Uuid,
+    pub code: String,
+    /// The campaign revenue from the code is attributed to
+    pub campaign: String,
+    #[schema(value_type = Option<String>)]
+    pub percent_off: Option<BigDecimal>,
+    #[schema(value_type = Option<String>)]
+    pub amount_off: Option<BigDecimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_guest: Option<i32>,
+    pub min_nights: Option<i32>,
+    /// Room classes the code is limited to; every class when empty
+    pub class_ids: Vec<Uuid>,
+    /// Bookings holding the code, leaving out cancelled ones and no-shows
+    pub uses: i64,
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+/// Why a promo code can't be used for a stay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PromoRejection {
+    Unknown,
+    Disabled,
+    #[serde(rename_all = "camelCase")]
+    NotStarted {
+        starts_at: DateTime<Utc>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Expired {
+        ends_at: DateTime<Utc>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ClassNotEligible {
+        class_id: Uuid,
+    },
+    #[serde(rename_all = "camelCase")]
+    MinNights {
+        min_nights: i32,
+    },
+    UsageLimitReached,
+    GuestLimitReached,
+}
+
+/// A stay a promo code is being redeemed for.
+#[derive(Debug, Clone, Copy)]
+pub struct Redemption {
+    pub class_id: Uuid,
+    pub nights: i64,
+    pub at: DateTime<Utc>,
+    /// Bookings the guest already holds with the code; unknown for anonymous quotes
+    pub guest_uses: Option<i64>,
+}
+
+impl PromoCodeDetails {
+    /// Checks the code can be used for `redemption`, reporting the first condition it fails.
+    pub fn check(&self, redemption: &Redemption) -> Result<(), PromoRejection> {
+        if self.disabled_at.is_some() {
+            return Err(PromoRejection::Disabled);
+        }
+        if let Some(starts_at) = self.starts_at
+            && redemption.at < starts_at
+        {
+            return Err(PromoRejection::NotStarted { starts_at });
+        }
+        if let Some(ends_at) = self.ends_at
+            && redemption.at >= ends_at
+        {
+            return Err(PromoRejection::Expired { ends_at });
+        }
+        self.check_stay(redemption.class_id, redemption.nights)?;
+        if self.max_uses.is_some_and(|max| self.uses >= i64::from(max)) {
+            return Err(PromoRejection::UsageLimitReached);
+        }
+        if let (Some(max), Some(uses)) = (self.max_uses_per_guest, redemption.guest_uses)
+            && uses >= i64::from(max)
+        {
+            return Err(PromoRejection::GuestLimitReached);
+        }
+
+        Ok(())
+    }
+
+    /// Checks the conditions on the stay itself, which a booking holding the code must keep
+    /// meeting when its dates change.
+    pub fn check_stay(&self, class_id: Uuid, nights: i64) -> Result<(), PromoRejection> {
+        if !self.class_ids.is_empty() && !self.class_ids.contains(&class_id) {
+            return Err(PromoRejection::ClassNotEligible { class_id });
+        }
+        if let Some(min_nights) = self.min_nights
+            && nights < i64::from(min_nights)
+        {
+            return Err(PromoRejection::MinNights { min_nights });
+        }
+
+        Ok(())
+    }
+
+    /// What the code takes off `subtotal`, rounded half up to the cent and never more than it.
+    pub fn discount(&self, subtotal: &BigDecimal) -> BigDecimal {
+        let discount = match (&self.percent_off, &self.amount_off) {
+            (Some(percent), _) => subtotal * percent / BigDecimal::from(100),
+            (None, Some(amount)) => amount.clone(),
+            (None, None) => BigDecimal::zero(),
+        };
+
+        discount
+            .min(subtotal.clone())
+            .with_scale_round(2, RoundingMode::HalfUp)
+    }
+}
+
+/// A promo code applied to a quote or booking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedDiscount {
+    pub promo_code_id: Uuid,
+    pub code: String,
+    pub campaign: String,
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::str::FromStr;
+
+    fn money(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    fn promo() -> PromoCodeDetails {
+        PromoCodeDetails {
+            id: Uuid::new_v4(),
+            code: "SPRING".to_string(),
+            campaign: "Spring sale".to_string(),
+            percent_off: Some(money("15")),
+            amount_off: None,
+            starts_at: None,
+            ends_at: None,
+            max_uses: None,
+            max_uses_per_guest: None,
+            min_nights: None,
+            class_ids: Vec::new(),
+            uses: 0,
+            disabled_at: None,
+        }
+    }
+
+    fn redemption() -> Redemption {
+        Redemption {
+            class_id: Uuid::new_v4(),
+            nights: 2,
+            at: Utc::now(),
+            guest_uses: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_discount_is_rounded_and_capped() {
+        let percent = promo();
+        assert_eq!(percent.discount(&money("199.99")), money("30.00"));
+
+        let fixed = PromoCodeDetails {
+            percent_off: None,
+            amount_off: Some(money("50.00")),
+            ..promo()
+        };
+        assert_eq!(fixed.discount(&money("120.00")), money("50.00"));
+        assert_eq!(fixed.discount(&money("40.00")), money("40.00"));
+    }
+
+    #[test]
+    fn test_check_reports_the_failed_condition() {
+        let redemption = redemption();
+        assert_eq!(promo().check(&redemption), Ok(()));
+
+        let ended = redemption.at - Duration::hours(1);
+        let expired = PromoCodeDetails {
+            ends_at: Some(ended),
+            ..promo()
+        };
+        assert_eq!(
+            expired.check(&redemption),
+            Err(PromoRejection::Expired { ends_at: ended })
+        );
+
+        let elsewhere = PromoCodeDetails {
+            class_ids: vec![Uuid::new_v4()],
+            ..promo()
+        };
+        assert_eq!(
+            elsewhere.check(&redemption),
+            Err(PromoRejection::ClassNotEligible {
+                class_id: redemption.class_id
+            })
+        );
+
+        let long_stays = PromoCodeDetails {
+            min_nights: Some(3),
+            ..promo()
+        };
+        assert_eq!(
+            long_stays.check(&redemption),
+            Err(PromoRejection::MinNights { min_nights: 3 })
+        );
+
+        let used_up = PromoCodeDetails {
+            max_uses: Some(5),
+            uses: 5,
+            ..promo()
+        };
+        assert_eq!(
+            used_up.check(&redemption),
+            Err(PromoRejection::UsageLimitReached)
+        );
+
+        let once_each = PromoCodeDetails {
+            max_uses_per_guest: Some(1),
+            ..promo()
+        };
+        assert_eq!(
+            once_each.check(&Redemption {
+                guest_uses: Some(1),
+                ..redemption
+            }),
+            Err(PromoRejection::GuestLimitReached)
+        );
+        // Anonymous quotes can't be held to a per guest limit
+        assert_eq!(
+            once_each.check(&Redemption {
+                guest_uses: None,
+                ..redemption
+            }),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::reports::ReportDetails;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReportRequest {
+    pub block_id: Uuid,
+    pub title: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReportSuccess {
+    pub report: ReportDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateReportError {
+    Unauthorized,
+    InternalError,
+    BlockNotFound,
+}
+
+impl Display for CreateReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateReportError::Unauthorized => write!(f, "Unauthorized"),
+            CreateReportError::InternalError => write!(f, "Internal Server Error"),
+            CreateReportError::BlockNotFound => write!(f, "Block not found"),
+        }
+    }
+}
+
+impl ResponseError for CreateReportError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateReportError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateReportError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateReportError::BlockNotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,49 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::reports::ReportDetails;
+
+#[derive(Debug, Clone)]
+pub struct DeleteReportOptions {
+    pub report_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteReportSuccess {
+    pub report: ReportDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum DeleteReportError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for DeleteReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeleteReportError::Unauthorized => write!(f, "Unauthorized"),
+            DeleteReportError::InternalError => write!(f, "Internal Server Error"),
+            DeleteReportError::NotFound => write!(f, "Report not found"),
+        }
+    }
+}
+
+impl ResponseError for DeleteReportError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeleteReportError::Unauthorized => StatusCode::UNAUTHORIZED,
+            DeleteReportError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteReportError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,51 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::reports::ReportDetails;
+
+#[derive(Debug, Clone)]
+pub struct ListReportsOptions {
+    pub block_id: Uuid,
+    /// Staff only: also return soft-deleted reports
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListReportsSuccess {
+    pub reports: Vec<ReportDetails>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListReportsError {
+    Unauthorized,
+    InternalError,
+    BlockNotFound,
+}
+
+impl Display for ListReportsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListReportsError::Unauthorized => write!(f, "Unauthorized"),
+            ListReportsError::InternalError => write!(f, "Internal Server Error"),
+            ListReportsError::BlockNotFound => write!(f, "Block not found"),
+        }
+    }
+}
+
+impl ResponseError for ListReportsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListReportsError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ListReportsError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ListReportsError::BlockNotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,52 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::reports::ReportDetails;
+
+#[derive(Debug, Clone)]
+pub struct RestoreReportOptions {
+    pub report_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReportSuccess {
+    pub report: ReportDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum RestoreReportError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    NotDeleted,
+}
+
+impl Display for RestoreReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RestoreReportError::Unauthorized => write!(f, "Unauthorized"),
+            RestoreReportError::InternalError => write!(f, "Internal Server Error"),
+            RestoreReportError::NotFound => write!(f, "Report not found"),
+            RestoreReportError::NotDeleted => write!(f, "Report is not deleted"),
+        }
+    }
+}
+
+impl ResponseError for RestoreReportError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RestoreReportError::Unauthorized => StatusCode::UNAUTHORIZED,
+            RestoreReportError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            RestoreReportError::NotFound => StatusCode::NOT_FOUND,
+            RestoreReportError::NotDeleted => StatusCode::CONFLICT,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
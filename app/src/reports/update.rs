@@ -0,0 +1,52 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::reports::ReportDetails;
+
+/// Fields left as `None` are not changed.
+#[derive(Debug, Clone)]
+pub struct UpdateReportOptions {
+    pub report_id: Uuid,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateReportSuccess {
+    pub report: ReportDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub enum UpdateReportError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for UpdateReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateReportError::Unauthorized => write!(f, "Unauthorized"),
+            UpdateReportError::InternalError => write!(f, "Internal Server Error"),
+            UpdateReportError::NotFound => write!(f, "Report not found"),
+        }
+    }
+}
+
+impl ResponseError for UpdateReportError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UpdateReportError::Unauthorized => StatusCode::UNAUTHORIZED,
+            UpdateReportError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            UpdateReportError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
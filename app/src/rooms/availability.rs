@@ -31,12 +31,26 @@ pub struct CalendarBlock {
     #[serde(rename = "type")]
     pub kind: BlockKind,
     pub label: Option<String>,
+    /// Incident reports filed against the block, excluding deleted ones
+    pub report_count: i64,
+}
+
+/// A live block standing in the way of a write to the room's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockConflict {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub kind: BlockKind,
+    #[schema(value_type = Vec<String>)]
+    pub period: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum BlockKind {
     Booking,
+    Housekeeping,
     Maintenance,
     Unknown,
 }
@@ -1,15 +1,19 @@
 use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::stay_rules::StayRestriction;
+
 #[derive(Debug, Clone, Deserialize, IntoParams)]
 pub struct FindRoomOptions {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
     pub class_id: Option<Uuid>,
+    pub guests: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -24,12 +28,22 @@ pub struct RoomSummary {
     pub id: Uuid,
     pub label: String,
     pub class_id: Uuid,
+    /// The plan the stay was quoted under, if the class has any on sale
+    pub rate_plan_id: Option<Uuid>,
+    /// What the stay costs, taxes included; none when it spans no night
+    #[schema(value_type = Option<String>)]
+    pub quoted_total: Option<BigDecimal>,
 }
 
 #[derive(Debug, Serialize)]
 pub enum FindRoomError {
     InternalError,
     InvalidDateRange,
+    InvalidGuests,
+    /// The stay breaks a stay rule of the room class searched, or of every class with rooms free
+    StayRestricted {
+        restriction: StayRestriction,
+    },
 }
 
 impl Display for FindRoomError {
@@ -37,6 +51,10 @@ impl Display for FindRoomError {
         match self {
             FindRoomError::InternalError => write!(f, "Internal Server Error"),
             FindRoomError::InvalidDateRange => write!(f, "Invalid date range"),
+            FindRoomError::InvalidGuests => write!(f, "At least one guest is required"),
+            FindRoomError::StayRestricted { .. } => {
+                write!(f, "The stay is not allowed by the room class's stay rules")
+            }
         }
     }
 }
@@ -46,6 +64,8 @@ impl ResponseError for FindRoomError {
         match self {
             FindRoomError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
             FindRoomError::InvalidDateRange => StatusCode::BAD_REQUEST,
+            FindRoomError::InvalidGuests => StatusCode::BAD_REQUEST,
+            FindRoomError::StayRestricted { .. } => StatusCode::UNPROCESSABLE_ENTITY,
         }
     }
 
@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Deserializer};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerSettings {
@@ -21,7 +22,35 @@ pub struct ApplicationSettings {
 #[derive(Debug, Deserialize, Clone)]
 pub struct SecuritySettings {
     pub session_duration: u64,
+    /// 32-byte key that encrypts new session tokens
     pub key: String,
+    /// Retired keys, still accepted when decrypting tokens issued before a rotation
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub previous_keys: Vec<String>,
+    /// Seconds a session revocation check is trusted before asking the database again
+    pub revocation_cache_ttl: u64,
+    /// Seconds after sign-in past which a session is no longer refreshed
+    pub max_session_age: u64,
+}
+
+/// Accepts a list or a comma separated string, since environment variables can only be the latter.
+fn comma_separated<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Values {
+        List(Vec<String>),
+        Joined(String),
+    }
+
+    Ok(match Values::deserialize(deserializer)? {
+        Values::List(values) => values,
+        Values::Joined(values) => values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+            .collect(),
+    })
 }
 
 impl SecuritySettings {
@@ -37,9 +66,85 @@ pub struct AppSettings {
     pub application: ApplicationSettings,
     pub security: SecuritySettings,
     pub imagekit: ImageKitSettings,
+    pub bookings: BookingSettings,
+    pub otp: OtpSettings,
+    pub login: LoginSettings,
+    pub pricing: PricingSettings,
+    pub notifications: NotificationSettings,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ImageKitSettings {
     pub url: String,
 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BookingSettings {
+    /// Seconds a pending booking holds its room before it is released
+    pub hold_ttl: u64,
+    /// Seconds between sweeps for expired holds
+    pub sweep_interval: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OtpSettings {
+    /// Seconds an issued code stays valid
+    pub ttl: u64,
+    /// Seconds a user must wait between two codes
+    pub cooldown: u64,
+    /// Codes a user may request within an hour
+    pub hourly_limit: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LoginSettings {
+    /// Failed sign-ins an account may accumulate before it is locked
+    pub max_failures: u32,
+    /// Failed sign-ins a client address may accumulate before it is locked
+    pub max_ip_failures: u32,
+    /// Seconds the first lockout lasts; each further failure doubles it
+    pub lockout: u64,
+    /// Upper bound, in seconds, on a single lockout
+    pub max_lockout: u64,
+    /// Seconds without a failure after which the count starts over
+    pub reset_after: u64,
+    /// Seconds a user has to enter their TOTP code after the password was accepted
+    pub challenge_ttl: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PricingSettings {
+    /// Taxes and fees added to every quote, in the order they are listed
+    #[serde(default)]
+    pub taxes: Vec<TaxSettings>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaxSettings {
+    /// A share of the room subtotal, e.g. `rate = "12.5"` for 12.5%
+    Percentage { name: String, rate: BigDecimal },
+    /// A flat amount for every night, or every guest and night when `per_guest` is set
+    PerNight {
+        name: String,
+        amount: BigDecimal,
+        #[serde(default)]
+        per_guest: bool,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NotificationSettings {
+    pub sender: NotificationChannel,
+    /// File the `file` sender appends to
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    /// Writes notifications to the application log
+    Log,
+    /// Appends notifications to a local file
+    File,
+}
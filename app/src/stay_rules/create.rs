@@ -0,0 +1,93 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStayRuleRequest {
+    pub class_id: Uuid,
+    pub starts_on: NaiveDate,
+    /// Included
+    pub ends_on: NaiveDate,
+    pub min_nights: Option<i32>,
+    pub max_nights: Option<i32>,
+    #[serde(default)]
+    pub closed_to_arrival: bool,
+    #[serde(default)]
+    pub closed_to_departure: bool,
+}
+
+impl CreateStayRuleRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        if self.ends_on < self.starts_on {
+            errors.add("endsOn", "can't be before the start");
+        }
+        if self.min_nights.is_some_and(|nights| nights < 1) {
+            errors.add("minNights", "must be at least 1");
+        }
+        if self.max_nights.is_some_and(|nights| nights < 1) {
+            errors.add("maxNights", "must be at least 1");
+        }
+        if let (Some(min), Some(max)) = (self.min_nights, self.max_nights)
+            && max < min
+        {
+            errors.add("maxNights", "can't be less than the minimum");
+        }
+        if self.min_nights.is_none()
+            && self.max_nights.is_none()
+            && !self.closed_to_arrival
+            && !self.closed_to_departure
+        {
+            errors.add(
+                "minNights",
+                "can't be blank when nothing else is restricted",
+            );
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub enum CreateStayRuleError {
+    Unauthorized,
+    InternalError,
+    Invalid(ValidationErrors),
+}
+
+impl Display for CreateStayRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateStayRuleError::Unauthorized => write!(f, "Unauthorized"),
+            CreateStayRuleError::InternalError => write!(f, "Internal Server Error"),
+            CreateStayRuleError::Invalid(_) => write!(f, "Invalid stay rule"),
+        }
+    }
+}
+
+impl ResponseError for CreateStayRuleError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateStayRuleError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CreateStayRuleError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateStayRuleError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            CreateStayRuleError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
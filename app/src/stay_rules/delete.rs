@@ -0,0 +1,40 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::Serialize;
+use std::fmt::Display;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct DeleteStayRuleOptions {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub enum DeleteStayRuleError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for DeleteStayRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeleteStayRuleError::Unauthorized => write!(f, "Unauthorized"),
+            DeleteStayRuleError::InternalError => write!(f, "Internal Server Error"),
+            DeleteStayRuleError::NotFound => write!(f, "Stay rule not found"),
+        }
+    }
+}
+
+impl ResponseError for DeleteStayRuleError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeleteStayRuleError::Unauthorized => StatusCode::UNAUTHORIZED,
+            DeleteStayRuleError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteStayRuleError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
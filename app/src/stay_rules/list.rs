@@ -0,0 +1,45 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::stay_rules::StayRule;
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ListStayRulesOptions {
+    pub class_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListStayRulesSuccess {
+    /// In date order
+    pub stay_rules: Vec<StayRule>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum ListStayRulesError {
+    InternalError,
+}
+
+impl Display for ListStayRulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListStayRulesError::InternalError => write!(f, "Internal Server Error"),
+        }
+    }
+}
+
+impl ResponseError for ListStayRulesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListStayRulesError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -0,0 +1,178 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod create;
+pub mod delete;
+pub mod list;
+
+/// Restrictions on the stays a room class can be sold for, within a range of dates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StayRule {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub starts_on: NaiveDate,
+    /// Included
+    pub ends_on: NaiveDate,
+    /// Fewest nights for stays arriving within the dates
+    pub min_nights: Option<i32>,
+    /// Most nights for stays arriving within the dates
+    pub max_nights: Option<i32>,
+    /// No stay may start on one of the dates
+    pub closed_to_arrival: bool,
+    /// No stay may end on one of the dates
+    pub closed_to_departure: bool,
+}
+
+impl StayRule {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.starts_on <= date && date <= self.ends_on
+    }
+}
+
+/// The rule a stay breaks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StayRestriction {
+    #[serde(rename_all = "camelCase")]
+    ClosedToArrival { rule_id: Uuid, date: NaiveDate },
+    #[serde(rename_all = "camelCase")]
+    ClosedToDeparture { rule_id: Uuid, date: NaiveDate },
+    #[serde(rename_all = "camelCase")]
+    MinNights { rule_id: Uuid, min_nights: i32 },
+    #[serde(rename_all = "camelCase")]
+    MaxNights { rule_id: Uuid, max_nights: i32 },
+}
+
+/// Finds the first of `rules` broken by a stay from `check_in` to `check_out`.
+///
+/// Arrival and length rules are taken from the rules covering `check_in`, departure rules from
+/// those covering `check_out`. Closures are reported before length limits.
+pub fn check_stay(
+    rules: &[StayRule],
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> Option<StayRestriction> {
+    let nights = (check_out - check_in).num_days();
+    let arriving = || rules.iter().filter(move |rule| rule.covers(check_in));
+
+    if let Some(rule) = arriving().find(|rule| rule.closed_to_arrival) {
+        return Some(StayRestriction::ClosedToArrival {
+            rule_id: rule.id,
+            date: check_in,
+        });
+    }
+
+    if let Some(rule) = rules
+        .iter()
+        .find(|rule| rule.closed_to_departure && rule.covers(check_out))
+    {
+        return Some(StayRestriction::ClosedToDeparture {
+            rule_id: rule.id,
+            date: check_out,
+        });
+    }
+
+    if let Some((rule, min_nights)) = arriving()
+        .filter_map(|rule| Some((rule, rule.min_nights?)))
+        .find(|(_, min_nights)| nights < i64::from(*min_nights))
+    {
+        return Some(StayRestriction::MinNights {
+            rule_id: rule.id,
+            min_nights,
+        });
+    }
+
+    arriving()
+        .filter_map(|rule| Some((rule, rule.max_nights?)))
+        .find(|(_, max_nights)| nights > i64::from(*max_nights))
+        .map(|(rule, max_nights)| StayRestriction::MaxNights {
+            rule_id: rule.id,
+            max_nights,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    fn rule(starts_on: &str, ends_on: &str) -> StayRule {
+        StayRule {
+            id: Uuid::new_v4(),
+            class_id: Uuid::new_v4(),
+            starts_on: date(starts_on),
+            ends_on: date(ends_on),
+            min_nights: None,
+            max_nights: None,
+            closed_to_arrival: false,
+            closed_to_departure: false,
+        }
+    }
+
+    #[test]
+    fn test_length_limits_follow_the_arrival_date() {
+        let rules = vec![StayRule {
+            min_nights: Some(3),
+            max_nights: Some(7),
+            ..rule("2026-12-20", "2026-12-31")
+        }];
+
+        assert!(matches!(
+            check_stay(&rules, date("2026-12-30"), date("2027-01-01")),
+            Some(StayRestriction::MinNights { min_nights: 3, .. })
+        ));
+        assert!(matches!(
+            check_stay(&rules, date("2026-12-20"), date("2026-12-28")),
+            Some(StayRestriction::MaxNights { max_nights: 7, .. })
+        ));
+        assert_eq!(
+            check_stay(&rules, date("2026-12-29"), date("2027-01-01")),
+            None
+        );
+        // Arriving before the rule starts is not limited, even if the stay runs into it
+        assert_eq!(
+            check_stay(&rules, date("2026-12-18"), date("2026-12-21")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_closures_apply_to_the_matching_end_of_the_stay() {
+        let sunday = StayRule {
+            closed_to_arrival: true,
+            ..rule("2026-06-07", "2026-06-07")
+        };
+        let monday = StayRule {
+            closed_to_departure: true,
+            ..rule("2026-06-08", "2026-06-08")
+        };
+        let rules = vec![sunday.clone(), monday.clone()];
+
+        assert_eq!(
+            check_stay(&rules, date("2026-06-07"), date("2026-06-09")),
+            Some(StayRestriction::ClosedToArrival {
+                rule_id: sunday.id,
+                date: date("2026-06-07"),
+            })
+        );
+        assert_eq!(
+            check_stay(&rules, date("2026-06-06"), date("2026-06-08")),
+            Some(StayRestriction::ClosedToDeparture {
+                rule_id: monday.id,
+                date: date("2026-06-08"),
+            })
+        );
+        // Staying through closed dates is fine
+        assert_eq!(
+            check_stay(&rules, date("2026-06-06"), date("2026-06-09")),
+            None
+        );
+    }
+}
@@ -24,6 +24,7 @@ pub struct UserDetails {
 
 #[derive(Debug, Serialize)]
 pub enum GetUserDetailsError {
+    Unauthorized,
     InternalError,
     NotFound,
 }
@@ -31,6 +32,7 @@ pub enum GetUserDetailsError {
 impl Display for GetUserDetailsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            GetUserDetailsError::Unauthorized => write!(f, "Unauthorized"),
             GetUserDetailsError::InternalError => write!(f, "Internal Server Error"),
             GetUserDetailsError::NotFound => write!(f, "User not found"),
         }
@@ -40,6 +42,7 @@ impl Display for GetUserDetailsError {
 impl ResponseError for GetUserDetailsError {
     fn status_code(&self) -> StatusCode {
         match self {
+            GetUserDetailsError::Unauthorized => StatusCode::UNAUTHORIZED,
             GetUserDetailsError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
             GetUserDetailsError::NotFound => StatusCode::NOT_FOUND,
         }
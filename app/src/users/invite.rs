@@ -0,0 +1,76 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::users::{details::UserDetails, is_valid_email};
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteUserRequest {
+    pub email: String,
+    /// Also give the invitee a staff record
+    #[serde(default)]
+    pub staff: bool,
+}
+
+impl InviteUserRequest {
+    /// Checks the fields that can be judged without the database.
+    pub fn validate(&self) -> ValidationErrors {
+        let mut errors = ValidationErrors::default();
+
+        if !is_valid_email(self.email.trim()) {
+            errors.add("email", "is not a valid email address");
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteUserSuccess {
+    pub user: UserDetails,
+    pub staff_id: Option<Uuid>,
+    /// When the onboarding code sent to the invitee stops working
+    pub otp_expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum InviteUserError {
+    Unauthorized,
+    InternalError,
+    Invalid(ValidationErrors),
+}
+
+impl Display for InviteUserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InviteUserError::Unauthorized => write!(f, "Unauthorized"),
+            InviteUserError::InternalError => write!(f, "Internal Server Error"),
+            InviteUserError::Invalid(_) => write!(f, "Invalid invitation details"),
+        }
+    }
+}
+
+impl ResponseError for InviteUserError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            InviteUserError::Unauthorized => StatusCode::UNAUTHORIZED,
+            InviteUserError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            InviteUserError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            InviteUserError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct UnlockAccountOptions {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockAccountSuccess {
+    /// Whether the account had failed sign-ins on record, locked or not
+    pub had_failures: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub enum UnlockAccountError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for UnlockAccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnlockAccountError::Unauthorized => write!(f, "Unauthorized"),
+            UnlockAccountError::InternalError => write!(f, "Internal Server Error"),
+            UnlockAccountError::NotFound => write!(f, "User not found"),
+        }
+    }
+}
+
+impl ResponseError for UnlockAccountError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UnlockAccountError::Unauthorized => StatusCode::UNAUTHORIZED,
+            UnlockAccountError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            UnlockAccountError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
@@ -1 +1,20 @@
 pub mod details;
+pub mod invite;
+pub mod lockout;
+pub mod roles;
+pub mod sessions;
+
+/// Loose shape check for an email address; deliverability is proven by the OTP flow.
+pub fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,68 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ValidationErrors;
+use crate::auth::Permission;
+
+#[derive(Debug, Clone)]
+pub struct AssignRolesOptions {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignRolesRequest {
+    /// Replaces every role the user had; an empty list makes them a guest
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignRolesSuccess {
+    pub user_id: Uuid,
+    pub roles: Vec<String>,
+    /// What the roles allow, applied from the user's next sign-in
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum AssignRolesError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+    Invalid(ValidationErrors),
+}
+
+impl Display for AssignRolesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssignRolesError::Unauthorized => write!(f, "Unauthorized"),
+            AssignRolesError::InternalError => write!(f, "Internal Server Error"),
+            AssignRolesError::NotFound => write!(f, "User not found"),
+            AssignRolesError::Invalid(_) => write!(f, "Invalid roles"),
+        }
+    }
+}
+
+impl ResponseError for AssignRolesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AssignRolesError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AssignRolesError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            AssignRolesError::NotFound => StatusCode::NOT_FOUND,
+            AssignRolesError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            AssignRolesError::Invalid(errors) => {
+                HttpResponse::build(self.status_code()).json(errors)
+            }
+            _ => HttpResponse::build(self.status_code()).json(self),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct RevokeSessionsOptions {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeSessionsSuccess {
+    /// Sessions issued before this instant are refused
+    pub revoked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub enum RevokeSessionsError {
+    Unauthorized,
+    InternalError,
+    NotFound,
+}
+
+impl Display for RevokeSessionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevokeSessionsError::Unauthorized => write!(f, "Unauthorized"),
+            RevokeSessionsError::InternalError => write!(f, "Internal Server Error"),
+            RevokeSessionsError::NotFound => write!(f, "User not found"),
+        }
+    }
+}
+
+impl ResponseError for RevokeSessionsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RevokeSessionsError::Unauthorized => StatusCode::UNAUTHORIZED,
+            RevokeSessionsError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            RevokeSessionsError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
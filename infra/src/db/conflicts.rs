@@ -0,0 +1,92 @@
+use std::ops::Bound;
+
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, result::Error};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use app::rooms::availability::{BlockConflict, BlockKind};
+
+use crate::{
+    db::errors::is_overlap_violation,
+    models::{Block, BlockKind as DbBlockKind},
+    schema::{blocks, housekeeping},
+};
+
+impl From<DbBlockKind> for BlockKind {
+    fn from(kind: DbBlockKind) -> Self {
+        match kind {
+            DbBlockKind::Booking => BlockKind::Booking,
+            DbBlockKind::Housekeeping => BlockKind::Housekeeping,
+            DbBlockKind::Maintenance => BlockKind::Maintenance,
+        }
+    }
+}
+
+/// Where a block was headed when it was written.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    /// The block being moved, if it already exists
+    pub block_id: Option<Uuid>,
+    pub room_id: Uuid,
+    pub interval: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+}
+
+/// A failed block write, keeping the placement when Postgres rejected it as an overlap.
+pub enum BlockWriteError {
+    Overlap(Placement),
+    Database(Error),
+}
+
+impl Placement {
+    /// Classifies `error` raised while writing this placement.
+    pub fn reject(self, error: Error) -> BlockWriteError {
+        if is_overlap_violation(&error) {
+            BlockWriteError::Overlap(self)
+        } else {
+            BlockWriteError::Database(error)
+        }
+    }
+}
+
+/// Lists the live blocks that stand in the way of `placement`.
+///
+/// Call this after the failed transaction has rolled back. The moved block and the turnover
+/// scheduled after it are left out, since they are back in place and would only report
+/// themselves. The lookup is best effort: if it fails the conflict stands with an empty list.
+pub async fn find_conflicts(
+    conn: &mut AsyncPgConnection,
+    placement: &Placement,
+) -> Vec<BlockConflict> {
+    let mut query = blocks::table
+        .filter(blocks::room_id.eq(placement.room_id))
+        .filter(blocks::released_at.is_null())
+        .filter(blocks::interval.overlaps_with(placement.interval))
+        .select(Block::as_select())
+        .order(blocks::interval.asc())
+        .into_boxed();
+
+    if let Some(block_id) = placement.block_id {
+        query = query.filter(blocks::id.ne(block_id)).filter(
+            blocks::id.ne_all(
+                housekeeping::table
+                    .filter(housekeeping::booking_id.eq(block_id))
+                    .select(housekeeping::block_id),
+            ),
+        );
+    }
+
+    let blocks: Vec<Block> = match query.load(conn).await {
+        Ok(blocks) => blocks,
+        Err(_) => return Vec::new(),
+    };
+
+    blocks
+        .into_iter()
+        .map(|block| BlockConflict {
+            id: block.id,
+            kind: block.kind.map_or(BlockKind::Unknown, BlockKind::from),
+            period: block.interval,
+        })
+        .collect()
+}
@@ -0,0 +1,56 @@
+use diesel::result::Error;
+
+/// Exclusion constraint on `blocks` that keeps two blocks on the same room from overlapping.
+pub const NO_OVERLAPS: &str = "no_overlaps";
+
+/// Raised at commit when a block does not have exactly one child row matching its kind.
+pub const BLOCK_CHILD_INTEGRITY: &str = "block_child_integrity";
+
+/// Returns true when `error` is Postgres rejecting a block because it overlaps another one.
+///
+/// diesel-async reports exclusion violations as `DatabaseErrorKind::Unknown`, so the
+/// constraint name is the only reliable signal.
+pub fn is_overlap_violation(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::DatabaseError(_, info) if info.constraint_name() == Some(NO_OVERLAPS)
+    )
+}
+
+/// Returns true when `error` is the commit-time check that pairs each block with its child.
+pub fn is_block_integrity_violation(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::DatabaseError(_, info) if info.constraint_name() == Some(BLOCK_CHILD_INTEGRITY)
+    )
+}
+
+/// Returns the name of the foreign key constraint `error` violated, if any.
+pub fn violated_foreign_key(error: &Error) -> Option<&str> {
+    match error {
+        Error::DatabaseError(diesel::result::DatabaseErrorKind::ForeignKeyViolation, info) => {
+            info.constraint_name()
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of the unique constraint `error` violated, if any.
+pub fn violated_unique(error: &Error) -> Option<&str> {
+    match error {
+        Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, info) => {
+            info.constraint_name()
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of the constraint `error` violated, whatever its kind.
+///
+/// Needed for exclusion constraints, which diesel-async reports as `DatabaseErrorKind::Unknown`.
+pub fn violated_constraint(error: &Error) -> Option<&str> {
+    match error {
+        Error::DatabaseError(_, info) => info.constraint_name(),
+        _ => None,
+    }
+}
@@ -1,3 +1,6 @@
+pub mod conflicts;
+pub mod errors;
+
 use app::settings::DatabaseSettings;
 use diesel::result::ConnectionError;
 use diesel_async::{
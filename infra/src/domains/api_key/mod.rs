@@ -0,0 +1,215 @@
+use crate::db::DbPool;
+use crate::domains::auth;
+use crate::models::{ApiKey, NewApiKey};
+use crate::schema::{api_keys, staff, users};
+use app::actix_web::HttpResponse;
+use app::actix_web::http::StatusCode;
+use app::api::ApiResponse;
+use app::api_keys::create::*;
+use app::api_keys::list::*;
+use app::api_keys::revoke::*;
+use app::api_keys::{ApiKeyDetails, ApiKeyPrincipal, generate_api_key, hash_api_key};
+use app::auth::{AuthError, Permission, SessionUser};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+/// Characters of the secret kept in the clear, `hsk_` included.
+const VISIBLE_PREFIX_LEN: usize = 12;
+
+fn details(key: ApiKey) -> ApiKeyDetails {
+    ApiKeyDetails {
+        id: key.id,
+        name: key.name,
+        prefix: key.key_prefix,
+        scopes: key.scopes,
+        created_by: key.created_by,
+        created_at: key.created_at,
+        expires_at: key.expires_at,
+        last_used_at: key.last_used_at,
+        revoked_at: key.revoked_at,
+    }
+}
+
+/// Issues a key that acts on behalf of the calling staff member within `scopes`.
+pub async fn create(
+    pool: &DbPool,
+    request: CreateApiKeyRequest,
+    user: &SessionUser,
+) -> ApiResponse<CreateApiKeySuccess, CreateApiKeyError> {
+    let Some(staff_id) = user
+        .staff_id
+        .filter(|_| user.can(Permission::ManageApiKeys))
+    else {
+        return ApiResponse::error(CreateApiKeyError::Unauthorized);
+    };
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(CreateApiKeyError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateApiKeyError::InternalError),
+    };
+
+    let secret = generate_api_key();
+    let mut scopes = request.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    let key: ApiKey = match diesel::insert_into(api_keys::table)
+        .values(&NewApiKey {
+            name: request.name.trim(),
+            key_hash: &hash_api_key(&secret),
+            key_prefix: &secret[..VISIBLE_PREFIX_LEN],
+            scopes: &scopes,
+            created_by: staff_id,
+            expires_at: request.expires_at,
+        })
+        .get_result(&mut conn)
+        .await
+    {
+        Ok(key) => key,
+        Err(_) => return ApiResponse::error(CreateApiKeyError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::CREATED,
+        CreateApiKeySuccess {
+            api_key: details(key),
+            key: secret,
+        },
+    ))
+}
+
+pub async fn list(
+    pool: &DbPool,
+    user: &SessionUser,
+) -> ApiResponse<ListApiKeysSuccess, ListApiKeysError> {
+    if !user.can(Permission::ManageApiKeys) {
+        return ApiResponse::error(ListApiKeysError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListApiKeysError::InternalError),
+    };
+
+    let keys: Vec<ApiKey> = match api_keys::table
+        .order(api_keys::created_at.desc())
+        .load(&mut conn)
+        .await
+    {
+        Ok(keys) => keys,
+        Err(_) => return ApiResponse::error(ListApiKeysError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        ListApiKeysSuccess {
+            api_keys: keys.into_iter().map(details).collect(),
+        },
+    ))
+}
+
+/// Stops a key from authenticating. Revoking a revoked key keeps the original revocation time.
+pub async fn revoke(
+    pool: &DbPool,
+    options: RevokeApiKeyOptions,
+    user: &SessionUser,
+) -> ApiResponse<RevokeApiKeySuccess, RevokeApiKeyError> {
+    if !user.can(Permission::ManageApiKeys) {
+        return ApiResponse::error(RevokeApiKeyError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(RevokeApiKeyError::InternalError),
+    };
+
+    let revoked: Option<ApiKey> = match diesel::update(
+        api_keys::table
+            .find(options.id)
+            .filter(api_keys::revoked_at.is_null()),
+    )
+    .set(api_keys::revoked_at.eq(Utc::now()))
+    .get_result(&mut conn)
+    .await
+    .optional()
+    {
+        Ok(key) => key,
+        Err(_) => return ApiResponse::error(RevokeApiKeyError::InternalError),
+    };
+
+    let key = match revoked {
+        Some(key) => key,
+        None => match api_keys::table.find(options.id).first(&mut conn).await {
+            Ok(key) => key,
+            Err(diesel::result::Error::NotFound) => {
+                return ApiResponse::error(RevokeApiKeyError::NotFound);
+            }
+            Err(_) => return ApiResponse::error(RevokeApiKeyError::InternalError),
+        },
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        RevokeApiKeySuccess {
+            api_key: details(key),
+        },
+    ))
+}
+
+/// Resolves a presented secret to the key's principal, recording the use.
+///
+/// Returns `None` for unknown, revoked and expired keys alike.
+pub async fn authenticate(pool: &DbPool, key: &str) -> Result<Option<ApiKeyPrincipal>, AuthError> {
+    let mut conn = pool.get().await.map_err(|_| AuthError::InternalError)?;
+
+    let now = Utc::now();
+    let key: Option<ApiKey> = diesel::update(
+        api_keys::table
+            .filter(api_keys::key_hash.eq(hash_api_key(key)))
+            .filter(api_keys::revoked_at.is_null())
+            .filter(
+                api_keys::expires_at
+                    .is_null()
+                    .or(api_keys::expires_at.gt(now)),
+            ),
+    )
+    .set(api_keys::last_used_at.eq(now))
+    .get_result(&mut conn)
+    .await
+    .optional()
+    .map_err(|_| AuthError::InternalError)?;
+
+    let Some(key) = key else {
+        return Ok(None);
+    };
+
+    let (user_id, email): (Uuid, String) = staff::table
+        .inner_join(users::table)
+        .filter(staff::id.eq(key.created_by))
+        .select((users::id, users::email))
+        .first(&mut conn)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    let permissions = auth::find_permissions(&mut conn, user_id)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    Ok(Some(ApiKeyPrincipal {
+        key_id: key.id,
+        user: SessionUser {
+            id: user_id,
+            staff_id: Some(key.created_by),
+            email,
+            permissions,
+        },
+        scopes: key.scopes,
+    }))
+}
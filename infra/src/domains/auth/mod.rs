@@ -1,50 +1,478 @@
 use crate::db::DbPool;
-use crate::models::User as DbUser;
-use crate::schema::{otps, staff, users};
-use app::auth::login::LoginRequest;
+use crate::db::errors::violated_unique;
+use crate::domains::totp;
+use crate::models::{
+    LoginThrottle, NewLoginChallenge, NewLoginFailure, NewOtp, NewRevokedSession, NewUser,
+    OtpPurpose, User as DbUser,
+};
+use crate::schema::{
+    login_challenges, login_failures, login_throttles, otps, revoked_sessions, role_permissions,
+    staff, user_roles, users,
+};
+use app::api::ValidationErrors;
+use app::auth::login::{LoginRequest, SignIn, ThrottleScope, account_subject, lockout_seconds};
 use app::auth::onboard::OnboardRequest;
-use app::auth::{AuthError, SessionUser, hash_password, verify_password};
+use app::auth::otp::{IssueOtpError, IssueOtpRequest, IssueOtpSuccess};
+use app::auth::password::{
+    ChangePasswordError, ChangePasswordRequest, ForgotPasswordError, ForgotPasswordRequest,
+    ResetPasswordError, ResetPasswordRequest,
+};
+use app::auth::signup::{SignupError, SignupRequest};
+use app::auth::totp::{
+    LoginChallenge, VerifyTotpRequest, generate_challenge_token, hash_challenge_token,
+};
+use app::auth::{AuthError, Permission, SessionUser, generate_otp, hash_password, verify_password};
+use app::notifications::{Notification, NotificationSender};
+use app::settings::{LoginSettings, OtpSettings};
+use chrono::{DateTime, Duration, Utc};
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use tracing::error;
 use uuid::Uuid;
 
-pub async fn login(pool: &DbPool, req: LoginRequest) -> Result<SessionUser, AuthError> {
+/// Reasons an OTP issuance transaction is rolled back.
+enum TxError {
+    /// Seconds until the user may request another code
+    RateLimited(u64),
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+/// Returns the staff record of a user, if they are staff.
+async fn find_staff_id(conn: &mut AsyncPgConnection, user_id: Uuid) -> QueryResult<Option<Uuid>> {
+    staff::table
+        .filter(staff::user_id.eq(user_id))
+        .select(staff::id)
+        .first::<Uuid>(conn)
+        .await
+        .optional()
+}
+
+/// Collects what the roles of a user allow.
+pub(crate) async fn find_permissions(
+    conn: &mut AsyncPgConnection,
+    user_id: Uuid,
+) -> QueryResult<Vec<Permission>> {
+    let names: Vec<String> = user_roles::table
+        .inner_join(
+            role_permissions::table.on(role_permissions::role_name.eq(user_roles::role_name)),
+        )
+        .filter(user_roles::user_id.eq(user_id))
+        .select(role_permissions::permission_name)
+        .distinct()
+        .order(role_permissions::permission_name)
+        .load(conn)
+        .await?;
+
+    // Permissions added to the table before the code knows them grant nothing
+    Ok(names
+        .iter()
+        .filter_map(|name| Permission::from_name(name))
+        .collect())
+}
+
+/// Builds the session of a signed-in user from their staff record and roles.
+pub(crate) async fn session_user(
+    conn: &mut AsyncPgConnection,
+    user: DbUser,
+) -> QueryResult<SessionUser> {
+    Ok(SessionUser {
+        id: user.id,
+        staff_id: find_staff_id(conn, user.id).await?,
+        email: user.email,
+        permissions: find_permissions(conn, user.id).await?,
+    })
+}
+
+/// Finishes signing a user in whose password or one-time code was accepted.
+///
+/// Users with TOTP get a challenge instead of a session. Members of a role that requires
+/// TOTP but who have not enrolled are signed in without permissions, so they can enroll.
+async fn finish_sign_in(
+    conn: &mut AsyncPgConnection,
+    user: DbUser,
+    settings: &LoginSettings,
+) -> QueryResult<SignIn> {
+    if totp::find_credential(conn, user.id).await?.is_some() {
+        let token = generate_challenge_token();
+        let expires_at = Utc::now() + Duration::seconds(settings.challenge_ttl as i64);
+
+        // Only the latest password sign-in may be completed
+        diesel::delete(login_challenges::table.filter(login_challenges::user_id.eq(user.id)))
+            .execute(conn)
+            .await?;
+
+        diesel::insert_into(login_challenges::table)
+            .values(&NewLoginChallenge {
+                token_hash: hash_challenge_token(&token),
+                user_id: user.id,
+                expires_at,
+            })
+            .execute(conn)
+            .await?;
+
+        return Ok(SignIn::Challenge {
+            user_id: user.id,
+            challenge: LoginChallenge {
+                challenge_token: token,
+                expires_at,
+            },
+        });
+    }
+
+    let enrollment_required = totp::is_required(conn, user.id).await?;
+    let mut session = session_user(conn, user).await?;
+
+    if enrollment_required {
+        session.permissions.clear();
+        return Ok(SignIn::EnrollmentRequired(session));
+    }
+
+    Ok(SignIn::Complete(session))
+}
+
+/// The subjects failed sign-ins with `email` from `ip` are counted against.
+fn throttle_subjects(email: &str, ip: Option<&str>) -> Vec<(ThrottleScope, String)> {
+    let mut subjects = vec![(ThrottleScope::Account, account_subject(email))];
+    if let Some(ip) = ip {
+        subjects.push((ThrottleScope::Ip, ip.to_string()));
+    }
+    subjects
+}
+
+/// Seconds until a lockout ending at `locked_until` is over, never less than one.
+fn retry_after(locked_until: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    (locked_until - now).num_seconds().max(1) as u64
+}
+
+/// Audits an attempt turned away by a lockout and returns the seconds until it ends.
+async fn refuse_locked(
+    conn: &mut AsyncPgConnection,
+    failure: NewLoginFailure<'_>,
+    locked_until: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> u64 {
+    if let Err(e) = diesel::insert_into(login_failures::table)
+        .values(&failure)
+        .execute(conn)
+        .await
+    {
+        error!("failed to audit locked sign-in: {e}");
+    }
+
+    retry_after(locked_until, now)
+}
+
+/// Forgets the failed sign-ins counted against an account; its addresses keep their own.
+async fn clear_account_failures(conn: &mut AsyncPgConnection, email: &str) -> QueryResult<()> {
+    diesel::delete(
+        login_throttles::table
+            .filter(login_throttles::scope.eq(ThrottleScope::Account.as_str()))
+            .filter(login_throttles::subject.eq(account_subject(email))),
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Signs a user in, refusing attempts while their email or `ip` is locked out.
+///
+/// Lockouts key on the submitted email rather than the account, so a locked response says
+/// nothing about whether the email is registered.
+pub async fn login(
+    pool: &DbPool,
+    req: LoginRequest,
+    ip: Option<&str>,
+    settings: &LoginSettings,
+) -> Result<SignIn, AuthError> {
     let mut conn = pool.get().await.map_err(|_| AuthError::InternalError)?;
 
-    let user: DbUser = users::table
+    let now = Utc::now();
+    let subjects = throttle_subjects(&req.email, ip);
+
+    let user: Option<DbUser> = users::table
         .filter(users::email.eq(&req.email))
         .first(&mut conn)
         .await
-        .map_err(|e| match e {
-            diesel::result::Error::NotFound => AuthError::InvalidCredentials,
-            _ => AuthError::InternalError,
-        })?;
+        .optional()
+        .map_err(|_| AuthError::InternalError)?;
+    let user_id = user.as_ref().map(|user| user.id);
+
+    let locked_until = find_lockout(&mut conn, &subjects, now)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    if let Some(locked_until) = locked_until {
+        let failure = NewLoginFailure {
+            email: &req.email,
+            user_id,
+            ip_address: ip,
+            locked: true,
+        };
+        let retry_after = refuse_locked(&mut conn, failure, locked_until, now).await;
+        return Err(AuthError::Locked { retry_after });
+    }
+
+    let verified = match user.as_ref().and_then(|user| user.password_hash.as_deref()) {
+        Some(stored_hash) => {
+            verify_password(&req.password, stored_hash).map_err(|_| AuthError::InternalError)?
+        }
+        None => false,
+    };
 
-    let stored_hash = user
-        .password_hash
-        .as_deref()
+    let user = match user {
+        Some(user) if verified => user,
+        _ => {
+            // Failing to count the attempt must not let it through uncounted
+            record_failure(&mut conn, &req.email, user_id, ip, &subjects, settings, now)
+                .await
+                .map_err(|_| AuthError::InternalError)?;
+            return Err(AuthError::InvalidCredentials);
+        }
+    };
+
+    let sign_in = finish_sign_in(&mut conn, user, settings)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    // Failures stay counted until the second factor is through as well
+    if !matches!(sign_in, SignIn::Challenge { .. }) {
+        clear_account_failures(&mut conn, &req.email)
+            .await
+            .map_err(|_| AuthError::InternalError)?;
+    }
+
+    Ok(sign_in)
+}
+
+/// Completes a sign-in held at a TOTP challenge.
+///
+/// Wrong codes count as failed sign-ins, so guessing runs into the same lockout as passwords.
+pub async fn verify_totp_challenge(
+    pool: &DbPool,
+    req: VerifyTotpRequest,
+    ip: Option<&str>,
+    settings: &LoginSettings,
+) -> Result<SignIn, AuthError> {
+    let mut conn = pool.get().await.map_err(|_| AuthError::InternalError)?;
+
+    let now = Utc::now();
+    let token_hash = hash_challenge_token(&req.challenge_token);
+
+    let user: DbUser = login_challenges::table
+        .inner_join(users::table)
+        .filter(login_challenges::token_hash.eq(&token_hash))
+        .filter(login_challenges::expires_at.gt(now))
+        .select(DbUser::as_select())
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| AuthError::InternalError)?
+        .ok_or(AuthError::InvalidCredentials)?;
+
+    let subjects = throttle_subjects(&user.email, ip);
+
+    let locked_until = find_lockout(&mut conn, &subjects, now)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    if let Some(locked_until) = locked_until {
+        let failure = NewLoginFailure {
+            email: &user.email,
+            user_id: Some(user.id),
+            ip_address: ip,
+            locked: true,
+        };
+        let retry_after = refuse_locked(&mut conn, failure, locked_until, now).await;
+        return Err(AuthError::Locked { retry_after });
+    }
+
+    // TOTP may have been turned off since the challenge was issued
+    let credential = totp::find_credential(&mut conn, user.id)
+        .await
+        .map_err(|_| AuthError::InternalError)?
         .ok_or(AuthError::InvalidCredentials)?;
 
-    if !verify_password(&req.password, stored_hash).map_err(|_| AuthError::InternalError)? {
+    if !totp::check_code(&mut conn, &credential, &req.code)
+        .await
+        .map_err(|_| AuthError::InternalError)?
+    {
+        record_failure(
+            &mut conn,
+            &user.email,
+            Some(user.id),
+            ip,
+            &subjects,
+            settings,
+            now,
+        )
+        .await
+        .map_err(|_| AuthError::InternalError)?;
         return Err(AuthError::InvalidCredentials);
     }
 
-    let staff_id: Option<Uuid> = staff::table
-        .filter(staff::user_id.eq(user.id))
-        .select(staff::id)
-        .first::<Uuid>(&mut conn)
+    diesel::delete(login_challenges::table.find(&token_hash))
+        .execute(&mut conn)
         .await
-        .optional()
         .map_err(|_| AuthError::InternalError)?;
 
-    Ok(SessionUser {
-        id: user.id,
-        staff_id,
-        email: user.email,
+    clear_account_failures(&mut conn, &user.email)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    session_user(&mut conn, user)
+        .await
+        .map(SignIn::Complete)
+        .map_err(|_| AuthError::InternalError)
+}
+
+/// Returns when the last of the lockouts on `subjects` ends, if any is in force.
+async fn find_lockout(
+    conn: &mut AsyncPgConnection,
+    subjects: &[(ThrottleScope, String)],
+    now: DateTime<Utc>,
+) -> QueryResult<Option<DateTime<Utc>>> {
+    let throttles: Vec<LoginThrottle> = login_throttles::table
+        .filter(login_throttles::subject.eq_any(subjects.iter().map(|(_, subject)| subject)))
+        .filter(login_throttles::locked_until.gt(now))
+        .select(LoginThrottle::as_select())
+        .load(conn)
+        .await?;
+
+    Ok(throttles
+        .into_iter()
+        .filter(|throttle| {
+            subjects.iter().any(|(scope, subject)| {
+                throttle.scope == scope.as_str() && &throttle.subject == subject
+            })
+        })
+        .filter_map(|throttle| throttle.locked_until)
+        .max())
+}
+
+/// Audits a failed sign-in and counts it against every subject, locking those over the limit.
+async fn record_failure(
+    conn: &mut AsyncPgConnection,
+    email: &str,
+    user_id: Option<Uuid>,
+    ip: Option<&str>,
+    subjects: &[(ThrottleScope, String)],
+    settings: &LoginSettings,
+    now: DateTime<Utc>,
+) -> QueryResult<()> {
+    let reset_after = Duration::seconds(settings.reset_after as i64);
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            diesel::insert_into(login_failures::table)
+                .values(&NewLoginFailure {
+                    email,
+                    user_id,
+                    ip_address: ip,
+                    locked: false,
+                })
+                .execute(conn)
+                .await?;
+
+            for (scope, subject) in subjects {
+                diesel::insert_into(login_throttles::table)
+                    .values((
+                        login_throttles::scope.eq(scope.as_str()),
+                        login_throttles::subject.eq(subject),
+                    ))
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .await?;
+
+                // Locking the row keeps concurrent failures from being counted once
+                let throttle: LoginThrottle = login_throttles::table
+                    .find((scope.as_str(), subject))
+                    .select(LoginThrottle::as_select())
+                    .for_update()
+                    .first(conn)
+                    .await?;
+
+                // A quiet spell after the last failure or lockout starts the count over
+                let quiet_since = throttle
+                    .locked_until
+                    .map_or(throttle.last_failed_at, |until| {
+                        until.max(throttle.last_failed_at)
+                    });
+                let failures = if quiet_since + reset_after > now {
+                    throttle.failures as u32 + 1
+                } else {
+                    1
+                };
+                let locked_until = lockout_seconds(*scope, failures, settings)
+                    .map(|seconds| now + Duration::seconds(seconds as i64));
+
+                diesel::update(login_throttles::table.find((scope.as_str(), subject)))
+                    .set((
+                        login_throttles::failures.eq(failures as i32),
+                        login_throttles::last_failed_at.eq(now),
+                        login_throttles::locked_until.eq(locked_until),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+
+            Ok(())
+        }
+        .scope_boxed()
     })
+    .await
+}
+
+/// Counts a wrong one-time code as a failed sign-in, returning when the lockout ends if the
+/// failure brought one on.
+///
+/// A lockout also voids every live code the user holds, so guessing can't pick up against the
+/// same code once it ends.
+async fn record_code_failure(
+    conn: &mut AsyncPgConnection,
+    email: &str,
+    user_id: Option<Uuid>,
+    ip: Option<&str>,
+    subjects: &[(ThrottleScope, String)],
+    settings: &LoginSettings,
+    now: DateTime<Utc>,
+) -> QueryResult<Option<DateTime<Utc>>> {
+    record_failure(conn, email, user_id, ip, subjects, settings, now).await?;
+
+    let locked_until = find_lockout(conn, subjects, now).await?;
+    if let Some(user_id) = user_id
+        && locked_until.is_some()
+    {
+        diesel::update(
+            otps::table
+                .filter(otps::user_id.eq(user_id))
+                .filter(otps::used_at.is_null()),
+        )
+        .set(otps::used_at.eq(now))
+        .execute(conn)
+        .await?;
+    }
+
+    Ok(locked_until)
 }
 
-pub async fn onboard(pool: &DbPool, req: OnboardRequest) -> Result<SessionUser, AuthError> {
+/// Sets the first password of an invited or onboarding account with its code and signs in.
+///
+/// Wrong codes count as failed sign-ins against the account's email and `ip`.
+pub async fn onboard(
+    pool: &DbPool,
+    req: OnboardRequest,
+    ip: Option<&str>,
+    settings: &LoginSettings,
+) -> Result<SignIn, AuthError> {
     let OnboardRequest {
         user_id,
         otp,
@@ -53,10 +481,40 @@ pub async fn onboard(pool: &DbPool, req: OnboardRequest) -> Result<SessionUser,
 
     let mut conn = pool.get().await.map_err(|_| AuthError::InternalError)?;
 
+    let now = Utc::now();
+
+    // Onboarding sets the first password; changing one goes through a reset
+    let email: String = users::table
+        .find(user_id)
+        .filter(users::password_hash.is_null())
+        .select(users::email)
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| AuthError::InternalError)?
+        .ok_or(AuthError::InvalidCredentials)?;
+    let subjects = throttle_subjects(&email, ip);
+
+    let locked_until = find_lockout(&mut conn, &subjects, now)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    if let Some(locked_until) = locked_until {
+        let failure = NewLoginFailure {
+            email: &email,
+            user_id: Some(user_id),
+            ip_address: ip,
+            locked: true,
+        };
+        let retry_after = refuse_locked(&mut conn, failure, locked_until, now).await;
+        return Err(AuthError::Locked { retry_after });
+    }
+
     let updated_count = diesel::update(
         otps::table
             .filter(otps::user_id.eq(user_id))
             .filter(otps::code.eq(&otp))
+            .filter(otps::purpose.eq(OtpPurpose::Onboarding))
             .filter(otps::used_at.is_null())
             .filter(otps::expires_at.gt(diesel::dsl::now)),
     )
@@ -66,7 +524,23 @@ pub async fn onboard(pool: &DbPool, req: OnboardRequest) -> Result<SessionUser,
     .map_err(|_| AuthError::InternalError)?;
 
     if updated_count == 0 {
-        return Err(AuthError::InvalidCredentials);
+        let locked_until = record_code_failure(
+            &mut conn,
+            &email,
+            Some(user_id),
+            ip,
+            &subjects,
+            settings,
+            now,
+        )
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+        return Err(match locked_until {
+            Some(locked_until) => AuthError::Locked {
+                retry_after: retry_after(locked_until, now),
+            },
+            None => AuthError::InvalidCredentials,
+        });
     }
 
     let password_hash = hash_password(&password).map_err(|_| AuthError::InternalError)?;
@@ -77,9 +551,443 @@ pub async fn onboard(pool: &DbPool, req: OnboardRequest) -> Result<SessionUser,
         .await
         .map_err(|_| AuthError::InternalError)?;
 
+    clear_account_failures(&mut conn, &email)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    // Invited staff finish here, so the session must carry their staff record and roles
+    finish_sign_in(&mut conn, updated_user, settings)
+        .await
+        .map_err(|_| AuthError::InternalError)
+}
+
+/// Creates a guest account with a password.
+pub async fn signup(pool: &DbPool, req: SignupRequest) -> Result<SessionUser, SignupError> {
+    let errors = req.validate();
+    if !errors.is_empty() {
+        return Err(SignupError::Invalid(errors));
+    }
+
+    let password_hash = hash_password(&req.password).map_err(|_| SignupError::InternalError)?;
+
+    let mut conn = pool.get().await.map_err(|_| SignupError::InternalError)?;
+
+    let user: DbUser = diesel::insert_into(users::table)
+        .values(&NewUser {
+            id: None,
+            email: req.email.trim(),
+            password_hash: Some(&password_hash),
+        })
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| match violated_unique(&e) {
+            Some("users_email_key") => {
+                SignupError::Invalid(ValidationErrors::field("email", "has already been taken"))
+            }
+            _ => SignupError::InternalError,
+        })?;
+
     Ok(SessionUser {
-        id: updated_user.id,
+        id: user.id,
         staff_id: None,
-        email: updated_user.email,
+        email: user.email,
+        permissions: Vec::new(),
     })
 }
+
+/// Issues a fresh onboarding code for a user who has yet to set a password and hands it to
+/// `sender`.
+///
+/// Unknown ids and users already onboarded get the same answer without a code, so the
+/// endpoint says nothing about which ids exist.
+pub async fn issue_otp(
+    pool: &DbPool,
+    req: IssueOtpRequest,
+    settings: &OtpSettings,
+    sender: &dyn NotificationSender,
+) -> Result<IssueOtpSuccess, IssueOtpError> {
+    let expires_at = issue_code(pool, req.user_id, OtpPurpose::Onboarding, settings, sender)
+        .await?
+        .unwrap_or_else(|| Utc::now() + Duration::seconds(settings.ttl as i64));
+
+    Ok(IssueOtpSuccess { expires_at })
+}
+
+/// Issues a code for `purpose` and hands it to `sender`, returning when it expires.
+///
+/// Onboarding codes only go to users without a password and reset codes only to users with
+/// one; anyone else gets `None` and nothing is sent. Earlier unused codes for the same
+/// purpose stop working. Each user must wait out a cooldown between codes and may only
+/// request a limited number per hour, counted for each purpose apart so that requests for
+/// one kind of code can't use up another.
+async fn issue_code(
+    pool: &DbPool,
+    user_id: Uuid,
+    purpose: OtpPurpose,
+    settings: &OtpSettings,
+    sender: &dyn NotificationSender,
+) -> Result<Option<DateTime<Utc>>, IssueOtpError> {
+    let mut conn = pool.get().await.map_err(|_| IssueOtpError::InternalError)?;
+
+    let code = generate_otp();
+    let now = Utc::now();
+    let expires_at = now + Duration::seconds(settings.ttl as i64);
+    let cooldown = Duration::seconds(settings.cooldown as i64);
+    let window = Duration::hours(1);
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            let code = &code;
+            async move {
+                // Locking the user serialises concurrent requests so the limits hold
+                let user: Option<(String, Option<String>)> = users::table
+                    .find(user_id)
+                    .select((users::email, users::password_hash))
+                    .for_update()
+                    .first(conn)
+                    .await
+                    .optional()?;
+
+                let email = match (user, purpose) {
+                    (Some((email, None)), OtpPurpose::Onboarding)
+                    | (Some((email, Some(_))), OtpPurpose::PasswordReset) => email,
+                    _ => return Ok(None),
+                };
+
+                let recent: Vec<DateTime<Utc>> = otps::table
+                    .filter(otps::user_id.eq(user_id))
+                    .filter(otps::purpose.eq(purpose))
+                    .filter(otps::created_at.gt(now - window))
+                    .select(otps::created_at)
+                    .load(conn)
+                    .await?;
+
+                let ready_at = match (recent.iter().max(), recent.iter().min()) {
+                    (_, Some(oldest)) if recent.len() >= settings.hourly_limit as usize => {
+                        Some(*oldest + window)
+                    }
+                    (Some(latest), _) if *latest + cooldown > now => Some(*latest + cooldown),
+                    _ => None,
+                };
+
+                if let Some(ready_at) = ready_at {
+                    let wait = (ready_at - now).num_seconds().max(1);
+                    return Err(TxError::RateLimited(wait as u64));
+                }
+
+                diesel::update(
+                    otps::table
+                        .filter(otps::user_id.eq(user_id))
+                        .filter(otps::purpose.eq(purpose))
+                        .filter(otps::used_at.is_null())
+                        .filter(otps::expires_at.gt(now)),
+                )
+                .set(otps::expires_at.eq(now))
+                .execute(conn)
+                .await?;
+
+                diesel::insert_into(otps::table)
+                    .values(&NewOtp {
+                        user_id,
+                        code,
+                        expires_at,
+                        purpose,
+                    })
+                    .execute(conn)
+                    .await?;
+
+                Ok(Some(email))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let email = match result {
+        Ok(Some(email)) => email,
+        Ok(None) => return Ok(None),
+        Err(TxError::RateLimited(retry_after)) => {
+            return Err(IssueOtpError::RateLimited { retry_after });
+        }
+        Err(TxError::Database(e)) => {
+            error!("Failed to issue OTP for user {}: {e}", user_id);
+            return Err(IssueOtpError::InternalError);
+        }
+    };
+
+    let notification = match purpose {
+        OtpPurpose::Onboarding => Notification::Otp {
+            email,
+            code,
+            expires_at,
+        },
+        OtpPurpose::PasswordReset => Notification::PasswordReset {
+            email,
+            code,
+            expires_at,
+        },
+    };
+
+    sender.send(&notification).await.map_err(|e| {
+        error!("Failed to deliver OTP to user {}: {e}", user_id);
+        IssueOtpError::InternalError
+    })?;
+
+    Ok(Some(expires_at))
+}
+
+/// Sends a password reset code to the account registered under `req.email`.
+///
+/// Unknown addresses and accounts that never set a password succeed silently, so the
+/// endpoint cannot be used to discover who has an account.
+pub async fn forgot_password(
+    pool: &DbPool,
+    req: ForgotPasswordRequest,
+    settings: &OtpSettings,
+    sender: &dyn NotificationSender,
+) -> Result<(), ForgotPasswordError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| ForgotPasswordError::InternalError)?;
+
+    let user_id: Option<Uuid> = users::table
+        .filter(users::email.eq(req.email.trim()))
+        .filter(users::password_hash.is_not_null())
+        .select(users::id)
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| ForgotPasswordError::InternalError)?;
+
+    let Some(user_id) = user_id else {
+        return Ok(());
+    };
+
+    match issue_code(pool, user_id, OtpPurpose::PasswordReset, settings, sender).await {
+        Ok(_) => Ok(()),
+        Err(IssueOtpError::RateLimited { retry_after }) => {
+            Err(ForgotPasswordError::RateLimited { retry_after })
+        }
+        Err(IssueOtpError::InternalError) => Err(ForgotPasswordError::InternalError),
+    }
+}
+
+/// Sets a new password with a reset code and signs out every existing session.
+///
+/// Wrong codes count as failed sign-ins against the email and `ip`, so a code can't be guessed
+/// faster than a password.
+pub async fn reset_password(
+    pool: &DbPool,
+    req: ResetPasswordRequest,
+    ip: Option<&str>,
+    settings: &LoginSettings,
+) -> Result<SignIn, ResetPasswordError> {
+    let errors = req.validate();
+    if !errors.is_empty() {
+        return Err(ResetPasswordError::Invalid(errors));
+    }
+
+    let password_hash =
+        hash_password(&req.password).map_err(|_| ResetPasswordError::InternalError)?;
+
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| ResetPasswordError::InternalError)?;
+
+    let now = Utc::now();
+    let email = req.email.trim();
+    let subjects = throttle_subjects(email, ip);
+
+    let user_id: Option<Uuid> = users::table
+        .filter(users::email.eq(email))
+        .select(users::id)
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| ResetPasswordError::InternalError)?;
+
+    let locked_until = find_lockout(&mut conn, &subjects, now)
+        .await
+        .map_err(|_| ResetPasswordError::InternalError)?;
+
+    if let Some(locked_until) = locked_until {
+        let failure = NewLoginFailure {
+            email,
+            user_id,
+            ip_address: ip,
+            locked: true,
+        };
+        let retry_after = refuse_locked(&mut conn, failure, locked_until, now).await;
+        return Err(ResetPasswordError::Locked { retry_after });
+    }
+
+    let result = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            let otp = &req.otp;
+            async move {
+                let Some(user_id) = user_id else {
+                    return Ok(None);
+                };
+
+                let consumed = diesel::update(
+                    otps::table
+                        .filter(otps::user_id.eq(user_id))
+                        .filter(otps::code.eq(otp))
+                        .filter(otps::purpose.eq(OtpPurpose::PasswordReset))
+                        .filter(otps::used_at.is_null())
+                        .filter(otps::expires_at.gt(diesel::dsl::now)),
+                )
+                .set(otps::used_at.eq(diesel::dsl::now))
+                .execute(conn)
+                .await?;
+
+                if consumed == 0 {
+                    return Ok(None);
+                }
+
+                let user: DbUser = diesel::update(users::table.find(user_id))
+                    .set((
+                        users::password_hash.eq(password_hash),
+                        users::sessions_revoked_at.eq(Utc::now()),
+                    ))
+                    .get_result(conn)
+                    .await?;
+
+                // A reset proves control of the email, not of the second factor
+                Ok(Some(finish_sign_in(conn, user, settings).await?))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    match result {
+        Ok(Some(sign_in)) => {
+            clear_account_failures(&mut conn, email)
+                .await
+                .map_err(|_| ResetPasswordError::InternalError)?;
+            Ok(sign_in)
+        }
+        Ok(None) => {
+            let locked_until =
+                record_code_failure(&mut conn, email, user_id, ip, &subjects, settings, now)
+                    .await
+                    .map_err(|_| ResetPasswordError::InternalError)?;
+            match locked_until {
+                Some(locked_until) => Err(ResetPasswordError::Locked {
+                    retry_after: retry_after(locked_until, now),
+                }),
+                None => Err(ResetPasswordError::InvalidCode),
+            }
+        }
+        Err(_) => Err(ResetPasswordError::InternalError),
+    }
+}
+
+/// Replaces the password of a signed-in user and signs out every existing session.
+pub async fn change_password(
+    pool: &DbPool,
+    req: ChangePasswordRequest,
+    user: &SessionUser,
+) -> Result<SessionUser, ChangePasswordError> {
+    let errors = req.validate();
+    if !errors.is_empty() {
+        return Err(ChangePasswordError::Invalid(errors));
+    }
+
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| ChangePasswordError::InternalError)?;
+
+    let stored_hash: Option<String> = users::table
+        .find(user.id)
+        .select(users::password_hash)
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| ChangePasswordError::InternalError)?
+        .flatten();
+
+    let stored_hash = stored_hash.ok_or(ChangePasswordError::InvalidCredentials)?;
+
+    if !verify_password(&req.current_password, &stored_hash)
+        .map_err(|_| ChangePasswordError::InternalError)?
+    {
+        return Err(ChangePasswordError::InvalidCredentials);
+    }
+
+    let password_hash =
+        hash_password(&req.new_password).map_err(|_| ChangePasswordError::InternalError)?;
+
+    diesel::update(users::table.find(user.id))
+        .set((
+            users::password_hash.eq(password_hash),
+            users::sessions_revoked_at.eq(Utc::now()),
+        ))
+        .execute(&mut conn)
+        .await
+        .map_err(|_| ChangePasswordError::InternalError)?;
+
+    Ok(user.clone())
+}
+
+/// Returns true when the session `token_id` of `user_id`, issued at `issued_at`, has been
+/// signed out on its own or along with every other session of the user.
+pub async fn is_session_revoked(
+    pool: &DbPool,
+    user_id: Uuid,
+    token_id: Uuid,
+    issued_at: DateTime<Utc>,
+) -> Result<bool, AuthError> {
+    let mut conn = pool.get().await.map_err(|_| AuthError::InternalError)?;
+
+    let status: Option<(Option<DateTime<Utc>>, bool)> = users::table
+        .find(user_id)
+        .select((
+            users::sessions_revoked_at,
+            diesel::dsl::exists(revoked_sessions::table.find(token_id)),
+        ))
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| AuthError::InternalError)?;
+
+    // Tokens carry millisecond timestamps, so compare at that precision; a token
+    // minted in the same millisecond as the revocation is the replacement for it
+    Ok(status.is_some_and(|(revoked_at, token_revoked)| {
+        token_revoked
+            || revoked_at.is_some_and(|at| issued_at.timestamp_millis() < at.timestamp_millis())
+    }))
+}
+
+/// Signs out a single session until it would have expired anyway.
+pub async fn revoke_session(
+    pool: &DbPool,
+    user_id: Uuid,
+    token_id: Uuid,
+    expires_at: DateTime<Utc>,
+) -> Result<(), AuthError> {
+    let mut conn = pool.get().await.map_err(|_| AuthError::InternalError)?;
+
+    diesel::insert_into(revoked_sessions::table)
+        .values(&NewRevokedSession {
+            token_id,
+            user_id,
+            expires_at,
+        })
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+        .await
+        .map_err(|_| AuthError::InternalError)?;
+
+    // Expired tokens are refused anyway, so their entries are dead weight
+    diesel::delete(
+        revoked_sessions::table.filter(revoked_sessions::expires_at.lt(diesel::dsl::now)),
+    )
+    .execute(&mut conn)
+    .await
+    .map_err(|_| AuthError::InternalError)?;
+
+    Ok(())
+}
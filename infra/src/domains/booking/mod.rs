@@ -0,0 +1,963 @@
+use std::ops::Bound;
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use uuid::Uuid;
+
+use app::{
+    actix_web::{HttpResponse, http::StatusCode},
+    api::ApiResponse,
+    auth::{Permission, SessionUser},
+    bookings::{
+        BookingDetails, amend::*, cancel::*, create::*, lifecycle::BookingAction, move_room::*,
+        transition::*,
+    },
+    pricing::Stay,
+    promotions::{PromoCodeDetails, PromoRejection, Redemption},
+    settings::{BookingSettings, PricingSettings},
+    stay_rules::StayRestriction,
+};
+
+use crate::{
+    db::{
+        DbPool,
+        conflicts::{BlockWriteError, Placement, find_conflicts},
+        errors::violated_foreign_key,
+    },
+    domains::{
+        promotion::{find_by_code, find_by_id, guest_uses},
+        quote::quote_class,
+        stay_rule::find_restrictions,
+    },
+    models::{
+        Block, BlockKind, Booking, BookingStatus, BookingTransition, HousekeepingStatus, NewBlock,
+        NewBooking, NewBookingTransition, NewHousekeeping,
+    },
+    schema::{blocks, booking_transitions, bookings, housekeeping, room_classes, rooms},
+};
+
+impl From<BookingStatus> for app::bookings::BookingStatus {
+    fn from(status: BookingStatus) -> Self {
+        match status {
+            BookingStatus::Pending => app::bookings::BookingStatus::Pending,
+            BookingStatus::Confirmed => app::bookings::BookingStatus::Confirmed,
+            BookingStatus::CheckedIn => app::bookings::BookingStatus::CheckedIn,
+            BookingStatus::CheckedOut => app::bookings::BookingStatus::CheckedOut,
+            BookingStatus::NoShow => app::bookings::BookingStatus::NoShow,
+            BookingStatus::Cancelled => app::bookings::BookingStatus::Cancelled,
+        }
+    }
+}
+
+impl From<app::bookings::BookingStatus> for BookingStatus {
+    fn from(status: app::bookings::BookingStatus) -> Self {
+        match status {
+            app::bookings::BookingStatus::Pending => BookingStatus::Pending,
+            app::bookings::BookingStatus::Confirmed => BookingStatus::Confirmed,
+            app::bookings::BookingStatus::CheckedIn => BookingStatus::CheckedIn,
+            app::bookings::BookingStatus::CheckedOut => BookingStatus::CheckedOut,
+            app::bookings::BookingStatus::NoShow => BookingStatus::NoShow,
+            app::bookings::BookingStatus::Cancelled => BookingStatus::Cancelled,
+        }
+    }
+}
+
+impl From<BookingTransition> for app::bookings::transition::BookingTransition {
+    fn from(transition: BookingTransition) -> Self {
+        app::bookings::transition::BookingTransition {
+            from: transition.from_status.into(),
+            to: transition.to_status.into(),
+            performed_by: transition.performed_by,
+            performed_at: transition.created_at,
+        }
+    }
+}
+
+fn to_details(block: Block, booking: Booking) -> BookingDetails {
+    BookingDetails {
+        id: booking.block_id,
+        room_id: block.room_id,
+        guest_id: booking.guest_id,
+        status: booking.status.into(),
+        period: block.interval,
+        hold_expires_at: booking.hold_expires_at,
+        promo_code_id: booking.promo_code_id,
+        discount: booking.discount,
+        guests: booking.guests as u32,
+    }
+}
+
+/// Reasons a booking transaction is rolled back.
+enum TxError {
+    NotFound,
+    Unauthorized,
+    InvalidStatus(BookingStatus),
+    /// Checking in before the stay starts
+    NotStarted(DateTime<Utc>),
+    /// The stay overlaps another block on the room
+    Overlap(Placement),
+    /// The stay fits but the room cannot be turned over before the next block
+    TurnoverUnavailable(Placement),
+    PromoRejected(PromoRejection),
+    StayRestricted(StayRestriction),
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+impl From<BlockWriteError> for TxError {
+    fn from(error: BlockWriteError) -> Self {
+        match error {
+            BlockWriteError::Overlap(placement) => TxError::Overlap(placement),
+            BlockWriteError::Database(error) => TxError::Database(error),
+        }
+    }
+}
+
+/// Loads a booking with its block, locking both rows until the transaction ends.
+async fn lock_booking(
+    conn: &mut AsyncPgConnection,
+    booking_id: Uuid,
+) -> Result<(Block, Booking), TxError> {
+    let (block, booking): (Block, Booking) = blocks::table
+        .inner_join(bookings::table)
+        .filter(blocks::id.eq(booking_id))
+        .select((Block::as_select(), Booking::as_select()))
+        .for_update()
+        .first(conn)
+        .await
+        .optional()?
+        .ok_or(TxError::NotFound)?;
+
+    Ok((block, booking))
+}
+
+/// Locks a booking that `user` is allowed to act on: their own, or any with `ManageBookings`.
+async fn lock_owned_booking(
+    conn: &mut AsyncPgConnection,
+    booking_id: Uuid,
+    user: &SessionUser,
+) -> Result<(Block, Booking), TxError> {
+    let (block, booking) = lock_booking(conn, booking_id).await?;
+
+    if booking.guest_id != user.id && !user.can(Permission::ManageBookings) {
+        return Err(TxError::Unauthorized);
+    }
+
+    Ok((block, booking))
+}
+
+/// Removes the turnover blocks scheduled after a booking.
+async fn clear_turnover(conn: &mut AsyncPgConnection, booking_id: Uuid) -> QueryResult<usize> {
+    diesel::delete(
+        blocks::table.filter(
+            blocks::id.eq_any(
+                housekeeping::table
+                    .filter(housekeeping::booking_id.eq(booking_id))
+                    .select(housekeeping::block_id),
+            ),
+        ),
+    )
+    .execute(conn)
+    .await
+}
+
+/// Books the room class's turnover window, if it has one, right after the stay.
+async fn schedule_turnover(
+    conn: &mut AsyncPgConnection,
+    block: &Block,
+    booking_id: Uuid,
+) -> Result<(), TxError> {
+    let turnover_hours: Option<i32> = rooms::table
+        .inner_join(room_classes::table)
+        .filter(rooms::id.eq(block.room_id))
+        .select(room_classes::turnover_hours)
+        .first(conn)
+        .await?;
+
+    let (Some(hours), Bound::Included(end) | Bound::Excluded(end)) =
+        (turnover_hours, block.interval.1)
+    else {
+        return Ok(());
+    };
+
+    let placement = Placement {
+        block_id: None,
+        room_id: block.room_id,
+        interval: (
+            Bound::Included(end),
+            Bound::Excluded(end + Duration::hours(hours.into())),
+        ),
+    };
+
+    let turnover: Block = diesel::insert_into(blocks::table)
+        .values(&NewBlock {
+            id: None,
+            room_id: placement.room_id,
+            interval: placement.interval,
+            kind: BlockKind::Housekeeping,
+        })
+        .get_result(conn)
+        .await
+        .map_err(|e| match placement.reject(e) {
+            BlockWriteError::Overlap(placement) => TxError::TurnoverUnavailable(placement),
+            BlockWriteError::Database(e) => TxError::Database(e),
+        })?;
+
+    diesel::insert_into(housekeeping::table)
+        .values(&NewHousekeeping {
+            block_id: turnover.id,
+            status: HousekeepingStatus::Pending,
+            instructions: None,
+            assignee_id: None,
+            booking_id: Some(booking_id),
+        })
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Moves a locked booking through its lifecycle and records who did it.
+///
+/// Cancelled and no-show stays release their block and drop their turnover; an early
+/// check-out shrinks the block to end now and moves the turnover with it, so the rest of
+/// the stay can be sold again. Guests can't be checked in before their stay starts.
+async fn apply_action(
+    conn: &mut AsyncPgConnection,
+    block: Block,
+    booking: Booking,
+    action: BookingAction,
+    performed_by: Option<Uuid>,
+) -> Result<(Block, Booking, BookingTransition), TxError> {
+    let from: app::bookings::BookingStatus = booking.status.into();
+    let to = from
+        .apply(action)
+        .ok_or(TxError::InvalidStatus(booking.status))?;
+
+    if action == BookingAction::CheckIn
+        && let Bound::Included(start) | Bound::Excluded(start) = block.interval.0
+        && start > Utc::now()
+    {
+        return Err(TxError::NotStarted(start));
+    }
+
+    let block = if !to.holds_room() {
+        clear_turnover(conn, booking.block_id).await?;
+
+        diesel::update(blocks::table.find(block.id))
+            .set(blocks::released_at.eq(diesel::dsl::now))
+            .get_result(conn)
+            .await?
+    } else if action == BookingAction::CheckOut {
+        let now = Utc::now();
+        match block.interval {
+            // Only a stay under way can end early; shrinking one that has not started would
+            // leave an empty range and lose its dates
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Excluded(end) | Bound::Included(end),
+            ) if start < now && now < end => {
+                let block: Block = diesel::update(blocks::table.find(block.id))
+                    .set(blocks::interval.eq((block.interval.0, Bound::Excluded(now))))
+                    .get_result(conn)
+                    .await?;
+
+                // The room is cleaned after the guest actually leaves, not at the old end
+                clear_turnover(conn, booking.block_id).await?;
+                schedule_turnover(conn, &block, booking.block_id).await?;
+
+                block
+            }
+            _ => block,
+        }
+    } else {
+        block
+    };
+
+    // Only pending bookings are holds, and nothing transitions back to pending
+    let updated: Booking = diesel::update(bookings::table.find(booking.block_id))
+        .set((
+            bookings::status.eq(BookingStatus::from(to)),
+            bookings::hold_expires_at.eq(None::<chrono::DateTime<Utc>>),
+        ))
+        .get_result(conn)
+        .await?;
+
+    let transition: BookingTransition = diesel::insert_into(booking_transitions::table)
+        .values(&NewBookingTransition {
+            booking_id: booking.block_id,
+            from_status: booking.status,
+            to_status: updated.status,
+            performed_by,
+        })
+        .get_result(conn)
+        .await?;
+
+    Ok((block, updated, transition))
+}
+
+/// Looks up the room class of a room, if the room exists.
+async fn room_class(conn: &mut AsyncPgConnection, room_id: Uuid) -> QueryResult<Option<Uuid>> {
+    rooms::table
+        .find(room_id)
+        .select(rooms::class_id)
+        .first(conn)
+        .await
+        .optional()
+}
+
+/// Refuses a stay over `interval` that the stay rules of `class_id` don't allow.
+async fn check_stay_rules(
+    conn: &mut AsyncPgConnection,
+    class_id: Uuid,
+    interval: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+) -> Result<(), TxError> {
+    let (
+        Bound::Included(start) | Bound::Excluded(start),
+        Bound::Included(end) | Bound::Excluded(end),
+    ) = interval
+    else {
+        return Ok(());
+    };
+
+    let mut restrictions =
+        find_restrictions(conn, &[class_id], start.date_naive(), end.date_naive()).await?;
+    match restrictions.remove(&class_id) {
+        Some(restriction) => Err(TxError::StayRestricted(restriction)),
+        None => Ok(()),
+    }
+}
+
+/// Checks `code` can be used by `guest_id` for a stay in a room class, and works out what it
+/// takes off the stay's quote. The code stays locked until the booking is written.
+async fn redeem_promo(
+    conn: &mut AsyncPgConnection,
+    code: &str,
+    guest_id: Uuid,
+    class_id: Uuid,
+    base_price: &BigDecimal,
+    stay: Option<Stay>,
+    pricing: &PricingSettings,
+) -> Result<(Uuid, BigDecimal), TxError> {
+    let promo = find_by_code(conn, code, true)
+        .await?
+        .ok_or(TxError::PromoRejected(PromoRejection::Unknown))?;
+
+    let redemption = Redemption {
+        class_id,
+        nights: stay.map_or(0, |stay| stay.nights()),
+        at: Utc::now(),
+        guest_uses: Some(guest_uses(conn, promo.id, guest_id).await?),
+    };
+    promo.check(&redemption).map_err(TxError::PromoRejected)?;
+
+    let discount = promo_discount(conn, &promo, class_id, base_price, stay, None, pricing).await?;
+
+    Ok((promo.id, discount))
+}
+
+/// Works out what `promo` takes off the quote for a stay in a room class. A booking being
+/// re-priced passes its own block as `exclude_block`, so its stay isn't counted as sold.
+async fn promo_discount(
+    conn: &mut AsyncPgConnection,
+    promo: &PromoCodeDetails,
+    class_id: Uuid,
+    base_price: &BigDecimal,
+    stay: Option<Stay>,
+    exclude_block: Option<Uuid>,
+    pricing: &PricingSettings,
+) -> QueryResult<BigDecimal> {
+    let subtotal = match stay {
+        Some(stay) => quote_class(
+            conn,
+            class_id,
+            base_price,
+            None,
+            &stay,
+            exclude_block,
+            pricing,
+        )
+        .await?
+        .map_or_else(BigDecimal::zero, |quote| quote.subtotal),
+        None => BigDecimal::zero(),
+    };
+
+    Ok(promo.discount(&subtotal))
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreateBookingRequest,
+    user: &SessionUser,
+    settings: &BookingSettings,
+    pricing: &PricingSettings,
+) -> ApiResponse<CreateBookingSuccess, CreateBookingError> {
+    if request.start >= request.end {
+        return ApiResponse::error(CreateBookingError::InvalidDateRange);
+    }
+
+    let guests = match i32::try_from(request.guests) {
+        Ok(guests) if guests >= 1 => guests,
+        _ => return ApiResponse::error(CreateBookingError::InvalidGuests),
+    };
+
+    let guest_id = match request.guest_id {
+        Some(guest_id) if guest_id != user.id => {
+            if !user.can(Permission::ManageBookings) {
+                return ApiResponse::error(CreateBookingError::Unauthorized);
+            }
+            guest_id
+        }
+        _ => user.id,
+    };
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateBookingError::InternalError),
+    };
+
+    let (class_id, base_price): (Uuid, BigDecimal) = match rooms::table
+        .inner_join(room_classes::table)
+        .filter(rooms::id.eq(request.room_id))
+        .select((room_classes::id, room_classes::base_price))
+        .first(&mut conn)
+        .await
+    {
+        Ok(class) => class,
+        Err(diesel::result::Error::NotFound) => {
+            return ApiResponse::error(CreateBookingError::RoomNotFound);
+        }
+        Err(_) => return ApiResponse::error(CreateBookingError::InternalError),
+    };
+
+    match find_restrictions(
+        &mut conn,
+        &[class_id],
+        request.start.date_naive(),
+        request.end.date_naive(),
+    )
+    .await
+    {
+        Ok(mut restrictions) => {
+            if let Some(restriction) = restrictions.remove(&class_id) {
+                return ApiResponse::error(CreateBookingError::StayRestricted { restriction });
+            }
+        }
+        Err(_) => return ApiResponse::error(CreateBookingError::InternalError),
+    }
+
+    let placement = Placement {
+        block_id: None,
+        room_id: request.room_id,
+        interval: (Bound::Included(request.start), Bound::Excluded(request.end)),
+    };
+
+    let hold_expires_at = Utc::now() + Duration::seconds(settings.hold_ttl as i64);
+    let promo_code = request.promo_code.as_deref();
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let discount = match promo_code {
+                    Some(code) => {
+                        let stay = Stay::from_interval(request.start, request.end, request.guests);
+                        Some(
+                            redeem_promo(
+                                conn,
+                                code,
+                                guest_id,
+                                class_id,
+                                &base_price,
+                                stay,
+                                pricing,
+                            )
+                            .await?,
+                        )
+                    }
+                    None => None,
+                };
+
+                let block: Block = diesel::insert_into(blocks::table)
+                    .values(&NewBlock {
+                        id: None,
+                        room_id: placement.room_id,
+                        interval: placement.interval,
+                        kind: BlockKind::Booking,
+                    })
+                    .get_result(conn)
+                    .await
+                    .map_err(|e| placement.reject(e))?;
+
+                let booking: Booking = diesel::insert_into(bookings::table)
+                    .values(&NewBooking {
+                        block_id: block.id,
+                        guest_id,
+                        status: BookingStatus::Pending,
+                        hold_expires_at: Some(hold_expires_at),
+                        promo_code_id: discount.as_ref().map(|(id, _)| *id),
+                        discount: discount.map(|(_, amount)| amount),
+                        guests,
+                    })
+                    .get_result(conn)
+                    .await?;
+
+                schedule_turnover(conn, &block, booking.block_id).await?;
+
+                Ok((block, booking))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, booking) = match result {
+        Ok(data) => data,
+        Err(TxError::Overlap(placement)) => {
+            return ApiResponse::error(CreateBookingError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::TurnoverUnavailable(placement)) => {
+            return ApiResponse::error(CreateBookingError::TurnoverUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::PromoRejected(rejection)) => {
+            return ApiResponse::error(CreateBookingError::PromoRejected { rejection });
+        }
+        Err(TxError::Database(e)) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("blocks_room_id_fkey") => CreateBookingError::RoomNotFound,
+                Some("bookings_guest_id_fkey") => CreateBookingError::GuestNotFound,
+                _ => CreateBookingError::InternalError,
+            });
+        }
+        Err(_) => return ApiResponse::error(CreateBookingError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::CREATED,
+        CreateBookingSuccess {
+            booking: to_details(block, booking),
+        },
+    ))
+}
+
+pub async fn cancel(
+    pool: &DbPool,
+    options: CancelBookingOptions,
+    user: &SessionUser,
+) -> ApiResponse<CancelBookingSuccess, CancelBookingError> {
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CancelBookingError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, booking) = lock_owned_booking(conn, options.booking_id, user).await?;
+
+                let (block, booking, _) =
+                    apply_action(conn, block, booking, BookingAction::Cancel, Some(user.id))
+                        .await?;
+
+                Ok((block, booking))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, booking) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(CancelBookingError::NotFound),
+        Err(TxError::Unauthorized) => {
+            return ApiResponse::error(CancelBookingError::Unauthorized);
+        }
+        Err(TxError::InvalidStatus(_)) => {
+            return ApiResponse::error(CancelBookingError::InvalidStatus);
+        }
+        Err(
+            TxError::NotStarted(_)
+            | TxError::Overlap(_)
+            | TxError::TurnoverUnavailable(_)
+            | TxError::PromoRejected(_)
+            | TxError::StayRestricted(_)
+            | TxError::Database(_),
+        ) => {
+            return ApiResponse::error(CancelBookingError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        CancelBookingSuccess {
+            booking: to_details(block, booking),
+        },
+    ))
+}
+
+pub async fn amend(
+    pool: &DbPool,
+    options: AmendBookingOptions,
+    user: &SessionUser,
+    pricing: &PricingSettings,
+) -> ApiResponse<AmendBookingSuccess, AmendBookingError> {
+    if options.start >= options.end {
+        return ApiResponse::error(AmendBookingError::InvalidDateRange);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(AmendBookingError::InternalError),
+    };
+
+    let interval = (Bound::Included(options.start), Bound::Excluded(options.end));
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, booking) = lock_owned_booking(conn, options.booking_id, user).await?;
+
+                if !app::bookings::BookingStatus::from(booking.status).is_modifiable() {
+                    return Err(TxError::InvalidStatus(booking.status));
+                }
+
+                let (class_id, base_price): (Uuid, BigDecimal) = rooms::table
+                    .inner_join(room_classes::table)
+                    .filter(rooms::id.eq(block.room_id))
+                    .select((room_classes::id, room_classes::base_price))
+                    .first(conn)
+                    .await?;
+
+                check_stay_rules(conn, class_id, interval).await?;
+
+                // The new dates must still qualify for the code, and are discounted afresh
+                let booking = match booking.promo_code_id {
+                    Some(promo_code_id) => {
+                        let promo = find_by_id(conn, promo_code_id)
+                            .await?
+                            .ok_or(diesel::result::Error::NotFound)?;
+                        let stay =
+                            Stay::from_interval(options.start, options.end, booking.guests as u32);
+                        promo
+                            .check_stay(class_id, stay.map_or(0, |stay| stay.nights()))
+                            .map_err(TxError::PromoRejected)?;
+                        let discount = promo_discount(
+                            conn,
+                            &promo,
+                            class_id,
+                            &base_price,
+                            stay,
+                            Some(booking.block_id),
+                            pricing,
+                        )
+                        .await?;
+
+                        diesel::update(bookings::table.find(booking.block_id))
+                            .set(bookings::discount.eq(discount))
+                            .get_result(conn)
+                            .await?
+                    }
+                    None => booking,
+                };
+
+                // The old turnover may sit inside the new dates, so clear it before moving
+                clear_turnover(conn, booking.block_id).await?;
+
+                let placement = Placement {
+                    block_id: Some(block.id),
+                    room_id: block.room_id,
+                    interval,
+                };
+
+                let block: Block = diesel::update(blocks::table.find(block.id))
+                    .set(blocks::interval.eq(interval))
+                    .get_result(conn)
+                    .await
+                    .map_err(|e| placement.reject(e))?;
+
+                schedule_turnover(conn, &block, booking.block_id).await?;
+
+                Ok((block, booking))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, booking) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(AmendBookingError::NotFound),
+        Err(TxError::Unauthorized) => return ApiResponse::error(AmendBookingError::Unauthorized),
+        Err(TxError::InvalidStatus(_)) => {
+            return ApiResponse::error(AmendBookingError::InvalidStatus);
+        }
+        Err(TxError::Overlap(placement)) => {
+            return ApiResponse::error(AmendBookingError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::TurnoverUnavailable(placement)) => {
+            return ApiResponse::error(AmendBookingError::TurnoverUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::StayRestricted(restriction)) => {
+            return ApiResponse::error(AmendBookingError::StayRestricted { restriction });
+        }
+        Err(TxError::PromoRejected(rejection)) => {
+            return ApiResponse::error(AmendBookingError::PromoRejected { rejection });
+        }
+        Err(TxError::NotStarted(_) | TxError::Database(_)) => {
+            return ApiResponse::error(AmendBookingError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        AmendBookingSuccess {
+            booking: to_details(block, booking),
+        },
+    ))
+}
+
+pub async fn move_room(
+    pool: &DbPool,
+    options: MoveBookingOptions,
+    user: &SessionUser,
+    pricing: &PricingSettings,
+) -> ApiResponse<MoveBookingSuccess, MoveBookingError> {
+    if !user.can(Permission::ManageBookings) {
+        return ApiResponse::error(MoveBookingError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(MoveBookingError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, booking) = lock_owned_booking(conn, options.booking_id, user).await?;
+
+                if !app::bookings::BookingStatus::from(booking.status).is_modifiable() {
+                    return Err(TxError::InvalidStatus(booking.status));
+                }
+
+                // Within its class the stay is the one booked, so only a new class is checked
+                let from_class = room_class(conn, block.room_id).await?;
+                let to_class: Option<(Uuid, BigDecimal)> = rooms::table
+                    .inner_join(room_classes::table)
+                    .filter(rooms::id.eq(options.room_id))
+                    .select((room_classes::id, room_classes::base_price))
+                    .first(conn)
+                    .await
+                    .optional()?;
+
+                let booking = match to_class {
+                    Some((to_class, base_price)) if Some(to_class) != from_class => {
+                        check_stay_rules(conn, to_class, block.interval).await?;
+
+                        // The code must still cover the new class, and is discounted afresh
+                        match booking.promo_code_id {
+                            Some(promo_code_id) => {
+                                let promo = find_by_id(conn, promo_code_id)
+                                    .await?
+                                    .ok_or(diesel::result::Error::NotFound)?;
+                                let stay = match block.interval {
+                                    (
+                                        Bound::Included(start) | Bound::Excluded(start),
+                                        Bound::Included(end) | Bound::Excluded(end),
+                                    ) => Stay::from_interval(start, end, booking.guests as u32),
+                                    _ => None,
+                                };
+                                promo
+                                    .check_stay(to_class, stay.map_or(0, |stay| stay.nights()))
+                                    .map_err(TxError::PromoRejected)?;
+                                let discount = promo_discount(
+                                    conn,
+                                    &promo,
+                                    to_class,
+                                    &base_price,
+                                    stay,
+                                    Some(booking.block_id),
+                                    pricing,
+                                )
+                                .await?;
+
+                                diesel::update(bookings::table.find(booking.block_id))
+                                    .set(bookings::discount.eq(discount))
+                                    .get_result(conn)
+                                    .await?
+                            }
+                            None => booking,
+                        }
+                    }
+                    _ => booking,
+                };
+
+                clear_turnover(conn, booking.block_id).await?;
+
+                let placement = Placement {
+                    block_id: Some(block.id),
+                    room_id: options.room_id,
+                    interval: block.interval,
+                };
+
+                let block: Block = diesel::update(blocks::table.find(block.id))
+                    .set(blocks::room_id.eq(options.room_id))
+                    .get_result(conn)
+                    .await
+                    .map_err(|e| placement.reject(e))?;
+
+                schedule_turnover(conn, &block, booking.block_id).await?;
+
+                Ok((block, booking))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, booking) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(MoveBookingError::NotFound),
+        Err(TxError::Unauthorized) => return ApiResponse::error(MoveBookingError::Unauthorized),
+        Err(TxError::InvalidStatus(_)) => {
+            return ApiResponse::error(MoveBookingError::InvalidStatus);
+        }
+        Err(TxError::Overlap(placement)) => {
+            return ApiResponse::error(MoveBookingError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::TurnoverUnavailable(placement)) => {
+            return ApiResponse::error(MoveBookingError::TurnoverUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::Database(e)) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("blocks_room_id_fkey") => MoveBookingError::RoomNotFound,
+                _ => MoveBookingError::InternalError,
+            });
+        }
+        Err(TxError::StayRestricted(restriction)) => {
+            return ApiResponse::error(MoveBookingError::StayRestricted { restriction });
+        }
+        Err(TxError::PromoRejected(rejection)) => {
+            return ApiResponse::error(MoveBookingError::PromoRejected { rejection });
+        }
+        Err(TxError::NotStarted(_)) => {
+            return ApiResponse::error(MoveBookingError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        MoveBookingSuccess {
+            booking: to_details(block, booking),
+        },
+    ))
+}
+
+pub async fn transition(
+    pool: &DbPool,
+    options: TransitionBookingOptions,
+    user: &SessionUser,
+) -> ApiResponse<TransitionBookingSuccess, TransitionBookingError> {
+    if !user.can(Permission::ManageBookings) {
+        return ApiResponse::error(TransitionBookingError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(TransitionBookingError::InternalError),
+    };
+
+    let action = options.action;
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, booking) = lock_booking(conn, options.booking_id).await?;
+                apply_action(conn, block, booking, action, Some(user.id)).await
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, booking, transition) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(TransitionBookingError::NotFound),
+        Err(TxError::Unauthorized) => {
+            return ApiResponse::error(TransitionBookingError::Unauthorized);
+        }
+        Err(TxError::InvalidStatus(from)) => {
+            return ApiResponse::error(TransitionBookingError::IllegalTransition {
+                from: from.into(),
+                action,
+            });
+        }
+        Err(TxError::NotStarted(starts_at)) => {
+            return ApiResponse::error(TransitionBookingError::NotStarted { starts_at });
+        }
+        Err(
+            TxError::Overlap(_)
+            | TxError::TurnoverUnavailable(_)
+            | TxError::PromoRejected(_)
+            | TxError::StayRestricted(_)
+            | TxError::Database(_),
+        ) => {
+            return ApiResponse::error(TransitionBookingError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        TransitionBookingSuccess {
+            booking: to_details(block, booking),
+            transition: transition.into(),
+        },
+    ))
+}
+
+/// Cancels pending bookings whose hold has lapsed so their rooms can be sold again.
+///
+/// Returns the number of bookings released.
+pub async fn expire_holds(conn: &mut AsyncPgConnection) -> QueryResult<usize> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let expired: Vec<(Block, Booking)> = blocks::table
+                .inner_join(bookings::table)
+                .filter(bookings::status.eq(BookingStatus::Pending))
+                .filter(bookings::hold_expires_at.lt(diesel::dsl::now))
+                .select((Block::as_select(), Booking::as_select()))
+                .for_update()
+                .skip_locked()
+                .load(conn)
+                .await?;
+
+            let mut released = 0;
+            for (block, booking) in expired {
+                match apply_action(conn, block, booking, BookingAction::Cancel, None).await {
+                    Ok(_) => released += 1,
+                    Err(TxError::Database(e)) => return Err(e),
+                    Err(_) => {}
+                }
+            }
+
+            Ok(released)
+        }
+        .scope_boxed()
+    })
+    .await
+}
@@ -0,0 +1,345 @@
+use std::ops::Bound;
+
+use diesel::prelude::*;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use uuid::Uuid;
+
+use app::{
+    actix_web::{HttpResponse, http::StatusCode},
+    api::ApiResponse,
+    auth::{Permission, SessionUser},
+    housekeeping::{HousekeepingDetails, create::*, delete::*, get::*, update::*},
+};
+
+use crate::{
+    db::{
+        DbPool,
+        conflicts::{BlockWriteError, Placement, find_conflicts},
+        errors::{is_overlap_violation, violated_foreign_key},
+    },
+    models::{
+        Block, BlockKind, Housekeeping, HousekeepingChangeset, HousekeepingStatus, NewBlock,
+        NewHousekeeping,
+    },
+    schema::{blocks, housekeeping},
+};
+
+impl From<HousekeepingStatus> for app::housekeeping::HousekeepingStatus {
+    fn from(status: HousekeepingStatus) -> Self {
+        match status {
+            HousekeepingStatus::Pending => app::housekeeping::HousekeepingStatus::Pending,
+            HousekeepingStatus::InProgress => app::housekeeping::HousekeepingStatus::InProgress,
+            HousekeepingStatus::Completed => app::housekeeping::HousekeepingStatus::Completed,
+        }
+    }
+}
+
+impl From<app::housekeeping::HousekeepingStatus> for HousekeepingStatus {
+    fn from(status: app::housekeeping::HousekeepingStatus) -> Self {
+        match status {
+            app::housekeeping::HousekeepingStatus::Pending => HousekeepingStatus::Pending,
+            app::housekeeping::HousekeepingStatus::InProgress => HousekeepingStatus::InProgress,
+            app::housekeeping::HousekeepingStatus::Completed => HousekeepingStatus::Completed,
+        }
+    }
+}
+
+fn to_details(block: Block, task: Housekeeping) -> HousekeepingDetails {
+    HousekeepingDetails {
+        id: task.block_id,
+        room_id: block.room_id,
+        status: task.status.into(),
+        period: block.interval,
+        instructions: task.instructions,
+        assignee_id: task.assignee_id,
+    }
+}
+
+/// Reasons a housekeeping transaction is rolled back.
+enum TxError {
+    NotFound,
+    Overlap(Placement),
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+impl From<BlockWriteError> for TxError {
+    fn from(error: BlockWriteError) -> Self {
+        match error {
+            BlockWriteError::Overlap(placement) => TxError::Overlap(placement),
+            BlockWriteError::Database(error) => TxError::Database(error),
+        }
+    }
+}
+
+/// Loads a housekeeping task with its block, locking both rows until the transaction ends.
+async fn lock_task(
+    conn: &mut AsyncPgConnection,
+    task_id: Uuid,
+) -> Result<(Block, Housekeeping), TxError> {
+    blocks::table
+        .inner_join(housekeeping::table)
+        .filter(blocks::id.eq(task_id))
+        .select((Block::as_select(), Housekeeping::as_select()))
+        .for_update()
+        .first(conn)
+        .await
+        .optional()?
+        .ok_or(TxError::NotFound)
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreateHousekeepingRequest,
+    user: &SessionUser,
+) -> ApiResponse<CreateHousekeepingSuccess, CreateHousekeepingError> {
+    if !user.can(Permission::ManageHousekeeping) {
+        return ApiResponse::error(CreateHousekeepingError::Unauthorized);
+    }
+
+    if request.start >= request.end {
+        return ApiResponse::error(CreateHousekeepingError::InvalidDateRange);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateHousekeepingError::InternalError),
+    };
+
+    let placement = Placement {
+        block_id: None,
+        room_id: request.room_id,
+        interval: (Bound::Included(request.start), Bound::Excluded(request.end)),
+    };
+
+    let new_block = NewBlock {
+        id: None,
+        room_id: placement.room_id,
+        interval: placement.interval,
+        kind: BlockKind::Housekeeping,
+    };
+
+    let result = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                let block: Block = diesel::insert_into(blocks::table)
+                    .values(&new_block)
+                    .get_result(conn)
+                    .await?;
+
+                let task: Housekeeping = diesel::insert_into(housekeeping::table)
+                    .values(&NewHousekeeping {
+                        block_id: block.id,
+                        status: HousekeepingStatus::Pending,
+                        instructions: request.instructions,
+                        assignee_id: request.assignee_id,
+                        booking_id: None,
+                    })
+                    .get_result(conn)
+                    .await?;
+
+                Ok((block, task))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, task) = match result {
+        Ok(data) => data,
+        Err(e) if is_overlap_violation(&e) => {
+            return ApiResponse::error(CreateHousekeepingError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(e) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("blocks_room_id_fkey") => CreateHousekeepingError::RoomNotFound,
+                Some("housekeeping_assignee_id_fkey") => CreateHousekeepingError::AssigneeNotFound,
+                _ => CreateHousekeepingError::InternalError,
+            });
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::CREATED,
+        CreateHousekeepingSuccess {
+            housekeeping: to_details(block, task),
+        },
+    ))
+}
+
+pub async fn get(
+    pool: &DbPool,
+    options: GetHousekeepingOptions,
+    user: &SessionUser,
+) -> ApiResponse<GetHousekeepingSuccess, GetHousekeepingError> {
+    if !user.can(Permission::ManageHousekeeping) {
+        return ApiResponse::error(GetHousekeepingError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(GetHousekeepingError::InternalError),
+    };
+
+    let (block, task) = match blocks::table
+        .inner_join(housekeeping::table)
+        .filter(blocks::id.eq(options.task_id))
+        .select((Block::as_select(), Housekeeping::as_select()))
+        .first::<(Block, Housekeeping)>(&mut conn)
+        .await
+        .optional()
+    {
+        Ok(Some(data)) => data,
+        Ok(None) => return ApiResponse::error(GetHousekeepingError::NotFound),
+        Err(_) => return ApiResponse::error(GetHousekeepingError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        GetHousekeepingSuccess {
+            housekeeping: to_details(block, task),
+        },
+    ))
+}
+
+pub async fn update(
+    pool: &DbPool,
+    options: UpdateHousekeepingOptions,
+    user: &SessionUser,
+) -> ApiResponse<UpdateHousekeepingSuccess, UpdateHousekeepingError> {
+    if !user.can(Permission::ManageHousekeeping) {
+        return ApiResponse::error(UpdateHousekeepingError::Unauthorized);
+    }
+
+    let interval = match (options.start, options.end) {
+        (Some(start), Some(end)) if start < end => {
+            Some((Bound::Included(start), Bound::Excluded(end)))
+        }
+        (None, None) => None,
+        _ => return ApiResponse::error(UpdateHousekeepingError::InvalidDateRange),
+    };
+
+    let changes = HousekeepingChangeset {
+        status: options.status.map(Into::into),
+        instructions: options.instructions,
+        assignee_id: options.assignee_id,
+    };
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(UpdateHousekeepingError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (mut block, mut task) = lock_task(conn, options.task_id).await?;
+
+                if let Some(interval) = interval {
+                    let placement = Placement {
+                        block_id: Some(block.id),
+                        room_id: block.room_id,
+                        interval,
+                    };
+
+                    block = diesel::update(blocks::table.find(block.id))
+                        .set(blocks::interval.eq(interval))
+                        .get_result(conn)
+                        .await
+                        .map_err(|e| placement.reject(e))?;
+                }
+
+                // Diesel rejects an empty changeset, so only touch the row when something changed
+                if changes.status.is_some()
+                    || changes.instructions.is_some()
+                    || changes.assignee_id.is_some()
+                {
+                    task = diesel::update(housekeeping::table.find(task.block_id))
+                        .set(&changes)
+                        .get_result(conn)
+                        .await?;
+                }
+
+                Ok((block, task))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, task) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(UpdateHousekeepingError::NotFound),
+        Err(TxError::Overlap(placement)) => {
+            return ApiResponse::error(UpdateHousekeepingError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::Database(e)) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("housekeeping_assignee_id_fkey") => UpdateHousekeepingError::AssigneeNotFound,
+                _ => UpdateHousekeepingError::InternalError,
+            });
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        UpdateHousekeepingSuccess {
+            housekeeping: to_details(block, task),
+        },
+    ))
+}
+
+pub async fn delete(
+    pool: &DbPool,
+    options: DeleteHousekeepingOptions,
+    user: &SessionUser,
+) -> ApiResponse<DeleteHousekeepingSuccess, DeleteHousekeepingError> {
+    if !user.can(Permission::ManageHousekeeping) {
+        return ApiResponse::error(DeleteHousekeepingError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(DeleteHousekeepingError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, task) = lock_task(conn, options.task_id).await?;
+
+                // The housekeeping row goes with its block via ON DELETE CASCADE
+                diesel::delete(blocks::table.find(block.id))
+                    .execute(conn)
+                    .await?;
+
+                Ok((block, task))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, task) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(DeleteHousekeepingError::NotFound),
+        Err(TxError::Overlap(_) | TxError::Database(_)) => {
+            return ApiResponse::error(DeleteHousekeepingError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        DeleteHousekeepingSuccess {
+            housekeeping: to_details(block, task),
+        },
+    ))
+}
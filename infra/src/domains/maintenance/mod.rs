@@ -0,0 +1,493 @@
+use std::ops::Bound;
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use uuid::Uuid;
+
+use app::{
+    actix_web::{HttpResponse, http::StatusCode},
+    api::ApiResponse,
+    auth::{Permission, SessionUser},
+    interval::{LowerBound, UpperBound},
+    maintenance::{WorkOrder, close::*, create::*, extend::*, list::*, reassign::*},
+};
+
+use crate::{
+    db::{
+        DbPool,
+        conflicts::{BlockWriteError, Placement, find_conflicts},
+        errors::{is_overlap_violation, violated_foreign_key},
+    },
+    models::{
+        Block, BlockKind, Maintenance, MaintenanceKind, MaintenanceSeverity, NewBlock,
+        NewMaintenance,
+    },
+    schema::{blocks, maintenance},
+};
+
+/// Most work orders returned in one page.
+const MAX_PER_PAGE: i64 = 100;
+
+impl From<MaintenanceKind> for app::maintenance::MaintenanceKind {
+    fn from(kind: MaintenanceKind) -> Self {
+        match kind {
+            MaintenanceKind::Electrical => app::maintenance::MaintenanceKind::Electrical,
+            MaintenanceKind::Plumbing => app::maintenance::MaintenanceKind::Plumbing,
+            MaintenanceKind::Structural => app::maintenance::MaintenanceKind::Structural,
+            MaintenanceKind::Hvac => app::maintenance::MaintenanceKind::Hvac,
+            MaintenanceKind::FireSafety => app::maintenance::MaintenanceKind::FireSafety,
+            MaintenanceKind::SecuritySystems => app::maintenance::MaintenanceKind::SecuritySystems,
+            MaintenanceKind::Groundskeeping => app::maintenance::MaintenanceKind::Groundskeeping,
+            MaintenanceKind::Janitorial => app::maintenance::MaintenanceKind::Janitorial,
+            MaintenanceKind::PestControl => app::maintenance::MaintenanceKind::PestControl,
+            MaintenanceKind::ItNetwork => app::maintenance::MaintenanceKind::ItNetwork,
+            MaintenanceKind::Painting => app::maintenance::MaintenanceKind::Painting,
+            MaintenanceKind::Appliances => app::maintenance::MaintenanceKind::Appliances,
+            MaintenanceKind::OutOfService => app::maintenance::MaintenanceKind::OutOfService,
+            MaintenanceKind::Other => app::maintenance::MaintenanceKind::Other,
+        }
+    }
+}
+
+impl From<app::maintenance::MaintenanceKind> for MaintenanceKind {
+    fn from(kind: app::maintenance::MaintenanceKind) -> Self {
+        match kind {
+            app::maintenance::MaintenanceKind::Electrical => MaintenanceKind::Electrical,
+            app::maintenance::MaintenanceKind::Plumbing => MaintenanceKind::Plumbing,
+            app::maintenance::MaintenanceKind::Structural => MaintenanceKind::Structural,
+            app::maintenance::MaintenanceKind::Hvac => MaintenanceKind::Hvac,
+            app::maintenance::MaintenanceKind::FireSafety => MaintenanceKind::FireSafety,
+            app::maintenance::MaintenanceKind::SecuritySystems => MaintenanceKind::SecuritySystems,
+            app::maintenance::MaintenanceKind::Groundskeeping => MaintenanceKind::Groundskeeping,
+            app::maintenance::MaintenanceKind::Janitorial => MaintenanceKind::Janitorial,
+            app::maintenance::MaintenanceKind::PestControl => MaintenanceKind::PestControl,
+            app::maintenance::MaintenanceKind::ItNetwork => MaintenanceKind::ItNetwork,
+            app::maintenance::MaintenanceKind::Painting => MaintenanceKind::Painting,
+            app::maintenance::MaintenanceKind::Appliances => MaintenanceKind::Appliances,
+            app::maintenance::MaintenanceKind::OutOfService => MaintenanceKind::OutOfService,
+            app::maintenance::MaintenanceKind::Other => MaintenanceKind::Other,
+        }
+    }
+}
+
+impl From<MaintenanceSeverity> for app::maintenance::MaintenanceSeverity {
+    fn from(severity: MaintenanceSeverity) -> Self {
+        match severity {
+            MaintenanceSeverity::Low => app::maintenance::MaintenanceSeverity::Low,
+            MaintenanceSeverity::Medium => app::maintenance::MaintenanceSeverity::Medium,
+            MaintenanceSeverity::High => app::maintenance::MaintenanceSeverity::High,
+        }
+    }
+}
+
+impl From<app::maintenance::MaintenanceSeverity> for MaintenanceSeverity {
+    fn from(severity: app::maintenance::MaintenanceSeverity) -> Self {
+        match severity {
+            app::maintenance::MaintenanceSeverity::Low => MaintenanceSeverity::Low,
+            app::maintenance::MaintenanceSeverity::Medium => MaintenanceSeverity::Medium,
+            app::maintenance::MaintenanceSeverity::High => MaintenanceSeverity::High,
+        }
+    }
+}
+
+fn to_work_order(block: Block, record: Maintenance) -> WorkOrder {
+    WorkOrder {
+        id: record.block_id,
+        room_id: block.room_id,
+        kind: record.kind.into(),
+        severity: record.severity.into(),
+        period: block.interval,
+        assigner_id: record.assigner_id,
+        closed_at: record.closed_at,
+    }
+}
+
+/// Reasons a work order transaction is rolled back.
+enum TxError {
+    NotFound,
+    AlreadyClosed,
+    InvalidDateRange,
+    Overlap(Placement),
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+impl From<BlockWriteError> for TxError {
+    fn from(error: BlockWriteError) -> Self {
+        match error {
+            BlockWriteError::Overlap(placement) => TxError::Overlap(placement),
+            BlockWriteError::Database(error) => TxError::Database(error),
+        }
+    }
+}
+
+/// Loads an open work order with its block, locking both rows until the transaction ends.
+async fn lock_open_work_order(
+    conn: &mut AsyncPgConnection,
+    work_order_id: Uuid,
+) -> Result<(Block, Maintenance), TxError> {
+    let (block, record): (Block, Maintenance) = blocks::table
+        .inner_join(maintenance::table)
+        .filter(blocks::id.eq(work_order_id))
+        .select((Block::as_select(), Maintenance::as_select()))
+        .for_update()
+        .first(conn)
+        .await
+        .optional()?
+        .ok_or(TxError::NotFound)?;
+
+    if record.closed_at.is_some() {
+        return Err(TxError::AlreadyClosed);
+    }
+
+    Ok((block, record))
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreateWorkOrderRequest,
+    user: &SessionUser,
+) -> ApiResponse<CreateWorkOrderSuccess, CreateWorkOrderError> {
+    if !user.can(Permission::ManageMaintenance) {
+        return ApiResponse::error(CreateWorkOrderError::Unauthorized);
+    }
+
+    if request.start >= request.end {
+        return ApiResponse::error(CreateWorkOrderError::InvalidDateRange);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateWorkOrderError::InternalError),
+    };
+
+    let placement = Placement {
+        block_id: None,
+        room_id: request.room_id,
+        interval: (Bound::Included(request.start), Bound::Excluded(request.end)),
+    };
+
+    let new_block = NewBlock {
+        id: None,
+        room_id: placement.room_id,
+        interval: placement.interval,
+        kind: BlockKind::Maintenance,
+    };
+
+    let result = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                let block: Block = diesel::insert_into(blocks::table)
+                    .values(&new_block)
+                    .get_result(conn)
+                    .await?;
+
+                let record: Maintenance = diesel::insert_into(maintenance::table)
+                    .values(&NewMaintenance {
+                        block_id: block.id,
+                        kind: request.kind.into(),
+                        severity: request.severity.into(),
+                        assigner_id: request.assigner_id,
+                    })
+                    .get_result(conn)
+                    .await?;
+
+                Ok((block, record))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, record) = match result {
+        Ok(data) => data,
+        Err(e) if is_overlap_violation(&e) => {
+            return ApiResponse::error(CreateWorkOrderError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(e) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("blocks_room_id_fkey") => CreateWorkOrderError::RoomNotFound,
+                Some("maintenance_assigner_id_fkey") => CreateWorkOrderError::StaffNotFound,
+                _ => CreateWorkOrderError::InternalError,
+            });
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::CREATED,
+        CreateWorkOrderSuccess {
+            work_order: to_work_order(block, record),
+        },
+    ))
+}
+
+pub async fn list(
+    pool: &DbPool,
+    options: ListWorkOrdersOptions,
+    user: &SessionUser,
+) -> ApiResponse<ListWorkOrdersSuccess, ListWorkOrdersError> {
+    if !user.can(Permission::ManageMaintenance) {
+        return ApiResponse::error(ListWorkOrdersError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListWorkOrdersError::InternalError),
+    };
+
+    let open_work_orders = || {
+        let mut query = blocks::table
+            .inner_join(maintenance::table)
+            .filter(maintenance::closed_at.is_null())
+            .filter(blocks::released_at.is_null())
+            .into_boxed();
+
+        if let Some(room_id) = options.room_id {
+            query = query.filter(blocks::room_id.eq(room_id));
+        }
+        if let Some(kind) = options.kind {
+            query = query.filter(maintenance::kind.eq(MaintenanceKind::from(kind)));
+        }
+        if let Some(severity) = options.severity {
+            query = query.filter(maintenance::severity.eq(MaintenanceSeverity::from(severity)));
+        }
+
+        query
+    };
+
+    let total: i64 = match open_work_orders().count().get_result(&mut conn).await {
+        Ok(total) => total,
+        Err(_) => return ApiResponse::error(ListWorkOrdersError::InternalError),
+    };
+
+    let page = options.page.max(1);
+    let per_page = options.per_page.clamp(1, MAX_PER_PAGE);
+    let offset = (page - 1).saturating_mul(per_page);
+
+    let rows: Vec<(Block, Maintenance)> = match open_work_orders()
+        .select((Block::as_select(), Maintenance::as_select()))
+        .order(blocks::interval.asc())
+        .limit(per_page)
+        .offset(offset)
+        .load(&mut conn)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(_) => return ApiResponse::error(ListWorkOrdersError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        ListWorkOrdersSuccess {
+            work_orders: rows
+                .into_iter()
+                .map(|(block, record)| to_work_order(block, record))
+                .collect(),
+            total: total as usize,
+        },
+    ))
+}
+
+pub async fn reassign(
+    pool: &DbPool,
+    options: ReassignWorkOrderOptions,
+    user: &SessionUser,
+) -> ApiResponse<ReassignWorkOrderSuccess, ReassignWorkOrderError> {
+    if !user.can(Permission::ManageMaintenance) {
+        return ApiResponse::error(ReassignWorkOrderError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ReassignWorkOrderError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, record) = lock_open_work_order(conn, options.work_order_id).await?;
+
+                let record: Maintenance = diesel::update(maintenance::table.find(record.block_id))
+                    .set(maintenance::assigner_id.eq(options.assigner_id))
+                    .get_result(conn)
+                    .await?;
+
+                Ok((block, record))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, record) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(ReassignWorkOrderError::NotFound),
+        Err(TxError::AlreadyClosed) => {
+            return ApiResponse::error(ReassignWorkOrderError::AlreadyClosed);
+        }
+        Err(TxError::InvalidDateRange | TxError::Overlap(_)) => {
+            return ApiResponse::error(ReassignWorkOrderError::InternalError);
+        }
+        Err(TxError::Database(e)) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("maintenance_assigner_id_fkey") => ReassignWorkOrderError::StaffNotFound,
+                _ => ReassignWorkOrderError::InternalError,
+            });
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        ReassignWorkOrderSuccess {
+            work_order: to_work_order(block, record),
+        },
+    ))
+}
+
+pub async fn extend(
+    pool: &DbPool,
+    options: ExtendWorkOrderOptions,
+    user: &SessionUser,
+) -> ApiResponse<ExtendWorkOrderSuccess, ExtendWorkOrderError> {
+    if !user.can(Permission::ManageMaintenance) {
+        return ApiResponse::error(ExtendWorkOrderError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ExtendWorkOrderError::InternalError),
+    };
+
+    let new_end = Bound::Excluded(options.end);
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, record) = lock_open_work_order(conn, options.work_order_id).await?;
+
+                if UpperBound(new_end) <= UpperBound(block.interval.1) {
+                    return Err(TxError::InvalidDateRange);
+                }
+
+                let placement = Placement {
+                    block_id: Some(block.id),
+                    room_id: block.room_id,
+                    interval: (block.interval.0, new_end),
+                };
+
+                // The exclusion constraint refuses the new window if it runs into a live block
+                let block: Block = diesel::update(blocks::table.find(block.id))
+                    .set(blocks::interval.eq(placement.interval))
+                    .get_result(conn)
+                    .await
+                    .map_err(|e| placement.reject(e))?;
+
+                Ok((block, record))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, record) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(ExtendWorkOrderError::NotFound),
+        Err(TxError::AlreadyClosed) => {
+            return ApiResponse::error(ExtendWorkOrderError::AlreadyClosed);
+        }
+        Err(TxError::InvalidDateRange) => {
+            return ApiResponse::error(ExtendWorkOrderError::InvalidDateRange);
+        }
+        Err(TxError::Overlap(placement)) => {
+            return ApiResponse::error(ExtendWorkOrderError::RoomUnavailable {
+                conflicts: find_conflicts(&mut conn, &placement).await,
+            });
+        }
+        Err(TxError::Database(_)) => {
+            return ApiResponse::error(ExtendWorkOrderError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        ExtendWorkOrderSuccess {
+            work_order: to_work_order(block, record),
+        },
+    ))
+}
+
+/// Closes a work order, handing back whatever is left of its window.
+///
+/// Work that has not started yet releases the whole block; work in progress is
+/// shrunk to end now.
+pub async fn close(
+    pool: &DbPool,
+    options: CloseWorkOrderOptions,
+    user: &SessionUser,
+) -> ApiResponse<CloseWorkOrderSuccess, CloseWorkOrderError> {
+    if !user.can(Permission::ManageMaintenance) {
+        return ApiResponse::error(CloseWorkOrderError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CloseWorkOrderError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            async move {
+                let (block, record) = lock_open_work_order(conn, options.work_order_id).await?;
+
+                let now = Utc::now();
+                let block: Block =
+                    if LowerBound(block.interval.0) >= LowerBound(Bound::Included(now)) {
+                        diesel::update(blocks::table.find(block.id))
+                            .set(blocks::released_at.eq(diesel::dsl::now))
+                            .get_result(conn)
+                            .await?
+                    } else if UpperBound(block.interval.1) > UpperBound(Bound::Excluded(now)) {
+                        diesel::update(blocks::table.find(block.id))
+                            .set(blocks::interval.eq((block.interval.0, Bound::Excluded(now))))
+                            .get_result(conn)
+                            .await?
+                    } else {
+                        block
+                    };
+
+                let record: Maintenance = diesel::update(maintenance::table.find(record.block_id))
+                    .set(maintenance::closed_at.eq(now))
+                    .get_result(conn)
+                    .await?;
+
+                Ok((block, record))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (block, record) = match result {
+        Ok(data) => data,
+        Err(TxError::NotFound) => return ApiResponse::error(CloseWorkOrderError::NotFound),
+        Err(TxError::AlreadyClosed) => {
+            return ApiResponse::error(CloseWorkOrderError::AlreadyClosed);
+        }
+        Err(TxError::InvalidDateRange | TxError::Overlap(_) | TxError::Database(_)) => {
+            return ApiResponse::error(CloseWorkOrderError::InternalError);
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        CloseWorkOrderSuccess {
+            work_order: to_work_order(block, record),
+        },
+    ))
+}
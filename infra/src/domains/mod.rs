@@ -1,3 +1,14 @@
+pub mod api_key;
 pub mod auth;
+pub mod booking;
+pub mod housekeeping;
+pub mod maintenance;
+pub mod occupancy;
+pub mod promotion;
+pub mod quote;
+pub mod rate_plan;
+pub mod report;
 pub mod room;
+pub mod stay_rule;
+pub mod totp;
 pub mod user;
@@ -0,0 +1,387 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
+
+use crate::db::DbPool;
+use crate::models::{Block, BookingStatus, OccupancyBand as DbOccupancyBand};
+use crate::schema::{blocks, bookings, occupancy_bands, room_classes, rooms};
+use app::actix_web::HttpResponse;
+use app::actix_web::http::StatusCode;
+use app::api::ApiResponse;
+use app::auth::{Permission, SessionUser};
+use app::pricing::Stay;
+use app::pricing::curve::*;
+use app::pricing::occupancy::*;
+use bigdecimal::BigDecimal;
+use chrono::{Days, NaiveDate, NaiveTime, Utc};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+/// Bookings that hold on to their room and count as sold.
+const SOLD: [BookingStatus; 4] = [
+    BookingStatus::Pending,
+    BookingStatus::Confirmed,
+    BookingStatus::CheckedIn,
+    BookingStatus::CheckedOut,
+];
+
+impl From<DbOccupancyBand> for OccupancyBand {
+    fn from(band: DbOccupancyBand) -> Self {
+        OccupancyBand {
+            min_occupancy: band.min_occupancy,
+            adjustment: band.adjustment,
+        }
+    }
+}
+
+async fn load_bands(
+    conn: &mut AsyncPgConnection,
+    class_id: Uuid,
+) -> QueryResult<Vec<OccupancyBand>> {
+    let bands: Vec<DbOccupancyBand> = occupancy_bands::table
+        .filter(occupancy_bands::class_id.eq(class_id))
+        .select(DbOccupancyBand::as_select())
+        .order(occupancy_bands::min_occupancy.asc())
+        .load(conn)
+        .await?;
+
+    Ok(bands.into_iter().map(OccupancyBand::from).collect())
+}
+
+/// Loads the bands of each of `class_ids` that has any, keyed by class.
+async fn load_class_bands(
+    conn: &mut AsyncPgConnection,
+    class_ids: &[Uuid],
+) -> QueryResult<HashMap<Uuid, Vec<OccupancyBand>>> {
+    let bands: Vec<DbOccupancyBand> = occupancy_bands::table
+        .filter(occupancy_bands::class_id.eq_any(class_ids))
+        .select(DbOccupancyBand::as_select())
+        .order(occupancy_bands::min_occupancy.asc())
+        .load(conn)
+        .await?;
+
+    let mut by_class: HashMap<Uuid, Vec<OccupancyBand>> = HashMap::new();
+    for band in bands {
+        by_class
+            .entry(band.class_id)
+            .or_default()
+            .push(OccupancyBand::from(band));
+    }
+
+    Ok(by_class)
+}
+
+/// Counts the rooms of each of `class_ids`, and how many are sold for every night from `from`
+/// up to, but not including, `to`. Classes without rooms are left out.
+///
+/// A room is sold for the nights its live bookings charge for, so the morning of departure
+/// leaves the night free. The booking on `exclude`, if any, is left out of the count.
+async fn count_sold(
+    conn: &mut AsyncPgConnection,
+    class_ids: &[Uuid],
+    from: NaiveDate,
+    to: NaiveDate,
+    exclude: Option<Uuid>,
+) -> QueryResult<HashMap<Uuid, (i64, HashMap<NaiveDate, i64>)>> {
+    let rooms: Vec<(Uuid, i64)> = rooms::table
+        .filter(rooms::class_id.eq_any(class_ids))
+        .group_by(rooms::class_id)
+        .select((rooms::class_id, count_star()))
+        .load(conn)
+        .await?;
+
+    let window = (
+        Bound::Included(from.and_time(NaiveTime::MIN).and_utc()),
+        Bound::Excluded(to.and_time(NaiveTime::MIN).and_utc()),
+    );
+    let mut query = blocks::table
+        .inner_join(bookings::table)
+        .inner_join(rooms::table)
+        .filter(rooms::class_id.eq_any(class_ids))
+        .filter(blocks::released_at.is_null())
+        .filter(bookings::status.eq_any(SOLD))
+        .filter(blocks::interval.overlaps_with(window))
+        .select((rooms::class_id, Block::as_select()))
+        .into_boxed();
+
+    if let Some(block_id) = exclude {
+        query = query.filter(blocks::id.ne(block_id));
+    }
+
+    let booked: Vec<(Uuid, Block)> = query.load(conn).await?;
+
+    // A room counts once a night, however its bookings are split
+    let mut nights = HashSet::new();
+    for (class_id, block) in booked {
+        let (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) = block.interval
+        else {
+            continue;
+        };
+        let Some(stay) = Stay::from_interval(start, end, 1) else {
+            continue;
+        };
+        for night in stay
+            .check_in
+            .iter_days()
+            .take_while(|night| *night < stay.check_out)
+        {
+            if from <= night && night < to {
+                nights.insert((class_id, night, block.room_id));
+            }
+        }
+    }
+
+    let mut counts: HashMap<Uuid, (i64, HashMap<NaiveDate, i64>)> = rooms
+        .into_iter()
+        .map(|(class_id, rooms)| (class_id, (rooms, HashMap::new())))
+        .collect();
+    for (class_id, night, _) in nights {
+        *counts
+            .entry(class_id)
+            .or_default()
+            .1
+            .entry(night)
+            .or_insert(0) += 1;
+    }
+
+    Ok(counts)
+}
+
+/// Works out how full a room class is for every night from `from` up to, but not including,
+/// `to`, along with its bands.
+pub(crate) async fn load_occupancy(
+    conn: &mut AsyncPgConnection,
+    class_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> QueryResult<Occupancy> {
+    let bands = load_bands(conn, class_id).await?;
+    let (rooms, sold) = count_sold(conn, &[class_id], from, to, None)
+        .await?
+        .remove(&class_id)
+        .unwrap_or_default();
+
+    Ok(Occupancy { rooms, sold, bands })
+}
+
+/// Like [`load_occupancy`] over the nights of `stay`, for each of `class_ids` at once. Classes
+/// without bands are left out without counting anything, as their prices don't move. A
+/// booking being re-priced passes its block as `exclude`, so it doesn't push up its own price.
+pub(crate) async fn stay_occupancies(
+    conn: &mut AsyncPgConnection,
+    class_ids: &[Uuid],
+    stay: &Stay,
+    exclude: Option<Uuid>,
+) -> QueryResult<HashMap<Uuid, Occupancy>> {
+    let bands = load_class_bands(conn, class_ids).await?;
+    if bands.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let banded: Vec<Uuid> = bands.keys().copied().collect();
+    let mut counts = count_sold(conn, &banded, stay.check_in, stay.check_out, exclude).await?;
+
+    Ok(bands
+        .into_iter()
+        .map(|(class_id, bands)| {
+            let (rooms, sold) = counts.remove(&class_id).unwrap_or_default();
+            (class_id, Occupancy { rooms, sold, bands })
+        })
+        .collect())
+}
+
+pub async fn list_bands(
+    pool: &DbPool,
+    options: ListOccupancyBandsOptions,
+    user: &SessionUser,
+) -> ApiResponse<OccupancyBands, ListOccupancyBandsError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(ListOccupancyBandsError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListOccupancyBandsError::InternalError),
+    };
+
+    match room_classes::table
+        .find(options.class_id)
+        .select(room_classes::id)
+        .first::<Uuid>(&mut conn)
+        .await
+        .optional()
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return ApiResponse::error(ListOccupancyBandsError::NotFound),
+        Err(_) => return ApiResponse::error(ListOccupancyBandsError::InternalError),
+    }
+
+    match load_bands(&mut conn, options.class_id).await {
+        Ok(bands) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::OK,
+            OccupancyBands {
+                class_id: options.class_id,
+                bands,
+            },
+        )),
+        Err(_) => ApiResponse::error(ListOccupancyBandsError::InternalError),
+    }
+}
+
+enum TxError {
+    NotFound,
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+/// Replaces every occupancy band of a room class.
+pub async fn set_bands(
+    pool: &DbPool,
+    options: SetOccupancyBandsOptions,
+    request: SetOccupancyBandsRequest,
+    user: &SessionUser,
+) -> ApiResponse<OccupancyBands, SetOccupancyBandsError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(SetOccupancyBandsError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(SetOccupancyBandsError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(SetOccupancyBandsError::InternalError),
+    };
+
+    let class_id = options.class_id;
+    let rows: Vec<DbOccupancyBand> = request
+        .bands
+        .into_iter()
+        .map(|band| DbOccupancyBand {
+            class_id,
+            min_occupancy: band.min_occupancy,
+            adjustment: band.adjustment,
+        })
+        .collect();
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            let rows = &rows;
+            async move {
+                // Locking the class keeps concurrent replacements from interleaving
+                room_classes::table
+                    .find(class_id)
+                    .select(room_classes::id)
+                    .for_update()
+                    .first::<Uuid>(conn)
+                    .await
+                    .optional()?
+                    .ok_or(TxError::NotFound)?;
+
+                diesel::delete(
+                    occupancy_bands::table.filter(occupancy_bands::class_id.eq(class_id)),
+                )
+                .execute(conn)
+                .await?;
+
+                diesel::insert_into(occupancy_bands::table)
+                    .values(rows)
+                    .execute(conn)
+                    .await?;
+
+                Ok(load_bands(conn, class_id).await?)
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    match result {
+        Ok(bands) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::OK,
+            OccupancyBands { class_id, bands },
+        )),
+        Err(TxError::NotFound) => ApiResponse::error(SetOccupancyBandsError::NotFound),
+        Err(TxError::Database(e)) => {
+            tracing::error!("failed to set occupancy bands: {e}");
+            ApiResponse::error(SetOccupancyBandsError::InternalError)
+        }
+    }
+}
+
+/// Previews the base price of a room class night by night from tonight, as bookings stand.
+pub async fn price_curve(
+    pool: &DbPool,
+    options: PriceCurveOptions,
+    user: &SessionUser,
+) -> ApiResponse<PriceCurve, PriceCurveError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(PriceCurveError::Unauthorized);
+    }
+
+    if !(1..=MAX_CURVE_DAYS).contains(&options.days) {
+        return ApiResponse::error(PriceCurveError::InvalidDays);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(PriceCurveError::InternalError),
+    };
+
+    let base_price: BigDecimal = match room_classes::table
+        .find(options.class_id)
+        .select(room_classes::base_price)
+        .first(&mut conn)
+        .await
+        .optional()
+    {
+        Ok(Some(base_price)) => base_price,
+        Ok(None) => return ApiResponse::error(PriceCurveError::NotFound),
+        Err(_) => return ApiResponse::error(PriceCurveError::InternalError),
+    };
+
+    let from = Utc::now().date_naive();
+    let to = from + Days::new(u64::from(options.days));
+
+    let occupancy = match load_occupancy(&mut conn, options.class_id, from, to).await {
+        Ok(occupancy) => occupancy,
+        Err(_) => return ApiResponse::error(PriceCurveError::InternalError),
+    };
+
+    let points = from
+        .iter_days()
+        .take_while(|night| *night < to)
+        .map(|night| {
+            let band = occupancy.band_on(night).cloned();
+            PricePoint {
+                date: night,
+                sold: occupancy.sold_on(night),
+                price: band
+                    .as_ref()
+                    .map_or_else(|| base_price.clone(), |band| band.adjust(&base_price)),
+                band,
+            }
+        })
+        .collect();
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        PriceCurve {
+            class_id: options.class_id,
+            base_price,
+            rooms: occupancy.rooms,
+            points,
+        },
+    ))
+}
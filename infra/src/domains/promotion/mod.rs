@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use crate::db::DbPool;
+use crate::db::errors::{violated_foreign_key, violated_unique};
+use crate::models::{BookingStatus, NewPromoCode, PromoCode, PromoCodeClass};
+use crate::schema::{bookings, promo_code_classes, promo_codes};
+use app::actix_web::HttpResponse;
+use app::actix_web::http::StatusCode;
+use app::api::{ApiResponse, ValidationErrors};
+use app::auth::{Permission, SessionUser};
+use app::promotions::create::*;
+use app::promotions::disable::*;
+use app::promotions::list::*;
+use app::promotions::{PromoCodeDetails, normalize_code};
+use chrono::Utc;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use uuid::Uuid;
+
+/// Bookings in these states use up their promo code; cancelled ones and no-shows give it back.
+const REDEEMED: [BookingStatus; 4] = [
+    BookingStatus::Pending,
+    BookingStatus::Confirmed,
+    BookingStatus::CheckedIn,
+    BookingStatus::CheckedOut,
+];
+
+async fn load_details(
+    conn: &mut AsyncPgConnection,
+    codes: Vec<PromoCode>,
+) -> QueryResult<Vec<PromoCodeDetails>> {
+    let ids: Vec<Uuid> = codes.iter().map(|code| code.id).collect();
+
+    let classes: Vec<PromoCodeClass> = promo_code_classes::table
+        .filter(promo_code_classes::promo_code_id.eq_any(&ids))
+        .select(PromoCodeClass::as_select())
+        .load(conn)
+        .await?;
+
+    let uses: HashMap<Option<Uuid>, i64> = bookings::table
+        .filter(bookings::promo_code_id.eq_any(&ids))
+        .filter(bookings::status.eq_any(REDEEMED))
+        .group_by(bookings::promo_code_id)
+        .select((bookings::promo_code_id, count_star()))
+        .load::<(Option<Uuid>, i64)>(conn)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut class_ids: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for class in classes {
+        class_ids
+            .entry(class.promo_code_id)
+            .or_default()
+            .push(class.class_id);
+    }
+
+    Ok(codes
+        .into_iter()
+        .map(|code| PromoCodeDetails {
+            class_ids: class_ids.remove(&code.id).unwrap_or_default(),
+            uses: uses.get(&Some(code.id)).copied().unwrap_or(0),
+            id: code.id,
+            code: code.code,
+            campaign: code.campaign,
+            percent_off: code.percent_off,
+            amount_off: code.amount_off,
+            starts_at: code.starts_at,
+            ends_at: code.ends_at,
+            max_uses: code.max_uses,
+            max_uses_per_guest: code.max_uses_per_guest,
+            min_nights: code.min_nights,
+            disabled_at: code.disabled_at,
+        })
+        .collect())
+}
+
+/// Looks a promo code up as a guest typed it.
+///
+/// With `lock` the code stays locked until the transaction ends, so concurrent bookings
+/// can't both take its last use.
+pub(crate) async fn find_by_code(
+    conn: &mut AsyncPgConnection,
+    code: &str,
+    lock: bool,
+) -> QueryResult<Option<PromoCodeDetails>> {
+    let query = promo_codes::table
+        .filter(promo_codes::code.eq(normalize_code(code)))
+        .select(PromoCode::as_select());
+
+    let found = if lock {
+        query.for_update().first(conn).await.optional()?
+    } else {
+        query.first(conn).await.optional()?
+    };
+
+    match found {
+        Some(code) => Ok(load_details(conn, vec![code]).await?.pop()),
+        None => Ok(None),
+    }
+}
+
+/// Looks a promo code up by its id, as a booking holding it refers to it.
+pub(crate) async fn find_by_id(
+    conn: &mut AsyncPgConnection,
+    id: Uuid,
+) -> QueryResult<Option<PromoCodeDetails>> {
+    match promo_codes::table
+        .find(id)
+        .select(PromoCode::as_select())
+        .first(conn)
+        .await
+        .optional()?
+    {
+        Some(code) => Ok(load_details(conn, vec![code]).await?.pop()),
+        None => Ok(None),
+    }
+}
+
+/// Counts the bookings `guest_id` holds with a promo code.
+pub(crate) async fn guest_uses(
+    conn: &mut AsyncPgConnection,
+    promo_code_id: Uuid,
+    guest_id: Uuid,
+) -> QueryResult<i64> {
+    bookings::table
+        .filter(bookings::promo_code_id.eq(promo_code_id))
+        .filter(bookings::guest_id.eq(guest_id))
+        .filter(bookings::status.eq_any(REDEEMED))
+        .count()
+        .get_result(conn)
+        .await
+}
+
+pub async fn list(
+    pool: &DbPool,
+    user: &SessionUser,
+) -> ApiResponse<ListPromoCodesSuccess, ListPromoCodesError> {
+    if !user.can(Permission::ManagePromotions) {
+        return ApiResponse::error(ListPromoCodesError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListPromoCodesError::InternalError),
+    };
+
+    let codes: Vec<PromoCode> = match promo_codes::table
+        .select(PromoCode::as_select())
+        .order(promo_codes::created_at.desc())
+        .load(&mut conn)
+        .await
+    {
+        Ok(codes) => codes,
+        Err(_) => return ApiResponse::error(ListPromoCodesError::InternalError),
+    };
+
+    match load_details(&mut conn, codes).await {
+        Ok(promo_codes) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::OK,
+            ListPromoCodesSuccess { promo_codes },
+        )),
+        Err(_) => ApiResponse::error(ListPromoCodesError::InternalError),
+    }
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreatePromoCodeRequest,
+    user: &SessionUser,
+) -> ApiResponse<PromoCodeDetails, CreatePromoCodeError> {
+    if !user.can(Permission::ManagePromotions) {
+        return ApiResponse::error(CreatePromoCodeError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(CreatePromoCodeError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreatePromoCodeError::InternalError),
+    };
+
+    let code = normalize_code(&request.code);
+    let request = &request;
+    let result = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            let code = &code;
+            async move {
+                let promo: PromoCode = diesel::insert_into(promo_codes::table)
+                    .values(&NewPromoCode {
+                        code,
+                        campaign: request.campaign.trim(),
+                        percent_off: request.percent_off.clone(),
+                        amount_off: request.amount_off.clone(),
+                        starts_at: request.starts_at,
+                        ends_at: request.ends_at,
+                        max_uses: request.max_uses,
+                        max_uses_per_guest: request.max_uses_per_guest,
+                        min_nights: request.min_nights,
+                    })
+                    .returning(PromoCode::as_returning())
+                    .get_result(conn)
+                    .await?;
+
+                let classes: Vec<PromoCodeClass> = request
+                    .class_ids
+                    .iter()
+                    .map(|class_id| PromoCodeClass {
+                        promo_code_id: promo.id,
+                        class_id: *class_id,
+                    })
+                    .collect();
+
+                diesel::insert_into(promo_code_classes::table)
+                    .values(&classes)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .await?;
+
+                Ok(load_details(conn, vec![promo]).await?.remove(0))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    match result {
+        Ok(details) => ApiResponse::success(HttpResponse::with_body(StatusCode::CREATED, details)),
+        Err(e) => {
+            let error = if violated_unique(&e) == Some("promo_codes_code_key") {
+                ValidationErrors::field("code", "has already been taken")
+            } else if violated_foreign_key(&e) == Some("promo_code_classes_class_id_fkey") {
+                ValidationErrors::field("classIds", "contains an unknown room class")
+            } else {
+                return ApiResponse::error(CreatePromoCodeError::InternalError);
+            };
+            ApiResponse::error(CreatePromoCodeError::Invalid(error))
+        }
+    }
+}
+
+/// Stops a code from being redeemed. Bookings already made with it keep their discount.
+pub async fn disable(
+    pool: &DbPool,
+    options: DisablePromoCodeOptions,
+    user: &SessionUser,
+) -> ApiResponse<PromoCodeDetails, DisablePromoCodeError> {
+    if !user.can(Permission::ManagePromotions) {
+        return ApiResponse::error(DisablePromoCodeError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(DisablePromoCodeError::InternalError),
+    };
+
+    if diesel::update(
+        promo_codes::table
+            .find(options.id)
+            .filter(promo_codes::disabled_at.is_null()),
+    )
+    .set(promo_codes::disabled_at.eq(Utc::now()))
+    .execute(&mut conn)
+    .await
+    .is_err()
+    {
+        return ApiResponse::error(DisablePromoCodeError::InternalError);
+    }
+
+    let promo: PromoCode = match promo_codes::table
+        .find(options.id)
+        .select(PromoCode::as_select())
+        .first(&mut conn)
+        .await
+    {
+        Ok(promo) => promo,
+        Err(diesel::result::Error::NotFound) => {
+            return ApiResponse::error(DisablePromoCodeError::NotFound);
+        }
+        Err(_) => return ApiResponse::error(DisablePromoCodeError::InternalError),
+    };
+
+    match load_details(&mut conn, vec![promo]).await {
+        Ok(mut details) => {
+            ApiResponse::success(HttpResponse::with_body(StatusCode::OK, details.remove(0)))
+        }
+        Err(_) => ApiResponse::error(DisablePromoCodeError::InternalError),
+    }
+}
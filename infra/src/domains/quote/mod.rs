@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use crate::db::DbPool;
+use crate::domains::occupancy::stay_occupancies;
+use crate::domains::promotion::find_by_code;
+use crate::domains::rate_plan::plan_pricings;
+use crate::models::RatePlan;
+use crate::schema::{rate_plans, room_classes};
+use app::actix_web::HttpResponse;
+use app::actix_web::http::StatusCode;
+use app::api::ApiResponse;
+use app::pricing::quote::*;
+use app::pricing::{PlanPricing, Stay};
+use app::promotions::{PromoRejection, Redemption};
+use app::settings::PricingSettings;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+/// Quotes `stay` in each of `classes`, given with their base prices, under `rate_plan_id` or
+/// else the cheapest plan on sale in the class.
+///
+/// A class without plans on sale is quoted at its base price. Nights at a base price are moved
+/// by the occupancy band they reach, not counting the booking on `exclude_block`. Classes are
+/// left out when `rate_plan_id` is not one of their plans on sale. Everything is loaded for
+/// all the classes at once and priced in memory.
+async fn quote_many(
+    conn: &mut AsyncPgConnection,
+    classes: &HashMap<Uuid, BigDecimal>,
+    rate_plan_id: Option<Uuid>,
+    stay: &Stay,
+    exclude_block: Option<Uuid>,
+    settings: &PricingSettings,
+) -> QueryResult<HashMap<Uuid, Quote>> {
+    if classes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let class_ids: Vec<Uuid> = classes.keys().copied().collect();
+    let mut query = rate_plans::table
+        .filter(rate_plans::class_id.eq_any(&class_ids))
+        .filter(rate_plans::archived_at.is_null())
+        .select(RatePlan::as_select())
+        .order((rate_plans::created_at.asc(), rate_plans::id.asc()))
+        .into_boxed();
+
+    if let Some(plan_id) = rate_plan_id {
+        query = query.filter(rate_plans::id.eq(plan_id));
+    }
+
+    let plans: Vec<RatePlan> = query.load(conn).await?;
+    let pricings = plan_pricings(conn, &plans, classes).await?;
+    let occupancies = stay_occupancies(conn, &class_ids, stay, exclude_block).await?;
+
+    let quote = |class_id: Uuid, plan_id: Option<Uuid>, pricing: &PlanPricing| {
+        let mut quote = Quote::new(class_id, plan_id, pricing, stay, &settings.taxes);
+        if let Some(occupancy) = occupancies.get(&class_id) {
+            quote.apply_occupancy(occupancy, &settings.taxes);
+        }
+        quote
+    };
+
+    // Plans come oldest first, so the oldest wins a tie and the choice is stable between
+    // searches
+    let mut quotes: HashMap<Uuid, Quote> = HashMap::new();
+    for (plan, pricing) in plans.iter().zip(&pricings) {
+        let candidate = quote(plan.class_id, Some(plan.id), pricing);
+        match quotes.get(&plan.class_id) {
+            Some(best) if best.total <= candidate.total => {}
+            _ => {
+                quotes.insert(plan.class_id, candidate);
+            }
+        }
+    }
+
+    if rate_plan_id.is_none() {
+        for (class_id, base_price) in classes {
+            if !quotes.contains_key(class_id) {
+                let pricing = PlanPricing {
+                    base_price: base_price.clone(),
+                    seasons: Vec::new(),
+                    weekday_rules: Vec::new(),
+                };
+                quotes.insert(*class_id, quote(*class_id, None, &pricing));
+            }
+        }
+    }
+
+    Ok(quotes)
+}
+
+/// Quotes `stay` in a room class, as [`quote_many`] does. Returns `None` when `rate_plan_id`
+/// is not a plan of the class on sale.
+pub(crate) async fn quote_class(
+    conn: &mut AsyncPgConnection,
+    class_id: Uuid,
+    class_base_price: &BigDecimal,
+    rate_plan_id: Option<Uuid>,
+    stay: &Stay,
+    exclude_block: Option<Uuid>,
+    settings: &PricingSettings,
+) -> QueryResult<Option<Quote>> {
+    let classes = HashMap::from([(class_id, class_base_price.clone())]);
+    let mut quotes =
+        quote_many(conn, &classes, rate_plan_id, stay, exclude_block, settings).await?;
+
+    Ok(quotes.remove(&class_id))
+}
+
+/// Quotes the cheapest plan on sale in each of `class_ids` for `stay`.
+pub(crate) async fn quote_classes(
+    conn: &mut AsyncPgConnection,
+    class_ids: &[Uuid],
+    stay: &Stay,
+    settings: &PricingSettings,
+) -> QueryResult<HashMap<Uuid, Quote>> {
+    let classes: HashMap<Uuid, BigDecimal> = room_classes::table
+        .filter(room_classes::id.eq_any(class_ids))
+        .select((room_classes::id, room_classes::base_price))
+        .load::<(Uuid, BigDecimal)>(conn)
+        .await?
+        .into_iter()
+        .collect();
+
+    quote_many(conn, &classes, None, stay, None, settings).await
+}
+
+pub async fn get(
+    pool: &DbPool,
+    options: QuoteOptions,
+    settings: &PricingSettings,
+) -> ApiResponse<Quote, QuoteError> {
+    if options.guests == 0 {
+        return ApiResponse::error(QuoteError::InvalidGuests);
+    }
+
+    let stay = match Stay::from_interval(options.start, options.end, options.guests) {
+        Some(stay) => stay,
+        None => return ApiResponse::error(QuoteError::InvalidDateRange),
+    };
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(QuoteError::InternalError),
+    };
+
+    let base_price: BigDecimal = match room_classes::table
+        .find(options.class_id)
+        .select(room_classes::base_price)
+        .first(&mut conn)
+        .await
+    {
+        Ok(base_price) => base_price,
+        Err(diesel::result::Error::NotFound) => {
+            return ApiResponse::error(QuoteError::NotFound);
+        }
+        Err(_) => return ApiResponse::error(QuoteError::InternalError),
+    };
+
+    let mut quote = match quote_class(
+        &mut conn,
+        options.class_id,
+        &base_price,
+        options.rate_plan_id,
+        &stay,
+        None,
+        settings,
+    )
+    .await
+    {
+        Ok(Some(quote)) => quote,
+        Ok(None) => return ApiResponse::error(QuoteError::NotFound),
+        Err(_) => return ApiResponse::error(QuoteError::InternalError),
+    };
+
+    if let Some(code) = &options.promo_code {
+        let promo = match find_by_code(&mut conn, code, false).await {
+            Ok(Some(promo)) => promo,
+            Ok(None) => {
+                return ApiResponse::error(QuoteError::PromoRejected {
+                    rejection: PromoRejection::Unknown,
+                });
+            }
+            Err(_) => return ApiResponse::error(QuoteError::InternalError),
+        };
+
+        // Per guest limits are left to booking, as quotes may be asked for anonymously
+        let redemption = Redemption {
+            class_id: options.class_id,
+            nights: stay.nights(),
+            at: Utc::now(),
+            guest_uses: None,
+        };
+        if let Err(rejection) = promo.check(&redemption) {
+            return ApiResponse::error(QuoteError::PromoRejected { rejection });
+        }
+
+        quote.apply_promo(&promo, &settings.taxes);
+    }
+
+    ApiResponse::success(HttpResponse::with_body(StatusCode::OK, quote))
+}
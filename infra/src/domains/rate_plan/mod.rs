@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+
+use crate::db::DbPool;
+use crate::db::errors::{violated_constraint, violated_foreign_key, violated_unique};
+use crate::models::{NewRatePlan, NewRateSeason, RatePlan, RateSeason, RateWeekdayRule};
+use crate::schema::{rate_plans, rate_seasons, rate_weekday_rules};
+use app::actix_web::HttpResponse;
+use app::actix_web::http::StatusCode;
+use app::api::{ApiResponse, ValidationErrors};
+use app::auth::{Permission, SessionUser};
+use app::pricing::plans::*;
+use app::pricing::seasons::*;
+use app::pricing::weekdays::*;
+use app::pricing::{PlanPricing, Season, Weekday, WeekdayRule};
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use uuid::Uuid;
+
+/// Exclusion constraint that keeps the seasons of a plan from overlapping.
+const SEASONS_NO_OVERLAPS: &str = "rate_seasons_no_overlaps";
+
+fn season(season: RateSeason) -> Season {
+    Season {
+        id: season.id,
+        name: season.name,
+        starts_on: season.starts_on,
+        ends_on: season.ends_on,
+        nightly_price: season.nightly_price,
+    }
+}
+
+fn weekday_rule(rule: RateWeekdayRule) -> Option<WeekdayRule> {
+    Some(WeekdayRule {
+        weekday: Weekday::from_number(rule.weekday)?,
+        nightly_price: rule.nightly_price,
+    })
+}
+
+/// Loads the seasons and weekday rules of `plans`, keyed by plan.
+async fn load_rules(
+    conn: &mut AsyncPgConnection,
+    plans: &[RatePlan],
+) -> QueryResult<HashMap<Uuid, (Vec<Season>, Vec<WeekdayRule>)>> {
+    let seasons: Vec<RateSeason> = RateSeason::belonging_to(plans)
+        .select(RateSeason::as_select())
+        .order(rate_seasons::starts_on.asc())
+        .load(conn)
+        .await?;
+
+    let plan_ids: Vec<Uuid> = plans.iter().map(|plan| plan.id).collect();
+    let weekday_rules: Vec<RateWeekdayRule> = rate_weekday_rules::table
+        .filter(rate_weekday_rules::plan_id.eq_any(plan_ids))
+        .select(RateWeekdayRule::as_select())
+        .order(rate_weekday_rules::weekday.asc())
+        .load(conn)
+        .await?;
+
+    let mut rules: HashMap<Uuid, (Vec<Season>, Vec<WeekdayRule>)> = HashMap::new();
+    for row in seasons {
+        rules.entry(row.plan_id).or_default().0.push(season(row));
+    }
+    for row in weekday_rules {
+        let plan_id = row.plan_id;
+        if let Some(rule) = weekday_rule(row) {
+            rules.entry(plan_id).or_default().1.push(rule);
+        }
+    }
+
+    Ok(rules)
+}
+
+async fn load_details(
+    conn: &mut AsyncPgConnection,
+    plans: Vec<RatePlan>,
+) -> QueryResult<Vec<RatePlanDetails>> {
+    let mut rules = load_rules(conn, &plans).await?;
+
+    Ok(plans
+        .into_iter()
+        .map(|plan| {
+            let (seasons, weekday_rules) = rules.remove(&plan.id).unwrap_or_default();
+            RatePlanDetails {
+                id: plan.id,
+                class_id: plan.class_id,
+                name: plan.name,
+                base_price: plan.base_price,
+                refundable: plan.refundable,
+                includes_breakfast: plan.includes_breakfast,
+                archived_at: plan.archived_at,
+                seasons,
+                weekday_rules,
+            }
+        })
+        .collect())
+}
+
+/// Gathers what the pricing engine needs to price nights under each of `plans`, which may
+/// belong to several classes. `class_base_prices` holds the base price of each plan's class.
+pub(crate) async fn plan_pricings(
+    conn: &mut AsyncPgConnection,
+    plans: &[RatePlan],
+    class_base_prices: &HashMap<Uuid, BigDecimal>,
+) -> QueryResult<Vec<PlanPricing>> {
+    let mut rules = load_rules(conn, plans).await?;
+
+    Ok(plans
+        .iter()
+        .map(|plan| {
+            let (seasons, weekday_rules) = rules.remove(&plan.id).unwrap_or_default();
+            PlanPricing {
+                base_price: plan
+                    .base_price
+                    .clone()
+                    .unwrap_or_else(|| class_base_prices[&plan.class_id].clone()),
+                seasons,
+                weekday_rules,
+            }
+        })
+        .collect())
+}
+
+/// Lists rate plans, for one room class or all of them, in creation order.
+pub async fn list(
+    pool: &DbPool,
+    options: ListRatePlansOptions,
+) -> ApiResponse<ListRatePlansSuccess, ListRatePlansError> {
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListRatePlansError::InternalError),
+    };
+
+    let mut query = rate_plans::table
+        .select(RatePlan::as_select())
+        .order((rate_plans::created_at.asc(), rate_plans::id.asc()))
+        .into_boxed();
+
+    if let Some(class_id) = options.class_id {
+        query = query.filter(rate_plans::class_id.eq(class_id));
+    }
+    if !options.include_archived {
+        query = query.filter(rate_plans::archived_at.is_null());
+    }
+
+    let plans = match query.load(&mut conn).await {
+        Ok(plans) => plans,
+        Err(_) => return ApiResponse::error(ListRatePlansError::InternalError),
+    };
+
+    match load_details(&mut conn, plans).await {
+        Ok(rate_plans) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::OK,
+            ListRatePlansSuccess { rate_plans },
+        )),
+        Err(_) => ApiResponse::error(ListRatePlansError::InternalError),
+    }
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreateRatePlanRequest,
+    user: &SessionUser,
+) -> ApiResponse<RatePlanDetails, CreateRatePlanError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(CreateRatePlanError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(CreateRatePlanError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateRatePlanError::InternalError),
+    };
+
+    let plan: RatePlan = match diesel::insert_into(rate_plans::table)
+        .values(&NewRatePlan {
+            class_id: request.class_id,
+            name: request.name.trim(),
+            base_price: request.base_price,
+            refundable: request.refundable,
+            includes_breakfast: request.includes_breakfast,
+        })
+        .returning(RatePlan::as_returning())
+        .get_result(&mut conn)
+        .await
+    {
+        Ok(plan) => plan,
+        Err(e) => {
+            let error = if violated_unique(&e) == Some("rate_plans_class_name_key") {
+                ValidationErrors::field("name", "has already been taken")
+            } else if violated_foreign_key(&e) == Some("rate_plans_class_id_fkey") {
+                ValidationErrors::field("classId", "does not exist")
+            } else {
+                return ApiResponse::error(CreateRatePlanError::InternalError);
+            };
+            return ApiResponse::error(CreateRatePlanError::Invalid(error));
+        }
+    };
+
+    match load_details(&mut conn, vec![plan]).await {
+        Ok(mut plans) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::CREATED,
+            plans.remove(0),
+        )),
+        Err(_) => ApiResponse::error(CreateRatePlanError::InternalError),
+    }
+}
+
+/// Stops selling a plan. Archiving an archived plan keeps the original time.
+pub async fn archive(
+    pool: &DbPool,
+    options: ArchiveRatePlanOptions,
+    user: &SessionUser,
+) -> ApiResponse<RatePlanDetails, ArchiveRatePlanError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(ArchiveRatePlanError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ArchiveRatePlanError::InternalError),
+    };
+
+    if let Err(e) = diesel::update(
+        rate_plans::table
+            .find(options.plan_id)
+            .filter(rate_plans::archived_at.is_null()),
+    )
+    .set(rate_plans::archived_at.eq(Utc::now()))
+    .execute(&mut conn)
+    .await
+    {
+        tracing::error!("failed to archive rate plan: {e}");
+        return ApiResponse::error(ArchiveRatePlanError::InternalError);
+    }
+
+    let plan: RatePlan = match rate_plans::table
+        .find(options.plan_id)
+        .select(RatePlan::as_select())
+        .first(&mut conn)
+        .await
+    {
+        Ok(plan) => plan,
+        Err(diesel::result::Error::NotFound) => {
+            return ApiResponse::error(ArchiveRatePlanError::NotFound);
+        }
+        Err(_) => return ApiResponse::error(ArchiveRatePlanError::InternalError),
+    };
+
+    match load_details(&mut conn, vec![plan]).await {
+        Ok(mut plans) => {
+            ApiResponse::success(HttpResponse::with_body(StatusCode::OK, plans.remove(0)))
+        }
+        Err(_) => ApiResponse::error(ArchiveRatePlanError::InternalError),
+    }
+}
+
+pub async fn add_season(
+    pool: &DbPool,
+    options: AddSeasonOptions,
+    request: AddSeasonRequest,
+    user: &SessionUser,
+) -> ApiResponse<Season, AddSeasonError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(AddSeasonError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(AddSeasonError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(AddSeasonError::InternalError),
+    };
+
+    let row: RateSeason = match diesel::insert_into(rate_seasons::table)
+        .values(&NewRateSeason {
+            plan_id: options.plan_id,
+            name: request.name.trim(),
+            starts_on: request.starts_on,
+            ends_on: request.ends_on,
+            nightly_price: request.nightly_price,
+        })
+        .returning(RateSeason::as_returning())
+        .get_result(&mut conn)
+        .await
+    {
+        Ok(row) => row,
+        Err(e) => {
+            return ApiResponse::error(match violated_constraint(&e) {
+                Some(SEASONS_NO_OVERLAPS) => AddSeasonError::Invalid(ValidationErrors::field(
+                    "startsOn",
+                    "overlaps another season of the plan",
+                )),
+                Some("rate_seasons_plan_id_fkey") => AddSeasonError::NotFound,
+                _ => AddSeasonError::InternalError,
+            });
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(StatusCode::CREATED, season(row)))
+}
+
+pub async fn remove_season(
+    pool: &DbPool,
+    options: RemoveSeasonOptions,
+    user: &SessionUser,
+) -> ApiResponse<(), RemoveSeasonError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(RemoveSeasonError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(RemoveSeasonError::InternalError),
+    };
+
+    match diesel::delete(
+        rate_seasons::table
+            .find(options.season_id)
+            .filter(rate_seasons::plan_id.eq(options.plan_id)),
+    )
+    .execute(&mut conn)
+    .await
+    {
+        Ok(0) => ApiResponse::error(RemoveSeasonError::NotFound),
+        Ok(_) => ApiResponse::success(HttpResponse::with_body(StatusCode::NO_CONTENT, ())),
+        Err(_) => ApiResponse::error(RemoveSeasonError::InternalError),
+    }
+}
+
+/// Reasons replacing the weekday rules of a plan is rolled back.
+enum TxError {
+    NotFound,
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+/// Replaces every weekday rule of a plan.
+pub async fn set_weekday_rules(
+    pool: &DbPool,
+    options: SetWeekdayRulesOptions,
+    request: SetWeekdayRulesRequest,
+    user: &SessionUser,
+) -> ApiResponse<RatePlanDetails, SetWeekdayRulesError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(SetWeekdayRulesError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(SetWeekdayRulesError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(SetWeekdayRulesError::InternalError),
+    };
+
+    let plan_id = options.plan_id;
+    let rows: Vec<RateWeekdayRule> = request
+        .rules
+        .into_iter()
+        .map(|rule| RateWeekdayRule {
+            plan_id,
+            weekday: rule.weekday.number(),
+            nightly_price: rule.nightly_price,
+        })
+        .collect();
+
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            let rows = &rows;
+            async move {
+                // Locking the plan keeps concurrent replacements from interleaving
+                let plan: RatePlan = rate_plans::table
+                    .find(plan_id)
+                    .select(RatePlan::as_select())
+                    .for_update()
+                    .first(conn)
+                    .await
+                    .optional()?
+                    .ok_or(TxError::NotFound)?;
+
+                diesel::delete(
+                    rate_weekday_rules::table.filter(rate_weekday_rules::plan_id.eq(plan_id)),
+                )
+                .execute(conn)
+                .await?;
+
+                diesel::insert_into(rate_weekday_rules::table)
+                    .values(rows)
+                    .execute(conn)
+                    .await?;
+
+                Ok(load_details(conn, vec![plan]).await?.remove(0))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    match result {
+        Ok(details) => ApiResponse::success(HttpResponse::with_body(StatusCode::OK, details)),
+        Err(TxError::NotFound) => ApiResponse::error(SetWeekdayRulesError::NotFound),
+        Err(TxError::Database(e)) => {
+            tracing::error!("failed to set weekday rules: {e}");
+            ApiResponse::error(SetWeekdayRulesError::InternalError)
+        }
+    }
+}
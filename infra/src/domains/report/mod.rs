@@ -0,0 +1,258 @@
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use app::{
+    actix_web::{HttpResponse, http::StatusCode},
+    api::ApiResponse,
+    auth::{Permission, SessionUser},
+    reports::{ReportDetails, create::*, delete::*, list::*, restore::*, update::*},
+};
+
+use crate::{
+    db::DbPool,
+    models::{NewReport, Report, ReportChangeset},
+    schema::{blocks, bookings, reports},
+};
+
+impl From<Report> for ReportDetails {
+    fn from(report: Report) -> Self {
+        ReportDetails {
+            id: report.id,
+            block_id: report.block_id,
+            title: report.title,
+            description: report.description,
+            created_at: report.created_at,
+            updated_at: report.updated_at,
+            deleted_at: report.deleted_at,
+        }
+    }
+}
+
+/// Whether `user` may read and file reports on a block: moderators, or the guest it is booked for.
+///
+/// Returns `None` when the block does not exist.
+async fn can_access_block(
+    conn: &mut AsyncPgConnection,
+    block_id: Uuid,
+    user: &SessionUser,
+) -> QueryResult<Option<bool>> {
+    let guest_id: Option<Option<Uuid>> = blocks::table
+        .left_join(bookings::table)
+        .filter(blocks::id.eq(block_id))
+        .select(bookings::guest_id.nullable())
+        .first(conn)
+        .await
+        .optional()?;
+
+    Ok(guest_id.map(|guest_id| user.can(Permission::ModerateReports) || guest_id == Some(user.id)))
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreateReportRequest,
+    user: &SessionUser,
+) -> ApiResponse<CreateReportSuccess, CreateReportError> {
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateReportError::InternalError),
+    };
+
+    match can_access_block(&mut conn, request.block_id, user).await {
+        Ok(Some(true)) => {}
+        Ok(Some(false)) => return ApiResponse::error(CreateReportError::Unauthorized),
+        Ok(None) => return ApiResponse::error(CreateReportError::BlockNotFound),
+        Err(_) => return ApiResponse::error(CreateReportError::InternalError),
+    }
+
+    let report: Report = match diesel::insert_into(reports::table)
+        .values(&NewReport {
+            id: None,
+            block_id: request.block_id,
+            title: &request.title,
+            description: &request.description,
+        })
+        .get_result(&mut conn)
+        .await
+    {
+        Ok(report) => report,
+        Err(_) => return ApiResponse::error(CreateReportError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::CREATED,
+        CreateReportSuccess {
+            report: report.into(),
+        },
+    ))
+}
+
+pub async fn list(
+    pool: &DbPool,
+    options: ListReportsOptions,
+    user: &SessionUser,
+) -> ApiResponse<ListReportsSuccess, ListReportsError> {
+    if options.include_deleted && !user.can(Permission::ModerateReports) {
+        return ApiResponse::error(ListReportsError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListReportsError::InternalError),
+    };
+
+    match can_access_block(&mut conn, options.block_id, user).await {
+        Ok(Some(true)) => {}
+        Ok(Some(false)) => return ApiResponse::error(ListReportsError::Unauthorized),
+        Ok(None) => return ApiResponse::error(ListReportsError::BlockNotFound),
+        Err(_) => return ApiResponse::error(ListReportsError::InternalError),
+    }
+
+    let mut query = reports::table
+        .filter(reports::block_id.eq(options.block_id))
+        .into_boxed();
+
+    if !options.include_deleted {
+        query = query.filter(reports::deleted_at.is_null());
+    }
+
+    let rows: Vec<Report> = match query.order(reports::created_at.asc()).load(&mut conn).await {
+        Ok(rows) => rows,
+        Err(_) => return ApiResponse::error(ListReportsError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        ListReportsSuccess {
+            reports: rows.into_iter().map(Into::into).collect(),
+        },
+    ))
+}
+
+pub async fn update(
+    pool: &DbPool,
+    options: UpdateReportOptions,
+    user: &SessionUser,
+) -> ApiResponse<UpdateReportSuccess, UpdateReportError> {
+    if !user.can(Permission::ModerateReports) {
+        return ApiResponse::error(UpdateReportError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(UpdateReportError::InternalError),
+    };
+
+    let live_report = reports::table
+        .filter(reports::id.eq(options.report_id))
+        .filter(reports::deleted_at.is_null());
+
+    // Diesel rejects an empty changeset, so an empty update just returns the report
+    let result = if options.title.is_none() && options.description.is_none() {
+        live_report.first::<Report>(&mut conn).await
+    } else {
+        diesel::update(live_report)
+            .set(&ReportChangeset {
+                title: options.title,
+                description: options.description,
+            })
+            .get_result::<Report>(&mut conn)
+            .await
+    };
+
+    let report = match result.optional() {
+        Ok(Some(report)) => report,
+        Ok(None) => return ApiResponse::error(UpdateReportError::NotFound),
+        Err(_) => return ApiResponse::error(UpdateReportError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        UpdateReportSuccess {
+            report: report.into(),
+        },
+    ))
+}
+
+pub async fn delete(
+    pool: &DbPool,
+    options: DeleteReportOptions,
+    user: &SessionUser,
+) -> ApiResponse<DeleteReportSuccess, DeleteReportError> {
+    if !user.can(Permission::ModerateReports) {
+        return ApiResponse::error(DeleteReportError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(DeleteReportError::InternalError),
+    };
+
+    let report = match diesel::update(
+        reports::table
+            .filter(reports::id.eq(options.report_id))
+            .filter(reports::deleted_at.is_null()),
+    )
+    .set(reports::deleted_at.eq(diesel::dsl::now))
+    .get_result::<Report>(&mut conn)
+    .await
+    .optional()
+    {
+        Ok(Some(report)) => report,
+        Ok(None) => return ApiResponse::error(DeleteReportError::NotFound),
+        Err(_) => return ApiResponse::error(DeleteReportError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        DeleteReportSuccess {
+            report: report.into(),
+        },
+    ))
+}
+
+pub async fn restore(
+    pool: &DbPool,
+    options: RestoreReportOptions,
+    user: &SessionUser,
+) -> ApiResponse<RestoreReportSuccess, RestoreReportError> {
+    if !user.can(Permission::ModerateReports) {
+        return ApiResponse::error(RestoreReportError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(RestoreReportError::InternalError),
+    };
+
+    let report: Report = match reports::table
+        .find(options.report_id)
+        .first(&mut conn)
+        .await
+        .optional()
+    {
+        Ok(Some(report)) => report,
+        Ok(None) => return ApiResponse::error(RestoreReportError::NotFound),
+        Err(_) => return ApiResponse::error(RestoreReportError::InternalError),
+    };
+
+    if report.deleted_at.is_none() {
+        return ApiResponse::error(RestoreReportError::NotDeleted);
+    }
+
+    let report: Report = match diesel::update(reports::table.find(report.id))
+        .set(reports::deleted_at.eq(None::<chrono::DateTime<chrono::Utc>>))
+        .get_result(&mut conn)
+        .await
+    {
+        Ok(report) => report,
+        Err(_) => return ApiResponse::error(RestoreReportError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        RestoreReportSuccess {
+            report: report.into(),
+        },
+    ))
+}
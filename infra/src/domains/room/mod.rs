@@ -1,24 +1,30 @@
+use std::collections::HashMap;
 use std::ops::Bound;
 
 use diesel::{dsl::*, prelude::*};
 use diesel_async::RunQueryDsl;
+use uuid::Uuid;
 
 use app::{
     actix_web::{HttpResponse, http::StatusCode},
     api::ApiResponse,
-    auth::SessionUser,
+    auth::{Permission, SessionUser},
     interval::{LowerBound, UpperBound},
+    pricing::Stay,
     rooms::{availability::*, classes::*, details::*, find::*, list::*},
-    settings::ImageKitSettings,
+    settings::{ImageKitSettings, PricingSettings},
 };
 
 use crate::{
     db::DbPool,
+    domains::{quote::quote_classes, stay_rule::find_restrictions},
     models::{
-        Amenity, Block, Booking, Maintenance, Room as DbRoom, RoomClass, RoomClassAmenity,
-        RoomClassMedia, RoomMedia,
+        Amenity, Block, BlockKind as DbBlockKind, Booking, Housekeeping, Maintenance,
+        Room as DbRoom, RoomClass, RoomClassAmenity, RoomClassMedia, RoomMedia,
+    },
+    schema::{
+        amenities, blocks, bookings, housekeeping, maintenance, reports, room_classes, rooms,
     },
-    schema::{amenities, blocks, bookings, maintenance, room_classes, rooms},
     services::imagekit::generate_url,
 };
 
@@ -39,7 +45,7 @@ pub async fn list(
     user: &SessionUser,
     _settings: &ImageKitSettings,
 ) -> ApiResponse<ListRoomSuccess, ListRoomError> {
-    if user.staff_id.is_none() {
+    if !user.can(Permission::ReadRooms) {
         return ApiResponse::error(ListRoomError::Unauthorized);
     }
 
@@ -92,7 +98,7 @@ pub async fn get_availability(
     options: GetAvailabilityOptions,
     user: &SessionUser,
 ) -> ApiResponse<GetAvailabilitySuccess, GetAvailabilityError> {
-    if user.staff_id.is_none() {
+    if !user.can(Permission::ReadRooms) {
         return ApiResponse::error(GetAvailabilityError::Unauthorized);
     }
 
@@ -103,28 +109,60 @@ pub async fn get_availability(
 
     let period = (Bound::Included(options.start), Bound::Excluded(options.end));
 
-    let data: Vec<(Block, Option<Booking>, Option<Maintenance>)> = match blocks::table
+    type BlockRow = (
+        Block,
+        Option<Booking>,
+        Option<Housekeeping>,
+        Option<Maintenance>,
+    );
+
+    let data: Vec<BlockRow> = match blocks::table
         .filter(blocks::room_id.eq(options.room_id))
+        .filter(blocks::released_at.is_null())
         .filter(blocks::interval.overlaps_with(period))
         .left_join(bookings::table)
+        .left_join(housekeeping::table)
         .left_join(maintenance::table)
         .order(blocks::interval.asc())
-        .load::<(Block, Option<Booking>, Option<Maintenance>)>(&mut conn)
+        .load::<BlockRow>(&mut conn)
         .await
     {
         Ok(data) => data,
         Err(_) => return ApiResponse::error(GetAvailabilityError::NotFound),
     };
 
+    let block_ids: Vec<Uuid> = data.iter().map(|(block, ..)| block.id).collect();
+
+    let report_counts: HashMap<Uuid, i64> = match reports::table
+        .filter(reports::block_id.eq_any(&block_ids))
+        .filter(reports::deleted_at.is_null())
+        .group_by(reports::block_id)
+        .select((reports::block_id, count_star()))
+        .load::<(Uuid, i64)>(&mut conn)
+        .await
+    {
+        Ok(counts) => counts.into_iter().collect(),
+        Err(_) => return ApiResponse::error(GetAvailabilityError::InternalError),
+    };
+
     let calendar_blocks = data
         .into_iter()
-        .map(|(block, booking, maintenance_record)| {
-            let (kind, label) = if let Some(booking) = booking {
-                (BlockKind::Booking, Some(booking.status.to_string()))
-            } else if let Some(m) = maintenance_record {
-                (BlockKind::Maintenance, Some(format!("{:?}", m.kind)))
-            } else {
-                (BlockKind::Unknown, None)
+        .map(|(block, booking, housekeeping_task, maintenance_record)| {
+            let (kind, label) = match block.kind {
+                Some(DbBlockKind::Booking) => (
+                    BlockKind::Booking,
+                    booking.map(|booking| booking.status.to_string()),
+                ),
+                Some(DbBlockKind::Housekeeping) => (
+                    BlockKind::Housekeeping,
+                    housekeeping_task.map(|task| task.status.to_string()),
+                ),
+                Some(DbBlockKind::Maintenance) => (
+                    BlockKind::Maintenance,
+                    maintenance_record.map(|m| format!("{:?}", m.kind)),
+                ),
+                // Orphans that predate the discriminator; `migrator check` lists them
+                None => (BlockKind::Unknown, None),
             };
 
             CalendarBlock {
@@ -132,6 +170,7 @@ pub async fn get_availability(
                 period: block.interval,
                 kind,
                 label,
+                report_count: report_counts.get(&block.id).copied().unwrap_or(0),
             }
         })
         .collect::<Vec<_>>();
@@ -323,10 +362,14 @@ pub async fn get_classes(
 pub async fn find(
     pool: &DbPool,
     options: FindRoomOptions,
+    pricing: &PricingSettings,
 ) -> ApiResponse<FindRoomSuccess, FindRoomError> {
     if options.start >= options.end {
         return ApiResponse::error(FindRoomError::InvalidDateRange);
     }
+    if options.guests == 0 {
+        return ApiResponse::error(FindRoomError::InvalidGuests);
+    }
 
     let mut conn = match pool.get().await {
         Ok(conn) => conn,
@@ -344,21 +387,67 @@ pub async fn find(
     db_query = db_query.filter(not(exists(
         blocks::table
             .filter(blocks::room_id.eq(rooms::id))
+            .filter(blocks::released_at.is_null())
             .filter(blocks::interval.overlaps_with(search_range)),
     )));
 
-    let available_rooms: Vec<DbRoom> =
+    let mut available_rooms: Vec<DbRoom> =
         match db_query.select(DbRoom::as_select()).load(&mut conn).await {
             Ok(rooms) => rooms,
             Err(_) => return ApiResponse::error(FindRoomError::InternalError),
         };
 
+    let mut class_ids: Vec<Uuid> = match options.class_id {
+        Some(class_id) => vec![class_id],
+        None => available_rooms.iter().map(|r| r.class_id).collect(),
+    };
+    class_ids.sort();
+    class_ids.dedup();
+
+    let restrictions = match find_restrictions(
+        &mut conn,
+        &class_ids,
+        options.start.date_naive(),
+        options.end.date_naive(),
+    )
+    .await
+    {
+        Ok(restrictions) => restrictions,
+        Err(_) => return ApiResponse::error(FindRoomError::InternalError),
+    };
+
+    // A search that only stay rules leave empty says which rule is in the way
+    let had_rooms = !available_rooms.is_empty();
+    available_rooms.retain(|r| !restrictions.contains_key(&r.class_id));
+    if (options.class_id.is_some() || (had_rooms && available_rooms.is_empty()))
+        && let Some(restriction) = class_ids.iter().find_map(|id| restrictions.get(id))
+    {
+        return ApiResponse::error(FindRoomError::StayRestricted {
+            restriction: restriction.clone(),
+        });
+    }
+    class_ids.retain(|id| !restrictions.contains_key(id));
+
+    // Rooms of a class cost the same, so each class is quoted once
+    let quotes = match Stay::from_interval(options.start, options.end, options.guests) {
+        Some(stay) => match quote_classes(&mut conn, &class_ids, &stay, pricing).await {
+            Ok(quotes) => quotes,
+            Err(_) => return ApiResponse::error(FindRoomError::InternalError),
+        },
+        None => HashMap::new(),
+    };
+
     let response_rooms: Vec<RoomSummary> = available_rooms
         .into_iter()
-        .map(|r| RoomSummary {
-            id: r.id,
-            label: r.label,
-            class_id: r.class_id,
+        .map(|r| {
+            let quote = quotes.get(&r.class_id);
+            RoomSummary {
+                id: r.id,
+                label: r.label,
+                class_id: r.class_id,
+                rate_plan_id: quote.and_then(|quote| quote.rate_plan_id),
+                quoted_total: quote.map(|quote| quote.total.clone()),
+            }
         })
         .collect();
 
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use crate::db::DbPool;
+use crate::db::errors::violated_foreign_key;
+use crate::models::{NewStayRule, StayRule as DbStayRule};
+use crate::schema::stay_rules;
+use app::actix_web::HttpResponse;
+use app::actix_web::http::StatusCode;
+use app::api::{ApiResponse, ValidationErrors};
+use app::auth::{Permission, SessionUser};
+use app::stay_rules::create::*;
+use app::stay_rules::delete::*;
+use app::stay_rules::list::*;
+use app::stay_rules::{StayRestriction, StayRule, check_stay};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use uuid::Uuid;
+
+impl From<DbStayRule> for StayRule {
+    fn from(rule: DbStayRule) -> Self {
+        StayRule {
+            id: rule.id,
+            class_id: rule.class_id,
+            starts_on: rule.starts_on,
+            ends_on: rule.ends_on,
+            min_nights: rule.min_nights,
+            max_nights: rule.max_nights,
+            closed_to_arrival: rule.closed_to_arrival,
+            closed_to_departure: rule.closed_to_departure,
+        }
+    }
+}
+
+/// Checks a stay from `check_in` to `check_out` against the rules of each of `class_ids`.
+///
+/// Only the classes whose rules rule the stay out are returned, with the first rule broken.
+pub(crate) async fn find_restrictions(
+    conn: &mut AsyncPgConnection,
+    class_ids: &[Uuid],
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> QueryResult<HashMap<Uuid, StayRestriction>> {
+    let rules: Vec<DbStayRule> = stay_rules::table
+        .filter(stay_rules::class_id.eq_any(class_ids))
+        .filter(stay_rules::starts_on.le(check_out))
+        .filter(stay_rules::ends_on.ge(check_in))
+        .select(DbStayRule::as_select())
+        .order((stay_rules::starts_on.asc(), stay_rules::created_at.asc()))
+        .load(conn)
+        .await?;
+
+    let mut by_class: HashMap<Uuid, Vec<StayRule>> = HashMap::new();
+    for rule in rules {
+        by_class
+            .entry(rule.class_id)
+            .or_default()
+            .push(StayRule::from(rule));
+    }
+
+    Ok(by_class
+        .into_iter()
+        .filter_map(|(class_id, rules)| Some((class_id, check_stay(&rules, check_in, check_out)?)))
+        .collect())
+}
+
+pub async fn list(
+    pool: &DbPool,
+    options: ListStayRulesOptions,
+) -> ApiResponse<ListStayRulesSuccess, ListStayRulesError> {
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(ListStayRulesError::InternalError),
+    };
+
+    let mut query = stay_rules::table
+        .select(DbStayRule::as_select())
+        .order((stay_rules::starts_on.asc(), stay_rules::created_at.asc()))
+        .into_boxed();
+
+    if let Some(class_id) = options.class_id {
+        query = query.filter(stay_rules::class_id.eq(class_id));
+    }
+
+    match query.load(&mut conn).await {
+        Ok(rules) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::OK,
+            ListStayRulesSuccess {
+                stay_rules: rules.into_iter().map(StayRule::from).collect(),
+            },
+        )),
+        Err(_) => ApiResponse::error(ListStayRulesError::InternalError),
+    }
+}
+
+pub async fn create(
+    pool: &DbPool,
+    request: CreateStayRuleRequest,
+    user: &SessionUser,
+) -> ApiResponse<StayRule, CreateStayRuleError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(CreateStayRuleError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(CreateStayRuleError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(CreateStayRuleError::InternalError),
+    };
+
+    match diesel::insert_into(stay_rules::table)
+        .values(&NewStayRule {
+            class_id: request.class_id,
+            starts_on: request.starts_on,
+            ends_on: request.ends_on,
+            min_nights: request.min_nights,
+            max_nights: request.max_nights,
+            closed_to_arrival: request.closed_to_arrival,
+            closed_to_departure: request.closed_to_departure,
+        })
+        .returning(DbStayRule::as_returning())
+        .get_result(&mut conn)
+        .await
+    {
+        Ok(rule) => ApiResponse::success(HttpResponse::with_body(
+            StatusCode::CREATED,
+            StayRule::from(rule),
+        )),
+        Err(e) => ApiResponse::error(match violated_foreign_key(&e) {
+            Some("stay_rules_class_id_fkey") => {
+                CreateStayRuleError::Invalid(ValidationErrors::field("classId", "does not exist"))
+            }
+            _ => CreateStayRuleError::InternalError,
+        }),
+    }
+}
+
+pub async fn delete(
+    pool: &DbPool,
+    options: DeleteStayRuleOptions,
+    user: &SessionUser,
+) -> ApiResponse<(), DeleteStayRuleError> {
+    if !user.can(Permission::ManageRates) {
+        return ApiResponse::error(DeleteStayRuleError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(DeleteStayRuleError::InternalError),
+    };
+
+    match diesel::delete(stay_rules::table.find(options.id))
+        .execute(&mut conn)
+        .await
+    {
+        Ok(0) => ApiResponse::error(DeleteStayRuleError::NotFound),
+        Ok(_) => ApiResponse::success(HttpResponse::with_body(StatusCode::NO_CONTENT, ())),
+        Err(_) => ApiResponse::error(DeleteStayRuleError::InternalError),
+    }
+}
@@ -0,0 +1,245 @@
+use crate::db::DbPool;
+use crate::domains::auth::session_user;
+use crate::models::{NewTotpRecoveryCode, TotpCredential, User as DbUser};
+use crate::schema::{roles, totp_credentials, totp_recovery_codes, user_roles, users};
+use app::auth::SessionUser;
+use app::auth::totp::{
+    ConfirmTotpRequest, ConfirmTotpSuccess, DisableTotpRequest, EnrollTotpSuccess, TotpError,
+    generate_recovery_codes, generate_totp_secret, hash_recovery_code, provisioning_uri, totp_step,
+    verify_totp,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::{
+    AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+};
+use uuid::Uuid;
+
+/// Whether any role of the user requires a second factor.
+pub(crate) async fn is_required(conn: &mut AsyncPgConnection, user_id: Uuid) -> QueryResult<bool> {
+    diesel::select(diesel::dsl::exists(
+        user_roles::table
+            .inner_join(roles::table)
+            .filter(user_roles::user_id.eq(user_id))
+            .filter(roles::requires_totp),
+    ))
+    .get_result(conn)
+    .await
+}
+
+/// Returns the TOTP credential of a user, once its enrollment is confirmed.
+pub(crate) async fn find_credential(
+    conn: &mut AsyncPgConnection,
+    user_id: Uuid,
+) -> QueryResult<Option<TotpCredential>> {
+    totp_credentials::table
+        .find(user_id)
+        .filter(totp_credentials::confirmed_at.is_not_null())
+        .select(TotpCredential::as_select())
+        .first(conn)
+        .await
+        .optional()
+}
+
+/// Accepts `code` as a current TOTP code or an unused recovery code, using it up either way.
+pub(crate) async fn check_code(
+    conn: &mut AsyncPgConnection,
+    credential: &TotpCredential,
+    code: &str,
+) -> QueryResult<bool> {
+    let step = totp_step(Utc::now().timestamp());
+    let last_used_step = credential.last_used_step.map(|step| step as u64);
+
+    if let Some(step) = verify_totp(&credential.secret, code, step, last_used_step) {
+        // Conditional, so two requests racing with the same code cannot both get through
+        let updated = diesel::update(
+            totp_credentials::table.find(credential.user_id).filter(
+                totp_credentials::last_used_step
+                    .is_null()
+                    .or(totp_credentials::last_used_step.lt(step as i64)),
+            ),
+        )
+        .set(totp_credentials::last_used_step.eq(step as i64))
+        .execute(conn)
+        .await?;
+
+        return Ok(updated > 0);
+    }
+
+    let used = diesel::update(
+        totp_recovery_codes::table
+            .filter(totp_recovery_codes::user_id.eq(credential.user_id))
+            .filter(totp_recovery_codes::code_hash.eq(hash_recovery_code(code)))
+            .filter(totp_recovery_codes::used_at.is_null()),
+    )
+    .set(totp_recovery_codes::used_at.eq(diesel::dsl::now))
+    .execute(conn)
+    .await?;
+
+    Ok(used > 0)
+}
+
+/// Starts enrolling the user with a fresh secret, replacing any unconfirmed one.
+pub async fn enroll(
+    pool: &DbPool,
+    user: &SessionUser,
+    issuer: &str,
+) -> Result<EnrollTotpSuccess, TotpError> {
+    let mut conn = pool.get().await.map_err(|_| TotpError::InternalError)?;
+
+    let secret = generate_totp_secret();
+
+    // A confirmed credential survives both statements; TOTP has to be disabled to start over
+    diesel::delete(
+        totp_credentials::table
+            .find(user.id)
+            .filter(totp_credentials::confirmed_at.is_null()),
+    )
+    .execute(&mut conn)
+    .await
+    .map_err(|_| TotpError::InternalError)?;
+
+    let inserted = diesel::insert_into(totp_credentials::table)
+        .values((
+            totp_credentials::user_id.eq(user.id),
+            totp_credentials::secret.eq(&secret),
+        ))
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+        .await
+        .map_err(|_| TotpError::InternalError)?;
+
+    if inserted == 0 {
+        return Err(TotpError::AlreadyEnabled);
+    }
+
+    Ok(EnrollTotpSuccess {
+        provisioning_uri: provisioning_uri(&secret, issuer, &user.email),
+        secret,
+    })
+}
+
+/// Enables TOTP once the user proves their authenticator works, handing out recovery codes.
+///
+/// Returns the session rebuilt with every permission, which was withheld if a role of the
+/// user required TOTP.
+pub async fn confirm(
+    pool: &DbPool,
+    req: ConfirmTotpRequest,
+    user: &SessionUser,
+) -> Result<(SessionUser, ConfirmTotpSuccess), TotpError> {
+    let mut conn = pool.get().await.map_err(|_| TotpError::InternalError)?;
+
+    let credential: TotpCredential = totp_credentials::table
+        .find(user.id)
+        .select(TotpCredential::as_select())
+        .first(&mut conn)
+        .await
+        .optional()
+        .map_err(|_| TotpError::InternalError)?
+        .ok_or(TotpError::NotEnrolled)?;
+
+    if credential.confirmed_at.is_some() {
+        return Err(TotpError::AlreadyEnabled);
+    }
+
+    let step = totp_step(Utc::now().timestamp());
+    let step =
+        verify_totp(&credential.secret, &req.code, step, None).ok_or(TotpError::InvalidCode)?;
+
+    let recovery_codes = generate_recovery_codes();
+    let user_id = user.id;
+
+    let result = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            let recovery_codes = &recovery_codes;
+            async move {
+                diesel::update(totp_credentials::table.find(user_id))
+                    .set((
+                        totp_credentials::confirmed_at.eq(diesel::dsl::now),
+                        totp_credentials::last_used_step.eq(step as i64),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                diesel::delete(
+                    totp_recovery_codes::table.filter(totp_recovery_codes::user_id.eq(user_id)),
+                )
+                .execute(conn)
+                .await?;
+
+                let new_codes: Vec<NewTotpRecoveryCode> = recovery_codes
+                    .iter()
+                    .map(|code| NewTotpRecoveryCode {
+                        user_id,
+                        code_hash: hash_recovery_code(code),
+                    })
+                    .collect();
+
+                diesel::insert_into(totp_recovery_codes::table)
+                    .values(&new_codes)
+                    .execute(conn)
+                    .await?;
+
+                let user: DbUser = users::table.find(user_id).first(conn).await?;
+
+                session_user(conn, user).await
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    match result {
+        Ok(session) => Ok((session, ConfirmTotpSuccess { recovery_codes })),
+        Err(_) => Err(TotpError::InternalError),
+    }
+}
+
+/// Turns TOTP off, which takes a current code or recovery code and no role requiring it.
+pub async fn disable(
+    pool: &DbPool,
+    req: DisableTotpRequest,
+    user: &SessionUser,
+) -> Result<(), TotpError> {
+    let mut conn = pool.get().await.map_err(|_| TotpError::InternalError)?;
+
+    let credential = find_credential(&mut conn, user.id)
+        .await
+        .map_err(|_| TotpError::InternalError)?
+        .ok_or(TotpError::NotEnrolled)?;
+
+    if is_required(&mut conn, user.id)
+        .await
+        .map_err(|_| TotpError::InternalError)?
+    {
+        return Err(TotpError::Required);
+    }
+
+    if !check_code(&mut conn, &credential, &req.code)
+        .await
+        .map_err(|_| TotpError::InternalError)?
+    {
+        return Err(TotpError::InvalidCode);
+    }
+
+    let user_id = user.id;
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            diesel::delete(
+                totp_recovery_codes::table.filter(totp_recovery_codes::user_id.eq(user_id)),
+            )
+            .execute(conn)
+            .await?;
+
+            diesel::delete(totp_credentials::table.find(user_id))
+                .execute(conn)
+                .await?;
+
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(|_| TotpError::InternalError)
+}
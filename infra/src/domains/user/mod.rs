@@ -1,17 +1,36 @@
 use crate::db::DbPool;
-use crate::models::User as DbUser;
+use crate::db::errors::{violated_foreign_key, violated_unique};
+use crate::domains::auth;
+use crate::models::{NewStaff, NewUser, NewUserRole, Staff, User as DbUser};
 use crate::schema::users::dsl as users_dsl;
+use crate::schema::{login_throttles, staff, user_roles, users};
 use app::actix_web::HttpResponse;
 use app::actix_web::http::StatusCode;
-use app::api::ApiResponse;
+use app::api::{ApiResponse, ValidationErrors};
+use app::auth::login::{ThrottleScope, account_subject};
+use app::auth::otp::IssueOtpRequest;
+use app::auth::{Permission, SessionUser};
+use app::notifications::NotificationSender;
+use app::settings::OtpSettings;
 use app::users::details::*;
+use app::users::invite::*;
+use app::users::lockout::*;
+use app::users::roles::*;
+use app::users::sessions::*;
+use chrono::Utc;
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncConnection, RunQueryDsl, scoped_futures::ScopedFutureExt};
 
+/// Reads a user record; guests may only read their own.
 pub async fn get_details(
     pool: &DbPool,
     options: GetUserDetailsOptions,
+    user: &SessionUser,
 ) -> ApiResponse<GetUserDetailsSuccess, GetUserDetailsError> {
+    if options.user_id != user.id && !user.can(Permission::ReadUsers) {
+        return ApiResponse::error(GetUserDetailsError::Unauthorized);
+    }
+
     let mut conn = match pool.get().await {
         Ok(conn) => conn,
         Err(_) => return ApiResponse::error(GetUserDetailsError::InternalError),
@@ -39,3 +58,269 @@ pub async fn get_details(
         },
     ))
 }
+
+/// Creates a passwordless account, optionally with a staff record, and sends the invitee an
+/// onboarding code to set their password with.
+pub async fn invite(
+    pool: &DbPool,
+    request: InviteUserRequest,
+    user: &SessionUser,
+    otp_settings: &OtpSettings,
+    sender: &dyn NotificationSender,
+) -> ApiResponse<InviteUserSuccess, InviteUserError> {
+    if !user.can(Permission::ManageUsers) {
+        return ApiResponse::error(InviteUserError::Unauthorized);
+    }
+
+    let errors = request.validate();
+    if !errors.is_empty() {
+        return ApiResponse::error(InviteUserError::Invalid(errors));
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(InviteUserError::InternalError),
+    };
+
+    let result = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                let invitee: DbUser = diesel::insert_into(users::table)
+                    .values(&NewUser {
+                        id: None,
+                        email: request.email.trim(),
+                        password_hash: None,
+                    })
+                    .get_result(conn)
+                    .await?;
+
+                let staff_id = if request.staff {
+                    let record: Staff = diesel::insert_into(staff::table)
+                        .values(&NewStaff {
+                            id: None,
+                            user_id: invitee.id,
+                        })
+                        .get_result(conn)
+                        .await?;
+                    Some(record.id)
+                } else {
+                    None
+                };
+
+                Ok((invitee, staff_id))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let (invitee, staff_id) = match result {
+        Ok(data) => data,
+        Err(e) => {
+            return ApiResponse::error(match violated_unique(&e) {
+                Some("users_email_key") => InviteUserError::Invalid(ValidationErrors::field(
+                    "email",
+                    "has already been taken",
+                )),
+                _ => InviteUserError::InternalError,
+            });
+        }
+    };
+
+    // The account stands even if delivery fails; the code can be reissued from /auth/otp
+    let otp = match auth::issue_otp(
+        pool,
+        IssueOtpRequest {
+            user_id: invitee.id,
+        },
+        otp_settings,
+        sender,
+    )
+    .await
+    {
+        Ok(otp) => otp,
+        Err(_) => return ApiResponse::error(InviteUserError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::CREATED,
+        InviteUserSuccess {
+            user: UserDetails {
+                id: invitee.id,
+                email: invitee.email,
+            },
+            staff_id,
+            otp_expires_at: otp.expires_at,
+        },
+    ))
+}
+
+/// Signs a user out everywhere, e.g. when a staff member leaves.
+pub async fn revoke_sessions(
+    pool: &DbPool,
+    options: RevokeSessionsOptions,
+    user: &SessionUser,
+) -> ApiResponse<RevokeSessionsSuccess, RevokeSessionsError> {
+    if !user.can(Permission::ManageUsers) {
+        return ApiResponse::error(RevokeSessionsError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(RevokeSessionsError::InternalError),
+    };
+
+    let revoked_at = Utc::now();
+
+    match diesel::update(users::table.find(options.user_id))
+        .set(users::sessions_revoked_at.eq(revoked_at))
+        .execute(&mut conn)
+        .await
+    {
+        Ok(0) => return ApiResponse::error(RevokeSessionsError::NotFound),
+        Ok(_) => {}
+        Err(_) => return ApiResponse::error(RevokeSessionsError::InternalError),
+    }
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        RevokeSessionsSuccess { revoked_at },
+    ))
+}
+
+/// Lifts a sign-in lockout early and clears the failures counted against the account.
+///
+/// Lockouts of the client addresses involved are left to expire on their own.
+pub async fn unlock(
+    pool: &DbPool,
+    options: UnlockAccountOptions,
+    user: &SessionUser,
+) -> ApiResponse<UnlockAccountSuccess, UnlockAccountError> {
+    if !user.can(Permission::ManageUsers) {
+        return ApiResponse::error(UnlockAccountError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(UnlockAccountError::InternalError),
+    };
+
+    let email: String = match users::table
+        .find(options.user_id)
+        .select(users::email)
+        .first(&mut conn)
+        .await
+    {
+        Ok(email) => email,
+        Err(diesel::result::Error::NotFound) => {
+            return ApiResponse::error(UnlockAccountError::NotFound);
+        }
+        Err(_) => return ApiResponse::error(UnlockAccountError::InternalError),
+    };
+
+    let cleared = match diesel::delete(
+        login_throttles::table
+            .filter(login_throttles::scope.eq(ThrottleScope::Account.as_str()))
+            .filter(login_throttles::subject.eq(account_subject(&email))),
+    )
+    .execute(&mut conn)
+    .await
+    {
+        Ok(cleared) => cleared,
+        Err(_) => return ApiResponse::error(UnlockAccountError::InternalError),
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        UnlockAccountSuccess {
+            had_failures: cleared > 0,
+        },
+    ))
+}
+
+/// Reasons a role assignment is rolled back.
+enum TxError {
+    NotFound,
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for TxError {
+    fn from(error: diesel::result::Error) -> Self {
+        TxError::Database(error)
+    }
+}
+
+/// Replaces the roles of a user and signs them out, so their next session carries the new
+/// permissions.
+pub async fn assign_roles(
+    pool: &DbPool,
+    options: AssignRolesOptions,
+    request: AssignRolesRequest,
+    user: &SessionUser,
+) -> ApiResponse<AssignRolesSuccess, AssignRolesError> {
+    if !user.can(Permission::ManageRoles) {
+        return ApiResponse::error(AssignRolesError::Unauthorized);
+    }
+
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(_) => return ApiResponse::error(AssignRolesError::InternalError),
+    };
+
+    let mut roles = request.roles;
+    roles.sort();
+    roles.dedup();
+
+    let user_id = options.user_id;
+    let result = conn
+        .transaction::<_, TxError, _>(|conn| {
+            let roles = &roles;
+            async move {
+                let updated = diesel::update(users::table.find(user_id))
+                    .set(users::sessions_revoked_at.eq(Utc::now()))
+                    .execute(conn)
+                    .await?;
+                if updated == 0 {
+                    return Err(TxError::NotFound);
+                }
+
+                diesel::delete(user_roles::table.filter(user_roles::user_id.eq(user_id)))
+                    .execute(conn)
+                    .await?;
+
+                let rows: Vec<NewUserRole> = roles
+                    .iter()
+                    .map(|role_name| NewUserRole { user_id, role_name })
+                    .collect();
+                diesel::insert_into(user_roles::table)
+                    .values(&rows)
+                    .execute(conn)
+                    .await?;
+
+                Ok(auth::find_permissions(conn, user_id).await?)
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    let permissions = match result {
+        Ok(permissions) => permissions,
+        Err(TxError::NotFound) => return ApiResponse::error(AssignRolesError::NotFound),
+        Err(TxError::Database(e)) => {
+            return ApiResponse::error(match violated_foreign_key(&e) {
+                Some("user_roles_role_name_fkey") => AssignRolesError::Invalid(
+                    ValidationErrors::field("roles", "contains an unknown role"),
+                ),
+                _ => AssignRolesError::InternalError,
+            });
+        }
+    };
+
+    ApiResponse::success(HttpResponse::with_body(
+        StatusCode::OK,
+        AssignRolesSuccess {
+            user_id,
+            roles,
+            permissions,
+        },
+    ))
+}
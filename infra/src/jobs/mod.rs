@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use app::settings::BookingSettings;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::{db::DbPool, domains::booking};
+
+/// Periodically releases pending bookings whose hold has expired.
+pub fn spawn_hold_sweeper(pool: DbPool, settings: &BookingSettings) -> JoinHandle<()> {
+    let period = Duration::from_secs(settings.sweep_interval.max(1));
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            let mut conn = match pool.get().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("Hold sweeper could not get a connection: {e}");
+                    continue;
+                }
+            };
+
+            match booking::expire_holds(&mut conn).await {
+                Ok(0) => {}
+                Ok(released) => info!("Released {released} expired booking holds"),
+                Err(e) => warn!("Failed to release expired booking holds: {e}"),
+            }
+        }
+    })
+}
@@ -1,5 +1,6 @@
 pub mod db;
 pub mod domains;
+pub mod jobs;
 pub mod models;
 
 pub mod schema;
@@ -1,5 +1,5 @@
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use derive_more::Display;
 use diesel::prelude::*;
 use std::collections::Bound;
@@ -7,11 +7,31 @@ use uuid::Uuid;
 
 use crate::schema::*;
 
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, diesel_derive_enum::DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::BlockKind"]
+pub enum BlockKind {
+    Booking,
+    Housekeeping,
+    Maintenance,
+}
+
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, diesel_derive_enum::DbEnum)]
 #[ExistingTypePath = "crate::schema::sql_types::BookingStatus"]
 pub enum BookingStatus {
     Pending,
     Confirmed,
+    Cancelled,
+    CheckedIn,
+    CheckedOut,
+    NoShow,
+}
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, diesel_derive_enum::DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::HousekeepingStatus"]
+pub enum HousekeepingStatus {
+    Pending,
+    InProgress,
+    Completed,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, diesel_derive_enum::DbEnum)]
@@ -41,6 +61,13 @@ pub enum MaintenanceSeverity {
     High,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, diesel_derive_enum::DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::OtpPurpose"]
+pub enum OtpPurpose {
+    Onboarding,
+    PasswordReset,
+}
+
 // =========================================================================
 //  USERS & STAFF
 // =========================================================================
@@ -53,6 +80,7 @@ pub struct User {
     pub password_hash: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub sessions_revoked_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -80,6 +108,101 @@ pub struct NewStaff {
     pub user_id: Uuid,
 }
 
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = user_roles)]
+pub struct NewUserRole<'a> {
+    pub user_id: Uuid,
+    pub role_name: &'a str,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = otps)]
+pub struct NewOtp<'a> {
+    pub user_id: Uuid,
+    pub code: &'a str,
+    pub expires_at: DateTime<Utc>,
+    pub purpose: OtpPurpose,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = revoked_sessions)]
+pub struct NewRevokedSession {
+    pub token_id: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq)]
+#[diesel(table_name = login_throttles)]
+pub struct LoginThrottle {
+    pub scope: String,
+    pub subject: String,
+    pub failures: i32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = login_failures)]
+pub struct NewLoginFailure<'a> {
+    pub email: &'a str,
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<&'a str>,
+    pub locked: bool,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = login_challenges)]
+pub struct NewLoginChallenge {
+    pub token_hash: String,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq)]
+#[diesel(table_name = totp_credentials)]
+#[diesel(primary_key(user_id))]
+pub struct TotpCredential {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = totp_recovery_codes)]
+pub struct NewTotpRecoveryCode {
+    pub user_id: Uuid,
+    pub code_hash: String,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq)]
+#[diesel(table_name = api_keys)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub key_hash: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKey<'a> {
+    pub name: &'a str,
+    pub key_hash: &'a str,
+    pub key_prefix: &'a str,
+    pub scopes: &'a [String],
+    pub created_by: Uuid,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
 // =========================================================================
 //  ROOMS & CLASSES
 // =========================================================================
@@ -107,6 +230,8 @@ pub struct RoomClass {
     pub name: String,
     pub base_price: BigDecimal,
     pub created_at: DateTime<Utc>,
+    /// Hours of housekeeping scheduled after each stay, if any
+    pub turnover_hours: Option<i32>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -115,6 +240,139 @@ pub struct NewRoomClass<'a> {
     pub id: Option<Uuid>,
     pub name: &'a str,
     pub base_price: BigDecimal,
+    pub turnover_hours: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
+#[diesel(belongs_to(RoomClass, foreign_key = class_id))]
+#[diesel(table_name = rate_plans)]
+pub struct RatePlan {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub name: String,
+    /// The class base price applies when unset
+    pub base_price: Option<BigDecimal>,
+    pub refundable: bool,
+    pub includes_breakfast: bool,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = rate_plans)]
+pub struct NewRatePlan<'a> {
+    pub class_id: Uuid,
+    pub name: &'a str,
+    pub base_price: Option<BigDecimal>,
+    pub refundable: bool,
+    pub includes_breakfast: bool,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
+#[diesel(belongs_to(RatePlan, foreign_key = plan_id))]
+#[diesel(table_name = rate_seasons)]
+pub struct RateSeason {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub name: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub nightly_price: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = rate_seasons)]
+pub struct NewRateSeason<'a> {
+    pub plan_id: Uuid,
+    pub name: &'a str,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub nightly_price: BigDecimal,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = occupancy_bands)]
+pub struct OccupancyBand {
+    pub class_id: Uuid,
+    /// Percentage of the class's rooms sold for the night
+    pub min_occupancy: i16,
+    /// Percentage added to the base price
+    pub adjustment: BigDecimal,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = rate_weekday_rules)]
+pub struct RateWeekdayRule {
+    pub plan_id: Uuid,
+    /// ISO 8601 day of the week, 1 for Monday
+    pub weekday: i16,
+    pub nightly_price: BigDecimal,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq)]
+#[diesel(table_name = promo_codes)]
+pub struct PromoCode {
+    pub id: Uuid,
+    pub code: String,
+    pub campaign: String,
+    pub percent_off: Option<BigDecimal>,
+    pub amount_off: Option<BigDecimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_guest: Option<i32>,
+    pub min_nights: Option<i32>,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = promo_codes)]
+pub struct NewPromoCode<'a> {
+    pub code: &'a str,
+    pub campaign: &'a str,
+    pub percent_off: Option<BigDecimal>,
+    pub amount_off: Option<BigDecimal>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_guest: Option<i32>,
+    pub min_nights: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = promo_code_classes)]
+pub struct PromoCodeClass {
+    pub promo_code_id: Uuid,
+    pub class_id: Uuid,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
+#[diesel(belongs_to(RoomClass, foreign_key = class_id))]
+#[diesel(table_name = stay_rules)]
+pub struct StayRule {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub min_nights: Option<i32>,
+    pub max_nights: Option<i32>,
+    pub closed_to_arrival: bool,
+    pub closed_to_departure: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = stay_rules)]
+pub struct NewStayRule {
+    pub class_id: Uuid,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub min_nights: Option<i32>,
+    pub max_nights: Option<i32>,
+    pub closed_to_arrival: bool,
+    pub closed_to_departure: bool,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
@@ -217,6 +475,9 @@ pub struct Block {
     pub interval: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub released_at: Option<DateTime<Utc>>,
+    /// Which child table holds the details; only missing on blocks that predate the column
+    pub kind: Option<BlockKind>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -225,10 +486,11 @@ pub struct NewBlock {
     pub id: Option<Uuid>,
     pub room_id: Uuid,
     pub interval: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+    pub kind: BlockKind,
 }
 
 // =========================================================================
-//  BOOKINGS & MAINTENANCE
+//  BOOKINGS, HOUSEKEEPING & MAINTENANCE
 // =========================================================================
 
 #[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
@@ -240,6 +502,10 @@ pub struct Booking {
     pub block_id: Uuid,
     pub guest_id: Uuid,
     pub status: BookingStatus,
+    pub hold_expires_at: Option<DateTime<Utc>>,
+    pub promo_code_id: Option<Uuid>,
+    pub discount: Option<BigDecimal>,
+    pub guests: i32,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -248,6 +514,65 @@ pub struct NewBooking {
     pub block_id: Uuid,
     pub guest_id: Uuid,
     pub status: BookingStatus,
+    pub hold_expires_at: Option<DateTime<Utc>>,
+    pub promo_code_id: Option<Uuid>,
+    pub discount: Option<BigDecimal>,
+    pub guests: i32,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
+#[diesel(belongs_to(Booking))]
+#[diesel(table_name = booking_transitions)]
+pub struct BookingTransition {
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub from_status: BookingStatus,
+    pub to_status: BookingStatus,
+    pub performed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = booking_transitions)]
+pub struct NewBookingTransition {
+    pub booking_id: Uuid,
+    pub from_status: BookingStatus,
+    pub to_status: BookingStatus,
+    pub performed_by: Option<Uuid>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
+#[diesel(belongs_to(Block))]
+#[diesel(belongs_to(Staff, foreign_key = assignee_id))]
+#[diesel(table_name = housekeeping)]
+#[diesel(primary_key(block_id))]
+pub struct Housekeeping {
+    pub block_id: Uuid,
+    pub status: HousekeepingStatus,
+    pub instructions: Option<String>,
+    pub assignee_id: Option<Uuid>,
+    /// Set on turnover tasks scheduled after a booking
+    pub booking_id: Option<Uuid>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = housekeeping)]
+pub struct NewHousekeeping {
+    pub block_id: Uuid,
+    pub status: HousekeepingStatus,
+    pub instructions: Option<String>,
+    pub assignee_id: Option<Uuid>,
+    /// Set on turnover tasks scheduled after a booking
+    pub booking_id: Option<Uuid>,
+}
+
+/// Partial update of a housekeeping task; `None` fields are left untouched.
+#[derive(AsChangeset, Debug, Clone, Default)]
+#[diesel(table_name = housekeeping)]
+pub struct HousekeepingChangeset {
+    pub status: Option<HousekeepingStatus>,
+    pub instructions: Option<String>,
+    pub assignee_id: Option<Uuid>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone, PartialEq)]
@@ -260,6 +585,7 @@ pub struct Maintenance {
     pub kind: MaintenanceKind,
     pub severity: MaintenanceSeverity,
     pub assigner_id: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -292,3 +618,11 @@ pub struct NewReport<'a> {
     pub title: &'a str,
     pub description: &'a str,
 }
+
+/// Partial update of a report; `None` fields are left untouched.
+#[derive(AsChangeset, Debug, Clone, Default)]
+#[diesel(table_name = reports)]
+pub struct ReportChangeset {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
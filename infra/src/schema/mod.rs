@@ -1,27 +1,39 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "block_kind"))]
+    pub struct BlockKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "booking_status"))]
     pub struct BookingStatus;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "housekeeping_status"))]
+    pub struct HousekeepingStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "maintenance_kind"))]
     pub struct MaintenanceKind;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "maintenance_severity"))]
     pub struct MaintenanceSeverity;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "media_kind"))]
     pub struct MediaKind;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "otp_purpose"))]
+    pub struct OtpPurpose;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "transaction_kind"))]
     pub struct TransactionKind;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "transaction_status"))]
     pub struct TransactionStatus;
 }
@@ -35,12 +47,46 @@ diesel::table! {
 }
 
 diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        name -> Text,
+        key_hash -> Text,
+        key_prefix -> Text,
+        scopes -> Array<Text>,
+        created_by -> Uuid,
+        created_at -> Timestamptz,
+        expires_at -> Nullable<Timestamptz>,
+        last_used_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BlockKind;
+
     blocks (id) {
         id -> Uuid,
         room_id -> Uuid,
         interval -> Tstzrange,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        released_at -> Nullable<Timestamptz>,
+        kind -> Nullable<BlockKind>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BookingStatus;
+
+    booking_transitions (id) {
+        id -> Uuid,
+        booking_id -> Uuid,
+        from_status -> BookingStatus,
+        to_status -> BookingStatus,
+        performed_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -52,6 +98,53 @@ diesel::table! {
         block_id -> Uuid,
         guest_id -> Uuid,
         status -> BookingStatus,
+        hold_expires_at -> Nullable<Timestamptz>,
+        promo_code_id -> Nullable<Uuid>,
+        discount -> Nullable<Numeric>,
+        guests -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::HousekeepingStatus;
+
+    housekeeping (block_id) {
+        block_id -> Uuid,
+        status -> HousekeepingStatus,
+        instructions -> Nullable<Text>,
+        assignee_id -> Nullable<Uuid>,
+        booking_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    login_challenges (token_hash) {
+        token_hash -> Text,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    login_failures (id) {
+        id -> Uuid,
+        email -> Text,
+        user_id -> Nullable<Uuid>,
+        ip_address -> Nullable<Text>,
+        locked -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    login_throttles (scope, subject) {
+        scope -> Text,
+        subject -> Text,
+        failures -> Int4,
+        last_failed_at -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
     }
 }
 
@@ -65,10 +158,22 @@ diesel::table! {
         kind -> MaintenanceKind,
         severity -> MaintenanceSeverity,
         assigner_id -> Nullable<Uuid>,
+        closed_at -> Nullable<Timestamptz>,
     }
 }
 
 diesel::table! {
+    occupancy_bands (class_id, min_occupancy) {
+        class_id -> Uuid,
+        min_occupancy -> Int2,
+        adjustment -> Numeric,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::OtpPurpose;
+
     otps (id) {
         id -> Uuid,
         user_id -> Uuid,
@@ -77,6 +182,71 @@ diesel::table! {
         expires_at -> Timestamptz,
         used_at -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
+        purpose -> OtpPurpose,
+    }
+}
+
+diesel::table! {
+    permissions (name) {
+        name -> Text,
+        description -> Text,
+    }
+}
+
+diesel::table! {
+    promo_code_classes (promo_code_id, class_id) {
+        promo_code_id -> Uuid,
+        class_id -> Uuid,
+    }
+}
+
+diesel::table! {
+    promo_codes (id) {
+        id -> Uuid,
+        code -> Text,
+        campaign -> Text,
+        percent_off -> Nullable<Numeric>,
+        amount_off -> Nullable<Numeric>,
+        starts_at -> Nullable<Timestamptz>,
+        ends_at -> Nullable<Timestamptz>,
+        max_uses -> Nullable<Int4>,
+        max_uses_per_guest -> Nullable<Int4>,
+        min_nights -> Nullable<Int4>,
+        disabled_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    rate_plans (id) {
+        id -> Uuid,
+        class_id -> Uuid,
+        name -> Text,
+        base_price -> Nullable<Numeric>,
+        refundable -> Bool,
+        includes_breakfast -> Bool,
+        archived_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    rate_seasons (id) {
+        id -> Uuid,
+        plan_id -> Uuid,
+        name -> Text,
+        starts_on -> Date,
+        ends_on -> Date,
+        nightly_price -> Numeric,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    rate_weekday_rules (plan_id, weekday) {
+        plan_id -> Uuid,
+        weekday -> Int2,
+        nightly_price -> Numeric,
     }
 }
 
@@ -92,12 +262,37 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    revoked_sessions (token_id) {
+        token_id -> Uuid,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    role_permissions (role_name, permission_name) {
+        role_name -> Text,
+        permission_name -> Text,
+    }
+}
+
+diesel::table! {
+    roles (name) {
+        name -> Text,
+        description -> Text,
+        requires_totp -> Bool,
+    }
+}
+
 diesel::table! {
     room_classes (id) {
         id -> Uuid,
         name -> Text,
         base_price -> Numeric,
         created_at -> Timestamptz,
+        turnover_hours -> Nullable<Int4>,
     }
 }
 
@@ -159,6 +354,40 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    stay_rules (id) {
+        id -> Uuid,
+        class_id -> Uuid,
+        starts_on -> Date,
+        ends_on -> Date,
+        min_nights -> Nullable<Int4>,
+        max_nights -> Nullable<Int4>,
+        closed_to_arrival -> Bool,
+        closed_to_departure -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    totp_credentials (user_id) {
+        user_id -> Uuid,
+        secret -> Text,
+        confirmed_at -> Nullable<Timestamptz>,
+        last_used_step -> Nullable<Int8>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    totp_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::TransactionStatus;
@@ -178,6 +407,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_roles (user_id, role_name) {
+        user_id -> Uuid,
+        role_name -> Text,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Uuid,
@@ -185,36 +421,81 @@ diesel::table! {
         password_hash -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        sessions_revoked_at -> Nullable<Timestamptz>,
     }
 }
 
+diesel::joinable!(api_keys -> staff (created_by));
 diesel::joinable!(blocks -> rooms (room_id));
+diesel::joinable!(booking_transitions -> bookings (booking_id));
+diesel::joinable!(booking_transitions -> users (performed_by));
 diesel::joinable!(bookings -> blocks (block_id));
+diesel::joinable!(bookings -> promo_codes (promo_code_id));
 diesel::joinable!(bookings -> users (guest_id));
+diesel::joinable!(housekeeping -> blocks (block_id));
+diesel::joinable!(housekeeping -> bookings (booking_id));
+diesel::joinable!(housekeeping -> staff (assignee_id));
+diesel::joinable!(login_challenges -> users (user_id));
+diesel::joinable!(login_failures -> users (user_id));
 diesel::joinable!(maintenance -> blocks (block_id));
 diesel::joinable!(maintenance -> staff (assigner_id));
+diesel::joinable!(occupancy_bands -> room_classes (class_id));
 diesel::joinable!(otps -> users (user_id));
+diesel::joinable!(promo_code_classes -> promo_codes (promo_code_id));
+diesel::joinable!(promo_code_classes -> room_classes (class_id));
+diesel::joinable!(rate_plans -> room_classes (class_id));
+diesel::joinable!(rate_seasons -> rate_plans (plan_id));
+diesel::joinable!(rate_weekday_rules -> rate_plans (plan_id));
+diesel::joinable!(reports -> blocks (block_id));
+diesel::joinable!(revoked_sessions -> users (user_id));
+diesel::joinable!(role_permissions -> permissions (permission_name));
+diesel::joinable!(role_permissions -> roles (role_name));
 diesel::joinable!(room_classes_amenities -> amenities (amenity_id));
 diesel::joinable!(room_classes_amenities -> room_classes (room_class_id));
 diesel::joinable!(room_classes_media -> room_classes (class_id));
 diesel::joinable!(rooms -> room_classes (class_id));
 diesel::joinable!(rooms_media -> rooms (room_id));
 diesel::joinable!(staff -> users (user_id));
+diesel::joinable!(stay_rules -> room_classes (class_id));
+diesel::joinable!(totp_credentials -> users (user_id));
+diesel::joinable!(totp_recovery_codes -> users (user_id));
 diesel::joinable!(transactions -> bookings (booking_id));
+diesel::joinable!(user_roles -> roles (role_name));
+diesel::joinable!(user_roles -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     amenities,
+    api_keys,
     blocks,
+    booking_transitions,
     bookings,
+    housekeeping,
+    login_challenges,
+    login_failures,
+    login_throttles,
     maintenance,
+    occupancy_bands,
     otps,
+    promo_code_classes,
+    promo_codes,
+    permissions,
+    rate_plans,
+    rate_seasons,
+    rate_weekday_rules,
     reports,
+    revoked_sessions,
+    role_permissions,
+    roles,
     room_classes,
     room_classes_amenities,
     room_classes_media,
     rooms,
     rooms_media,
     staff,
+    stay_rules,
+    totp_credentials,
+    totp_recovery_codes,
     transactions,
+    user_roles,
     users,
 );
@@ -1 +1,2 @@
 pub mod imagekit;
+pub mod notifications;
@@ -0,0 +1,98 @@
+use std::{path::PathBuf, sync::Arc};
+
+use app::{
+    notifications::{Notification, NotificationSender, SendFuture},
+    settings::{NotificationChannel, NotificationSettings},
+};
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+/// Renders a notification as a single line for the local senders, leaving the code out
+/// unless `with_code` is set.
+fn render(notification: &Notification, with_code: bool) -> String {
+    let (kind, email, code, expires_at) = match notification {
+        Notification::Otp {
+            email,
+            code,
+            expires_at,
+        } => ("otp", email, code, expires_at),
+        Notification::PasswordReset {
+            email,
+            code,
+            expires_at,
+        } => ("password_reset", email, code, expires_at),
+    };
+    let code = if with_code {
+        code.as_str()
+    } else {
+        "<redacted>"
+    };
+
+    format!(
+        "{kind} to={email} code={code} expires_at={}",
+        expires_at.to_rfc3339()
+    )
+}
+
+/// Writes notifications to the application log instead of delivering them.
+///
+/// Logs are collected and kept well beyond a code's lifetime, so codes are redacted.
+pub struct LogSender;
+
+impl NotificationSender for LogSender {
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            info!("notification {}", render(notification, false));
+            Ok(())
+        })
+    }
+}
+
+/// Appends notifications to a file, one per line, for local testing.
+pub struct FileSender {
+    path: PathBuf,
+}
+
+impl FileSender {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl NotificationSender for FileSender {
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            file.write_all(format!("{}\n", render(notification, true)).as_bytes())
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Builds the sender selected in the settings.
+///
+/// The `file` sender keeps codes in plain text, so it is refused outside `development`.
+pub fn init_sender(
+    settings: &NotificationSettings,
+    environment: &str,
+) -> Result<Arc<dyn NotificationSender>, String> {
+    if settings.sender == NotificationChannel::File && environment != "development" {
+        return Err(format!(
+            "the file notification sender can't be used in {environment}"
+        ));
+    }
+
+    Ok(match settings.sender {
+        NotificationChannel::Log => Arc::new(LogSender),
+        NotificationChannel::File => Arc::new(FileSender::new(
+            settings.path.as_deref().unwrap_or("notifications.log"),
+        )),
+    })
+}
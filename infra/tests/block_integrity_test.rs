@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::{Duration, Utc};
+    use diesel_async::{AsyncConnection, RunQueryDsl, scoped_futures::ScopedFutureExt};
+    use std::collections::Bound;
+
+    use config::{Config, File};
+    use uuid::Uuid;
+
+    use app::AppSettings;
+    use infra::db::{self, errors::is_block_integrity_violation};
+    use infra::models::{
+        BlockKind, MaintenanceKind, MaintenanceSeverity, NewBlock, NewMaintenance, NewRoom,
+        NewRoomClass,
+    };
+    use infra::schema::{blocks, maintenance, room_classes, rooms};
+
+    #[tokio::test]
+    async fn test_block_requires_matching_child() {
+        let app_config = {
+            dotenvy::from_filename(".env.test").ok();
+
+            let app_config: AppSettings = Config::builder()
+                .add_source(File::with_name("../config/default"))
+                .add_source(File::with_name("../config/development"))
+                .add_source(config::Environment::with_prefix("APP").separator("__"))
+                .build()
+                .expect("Failed to build configuration")
+                .try_deserialize()
+                .expect("Failed to deserialize configuration");
+
+            app_config
+        };
+
+        let pool =
+            db::init_pool(&app_config.database).expect("Failed to initialize pg connection pool");
+
+        let mut conn = pool.get().await.unwrap();
+
+        // 1. Create Room Class and Room
+        let class_id = Uuid::new_v4();
+        diesel::insert_into(room_classes::table)
+            .values(&NewRoomClass {
+                id: Some(class_id),
+                name: "Standard",
+                base_price: BigDecimal::from(100),
+                turnover_hours: None,
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let room_id = Uuid::new_v4();
+        diesel::insert_into(rooms::table)
+            .values(&NewRoom {
+                id: Some(room_id),
+                label: "301",
+                class_id,
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let start = Utc::now() + Duration::days(10);
+        let new_block = NewBlock {
+            id: None,
+            room_id,
+            interval: (
+                Bound::Included(start),
+                Bound::Excluded(start + Duration::days(1)),
+            ),
+            kind: BlockKind::Booking,
+        };
+
+        // 2. A block committed without any child is rejected
+        let result = diesel::insert_into(blocks::table)
+            .values(&new_block)
+            .execute(&mut conn)
+            .await;
+        let error = result.expect_err("Orphan block should be rejected");
+        assert!(is_block_integrity_violation(&error));
+
+        // 3. A child in the wrong table is rejected too
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let block_id: Uuid = diesel::insert_into(blocks::table)
+                        .values(&new_block)
+                        .returning(blocks::id)
+                        .get_result(conn)
+                        .await?;
+
+                    diesel::insert_into(maintenance::table)
+                        .values(&NewMaintenance {
+                            block_id,
+                            kind: MaintenanceKind::Other,
+                            severity: MaintenanceSeverity::Low,
+                            assigner_id: None,
+                        })
+                        .execute(conn)
+                        .await?;
+
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await;
+        let error = result.expect_err("Booking block with a maintenance child should be rejected");
+        assert!(is_block_integrity_violation(&error));
+    }
+}
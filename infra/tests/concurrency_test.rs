@@ -2,7 +2,10 @@
 mod tests {
     use bigdecimal::BigDecimal;
     use chrono::{TimeZone, Utc};
-    use diesel_async::RunQueryDsl;
+    use diesel::QueryResult;
+    use diesel_async::{
+        AsyncConnection, AsyncPgConnection, RunQueryDsl, scoped_futures::ScopedFutureExt,
+    };
     use std::collections::Bound;
 
     use config::{Config, File};
@@ -10,8 +13,41 @@ mod tests {
 
     use app::AppSettings;
     use infra::db;
-    use infra::models::{NewBlock, NewRoom, NewRoomClass};
-    use infra::schema::{blocks, room_classes, rooms};
+    use infra::models::{
+        BlockKind, MaintenanceKind, MaintenanceSeverity, NewBlock, NewMaintenance, NewRoom,
+        NewRoomClass,
+    };
+    use infra::schema::{blocks, maintenance, room_classes, rooms};
+
+    /// Blocks are committed together with their child row, as the integrity triggers require.
+    async fn insert_maintenance_block(
+        conn: &mut AsyncPgConnection,
+        block: NewBlock,
+    ) -> QueryResult<()> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                let block_id: Uuid = diesel::insert_into(blocks::table)
+                    .values(&block)
+                    .returning(blocks::id)
+                    .get_result(conn)
+                    .await?;
+
+                diesel::insert_into(maintenance::table)
+                    .values(&NewMaintenance {
+                        block_id,
+                        kind: MaintenanceKind::Other,
+                        severity: MaintenanceSeverity::Low,
+                        assigner_id: None,
+                    })
+                    .execute(conn)
+                    .await?;
+
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+    }
 
     #[tokio::test]
     async fn test_double_booking_prevention() {
@@ -20,6 +56,7 @@ mod tests {
 
             let app_config: AppSettings = Config::builder()
                 .add_source(File::with_name("../config/default"))
+                .add_source(File::with_name("../config/development"))
                 .add_source(config::Environment::with_prefix("APP").separator("__"))
                 .build()
                 .expect("Failed to build configuration")
@@ -46,6 +83,7 @@ mod tests {
             id: Some(class_id),
             name: "Standard",
             base_price: BigDecimal::from(100),
+            turnover_hours: None,
         };
 
         diesel::insert_into(room_classes::table)
@@ -78,12 +116,10 @@ mod tests {
             id: None,
             room_id,
             interval: (Bound::Included(start1), Bound::Excluded(end1)),
+            kind: BlockKind::Maintenance,
         };
 
-        let result1 = diesel::insert_into(blocks::table)
-            .values(&block1)
-            .execute(&mut conn)
-            .await;
+        let result1 = insert_maintenance_block(&mut conn, block1).await;
 
         assert!(result1.is_ok(), "First block should succeed");
 
@@ -95,12 +131,10 @@ mod tests {
             id: None,
             room_id,
             interval: (Bound::Included(start2), Bound::Excluded(end2)),
+            kind: BlockKind::Maintenance,
         };
 
-        let result2 = diesel::insert_into(blocks::table)
-            .values(&block2)
-            .execute(&mut conn)
-            .await;
+        let result2 = insert_maintenance_block(&mut conn, block2).await;
 
         assert!(result2.is_err(), "Second block should fail due to overlap");
     }
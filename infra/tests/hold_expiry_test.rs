@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::{Duration, Utc};
+    use diesel_async::RunQueryDsl;
+
+    use config::{Config, File};
+    use uuid::Uuid;
+
+    use app::AppSettings;
+    use app::api::ApiResponse;
+    use app::auth::SessionUser;
+    use app::bookings::create::CreateBookingRequest;
+    use app::rooms::find::FindRoomOptions;
+    use app::settings::{BookingSettings, PricingSettings};
+    use infra::db::{self, DbPool};
+    use infra::domains::{booking, room};
+    use infra::models::{NewRoom, NewRoomClass, NewUser};
+    use infra::schema::{room_classes, rooms, users};
+
+    async fn is_room_free(pool: &DbPool, room_id: Uuid, options: &FindRoomOptions) -> bool {
+        let pricing = PricingSettings { taxes: Vec::new() };
+        match room::find(pool, options.clone(), &pricing).await {
+            ApiResponse::Success(response) => response
+                .into_body()
+                .rooms
+                .iter()
+                .any(|room| room.id == room_id),
+            ApiResponse::Error(e) => panic!("Room search failed: {}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_hold_frees_room() {
+        let app_config = {
+            dotenvy::from_filename(".env.test").ok();
+
+            let app_config: AppSettings = Config::builder()
+                .add_source(File::with_name("../config/default"))
+                .add_source(File::with_name("../config/development"))
+                .add_source(config::Environment::with_prefix("APP").separator("__"))
+                .build()
+                .expect("Failed to build configuration")
+                .try_deserialize()
+                .expect("Failed to deserialize configuration");
+
+            app_config
+        };
+
+        let pool =
+            db::init_pool(&app_config.database).expect("Failed to initialize pg connection pool");
+
+        let mut conn = pool.get().await.unwrap();
+
+        // 1. Create Room Class, Room and Guest
+        let class_id = Uuid::new_v4();
+        diesel::insert_into(room_classes::table)
+            .values(&NewRoomClass {
+                id: Some(class_id),
+                name: "Standard",
+                base_price: BigDecimal::from(100),
+                turnover_hours: None,
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let room_id = Uuid::new_v4();
+        diesel::insert_into(rooms::table)
+            .values(&NewRoom {
+                id: Some(room_id),
+                label: "201",
+                class_id,
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let guest_id = Uuid::new_v4();
+        let email = format!("{}@test.com", guest_id);
+        diesel::insert_into(users::table)
+            .values(&NewUser {
+                id: Some(guest_id),
+                email: &email,
+                password_hash: None,
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let guest = SessionUser {
+            id: guest_id,
+            staff_id: None,
+            email,
+            permissions: Vec::new(),
+        };
+
+        let start = Utc::now() + Duration::days(10);
+        let end = start + Duration::days(2);
+        let search = FindRoomOptions {
+            start,
+            end,
+            class_id: Some(class_id),
+            guests: 1,
+        };
+
+        // 2. Place a hold that lapses immediately
+        let settings = BookingSettings {
+            hold_ttl: 0,
+            sweep_interval: 60,
+        };
+        let request = CreateBookingRequest {
+            room_id,
+            start,
+            end,
+            guest_id: None,
+            guests: 1,
+            promo_code: None,
+        };
+        let pricing = PricingSettings { taxes: Vec::new() };
+
+        let created = booking::create(&pool, request, &guest, &settings, &pricing).await;
+        assert!(
+            matches!(created, ApiResponse::Success(_)),
+            "Hold should be placed"
+        );
+
+        assert!(
+            !is_room_free(&pool, room_id, &search).await,
+            "Room should be held by the pending booking"
+        );
+
+        // 3. Sweep and check the room is sellable again
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let released = booking::expire_holds(&mut conn).await.unwrap();
+        assert!(released >= 1, "Expired hold should be released");
+
+        assert!(
+            is_room_free(&pool, room_id, &search).await,
+            "Room should be available once the hold lapses"
+        );
+    }
+}
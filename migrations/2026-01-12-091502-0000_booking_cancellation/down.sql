@@ -0,0 +1,21 @@
+-- This file should undo anything in `up.sql`
+
+DROP TRIGGER IF EXISTS update_blocks_modtime ON blocks;
+
+DELETE FROM blocks WHERE released_at IS NOT NULL;
+
+ALTER TABLE blocks DROP CONSTRAINT no_overlaps;
+ALTER TABLE blocks ADD CONSTRAINT no_overlaps EXCLUDE USING GIST (
+    room_id WITH =,
+    interval WITH &&
+);
+
+ALTER TABLE blocks DROP COLUMN released_at;
+
+ALTER TYPE booking_status RENAME TO booking_status_old;
+CREATE TYPE booking_status AS ENUM ('pending', 'confirmed');
+ALTER TABLE bookings
+    ALTER COLUMN status DROP DEFAULT,
+    ALTER COLUMN status TYPE booking_status USING status::text::booking_status,
+    ALTER COLUMN status SET DEFAULT 'confirmed';
+DROP TYPE booking_status_old;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+
+ALTER TYPE booking_status ADD VALUE IF NOT EXISTS 'cancelled';
+
+-- Released blocks stay on record but no longer hold the room
+ALTER TABLE blocks ADD COLUMN released_at TIMESTAMPTZ;
+
+ALTER TABLE blocks DROP CONSTRAINT no_overlaps;
+ALTER TABLE blocks ADD CONSTRAINT no_overlaps EXCLUDE USING GIST (
+    room_id WITH =,
+    interval WITH &&
+) WHERE (released_at IS NULL);
+
+CREATE TRIGGER update_blocks_modtime
+BEFORE UPDATE ON blocks
+FOR EACH ROW
+EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE IF EXISTS booking_transitions;
+
+DELETE FROM blocks
+WHERE id IN (
+    SELECT block_id FROM bookings WHERE status IN ('checked_in', 'checked_out', 'no_show')
+);
+
+ALTER TYPE booking_status RENAME TO booking_status_old;
+CREATE TYPE booking_status AS ENUM ('pending', 'confirmed', 'cancelled');
+ALTER TABLE bookings
+    ALTER COLUMN status DROP DEFAULT,
+    ALTER COLUMN status TYPE booking_status USING status::text::booking_status,
+    ALTER COLUMN status SET DEFAULT 'confirmed';
+DROP TYPE booking_status_old;
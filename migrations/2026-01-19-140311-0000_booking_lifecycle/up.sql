@@ -0,0 +1,17 @@
+-- Your SQL goes here
+
+ALTER TYPE booking_status ADD VALUE IF NOT EXISTS 'checked_in';
+ALTER TYPE booking_status ADD VALUE IF NOT EXISTS 'checked_out';
+ALTER TYPE booking_status ADD VALUE IF NOT EXISTS 'no_show';
+
+-- performed_by is NULL for transitions made by the system rather than a person
+CREATE TABLE booking_transitions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    booking_id UUID NOT NULL REFERENCES bookings(block_id) ON DELETE CASCADE,
+    from_status booking_status NOT NULL,
+    to_status booking_status NOT NULL,
+    performed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_booking_transitions_booking_id ON booking_transitions(booking_id);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+DROP INDEX IF EXISTS idx_bookings_pending_holds;
+
+ALTER TABLE bookings DROP COLUMN hold_expires_at;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+
+ALTER TABLE bookings ADD COLUMN hold_expires_at TIMESTAMPTZ;
+
+CREATE INDEX idx_bookings_pending_holds ON bookings(hold_expires_at) WHERE status = 'pending';
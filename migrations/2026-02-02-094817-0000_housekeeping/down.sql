@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM blocks WHERE id IN (SELECT block_id FROM housekeeping);
+DROP TABLE IF EXISTS housekeeping;
+DROP TYPE IF EXISTS housekeeping_status;
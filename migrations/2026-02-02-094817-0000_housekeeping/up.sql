@@ -0,0 +1,10 @@
+-- Your SQL goes here
+
+CREATE TYPE "housekeeping_status" AS ENUM ('pending', 'in_progress', 'completed');
+
+CREATE TABLE housekeeping (
+    block_id UUID PRIMARY KEY REFERENCES blocks(id) ON DELETE CASCADE,
+    status "housekeeping_status" NOT NULL DEFAULT 'pending',
+    instructions TEXT,
+    assignee_id UUID REFERENCES staff(id) ON DELETE SET NULL
+);
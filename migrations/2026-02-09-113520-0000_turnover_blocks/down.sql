@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS idx_housekeeping_booking_id;
+ALTER TABLE housekeeping DROP COLUMN IF EXISTS booking_id;
+ALTER TABLE room_classes DROP COLUMN IF EXISTS turnover_hours;
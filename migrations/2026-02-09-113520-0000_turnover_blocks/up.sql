@@ -0,0 +1,10 @@
+-- Your SQL goes here
+
+ALTER TABLE room_classes
+    ADD COLUMN turnover_hours INTEGER CHECK (turnover_hours > 0);
+
+-- Turnover tasks are scheduled for a booking and go away with it
+ALTER TABLE housekeeping
+    ADD COLUMN booking_id UUID REFERENCES bookings(block_id) ON DELETE CASCADE;
+
+CREATE INDEX idx_housekeeping_booking_id ON housekeeping(booking_id);
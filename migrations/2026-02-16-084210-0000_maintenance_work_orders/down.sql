@@ -0,0 +1,33 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS idx_maintenance_open;
+ALTER TABLE maintenance DROP COLUMN IF EXISTS closed_at;
+
+ALTER TYPE "maintenance_kind" RENAME TO "maintenance_kind_old";
+
+CREATE TYPE "maintenance_kind" AS ENUM (
+    'electrical',
+    'plumbing',
+    'structural',
+    'hvac',
+    'fire_safety',
+    'security_systems',
+    'groundskeeping',
+    'janitorial',
+    'pest_control',
+    'it_network',
+    'painting',
+    'appliances',
+    'out_of_serviceother'
+);
+
+ALTER TABLE maintenance
+    ALTER COLUMN kind TYPE "maintenance_kind"
+    USING (
+        CASE kind::text
+            WHEN 'out_of_service' THEN 'out_of_serviceother'
+            WHEN 'other' THEN 'out_of_serviceother'
+            ELSE kind::text
+        END
+    )::"maintenance_kind";
+
+DROP TYPE "maintenance_kind_old";
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+-- The original type was missing a comma, which fused the last two values
+ALTER TYPE "maintenance_kind" RENAME VALUE 'out_of_serviceother' TO 'out_of_service';
+ALTER TYPE "maintenance_kind" ADD VALUE 'other';
+
+ALTER TABLE maintenance ADD COLUMN closed_at TIMESTAMPTZ;
+
+CREATE INDEX idx_maintenance_open ON maintenance(block_id) WHERE closed_at IS NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS idx_reports_live_block_id;
+DROP TRIGGER IF EXISTS update_reports_modtime ON reports;
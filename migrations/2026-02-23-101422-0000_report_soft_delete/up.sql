@@ -0,0 +1,8 @@
+-- Your SQL goes here
+
+CREATE TRIGGER update_reports_modtime
+BEFORE UPDATE ON reports
+FOR EACH ROW
+EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX idx_reports_live_block_id ON reports(block_id) WHERE deleted_at IS NULL;
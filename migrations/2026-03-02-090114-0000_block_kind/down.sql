@@ -0,0 +1,11 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS maintenance_block_integrity ON maintenance;
+DROP TRIGGER IF EXISTS housekeeping_block_integrity ON housekeeping;
+DROP TRIGGER IF EXISTS bookings_block_integrity ON bookings;
+DROP TRIGGER IF EXISTS blocks_child_integrity ON blocks;
+DROP FUNCTION IF EXISTS check_block_parent();
+DROP FUNCTION IF EXISTS check_block_child();
+DROP FUNCTION IF EXISTS assert_block_child(UUID);
+ALTER TABLE blocks DROP CONSTRAINT IF EXISTS blocks_kind_not_null;
+ALTER TABLE blocks DROP COLUMN IF EXISTS kind;
+DROP TYPE IF EXISTS block_kind;
@@ -0,0 +1,88 @@
+-- Your SQL goes here
+
+CREATE TYPE "block_kind" AS ENUM ('booking', 'housekeeping', 'maintenance');
+
+ALTER TABLE blocks ADD COLUMN kind "block_kind";
+
+UPDATE blocks SET kind = 'booking' WHERE id IN (SELECT block_id FROM bookings);
+UPDATE blocks SET kind = 'housekeeping' WHERE id IN (SELECT block_id FROM housekeeping);
+UPDATE blocks SET kind = 'maintenance' WHERE id IN (SELECT block_id FROM maintenance);
+
+-- Enforced for new rows only; orphans left over from before are reported by `migrator check`
+ALTER TABLE blocks ADD CONSTRAINT blocks_kind_not_null CHECK (kind IS NOT NULL) NOT VALID;
+
+-- Raises unless the block has exactly one child row, in the table named by its kind
+CREATE OR REPLACE FUNCTION assert_block_child(target UUID)
+RETURNS VOID AS $$
+DECLARE
+    target_kind "block_kind";
+    has_booking BOOLEAN;
+    has_housekeeping BOOLEAN;
+    has_maintenance BOOLEAN;
+BEGIN
+    SELECT kind INTO target_kind FROM blocks WHERE id = target;
+    IF NOT FOUND THEN
+        -- The block itself was deleted, taking its children with it
+        RETURN;
+    END IF;
+
+    has_booking := EXISTS (SELECT 1 FROM bookings WHERE block_id = target);
+    has_housekeeping := EXISTS (SELECT 1 FROM housekeeping WHERE block_id = target);
+    has_maintenance := EXISTS (SELECT 1 FROM maintenance WHERE block_id = target);
+
+    IF has_booking::INT + has_housekeeping::INT + has_maintenance::INT <> 1
+        OR (target_kind = 'booking' AND NOT has_booking)
+        OR (target_kind = 'housekeeping' AND NOT has_housekeeping)
+        OR (target_kind = 'maintenance' AND NOT has_maintenance)
+    THEN
+        RAISE EXCEPTION 'block % must have exactly one % child', target, target_kind
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'block_child_integrity';
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION check_block_child()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM assert_block_child(NEW.id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION check_block_parent()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        PERFORM assert_block_child(NEW.block_id);
+    END IF;
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        PERFORM assert_block_child(OLD.block_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Deferred to commit so a block and its child can be inserted in either order
+CREATE CONSTRAINT TRIGGER blocks_child_integrity
+AFTER INSERT OR UPDATE OF kind ON blocks
+DEFERRABLE INITIALLY DEFERRED
+FOR EACH ROW
+EXECUTE FUNCTION check_block_child();
+
+CREATE CONSTRAINT TRIGGER bookings_block_integrity
+AFTER INSERT OR UPDATE OF block_id OR DELETE ON bookings
+DEFERRABLE INITIALLY DEFERRED
+FOR EACH ROW
+EXECUTE FUNCTION check_block_parent();
+
+CREATE CONSTRAINT TRIGGER housekeeping_block_integrity
+AFTER INSERT OR UPDATE OF block_id OR DELETE ON housekeeping
+DEFERRABLE INITIALLY DEFERRED
+FOR EACH ROW
+EXECUTE FUNCTION check_block_parent();
+
+CREATE CONSTRAINT TRIGGER maintenance_block_integrity
+AFTER INSERT OR UPDATE OF block_id OR DELETE ON maintenance
+DEFERRABLE INITIALLY DEFERRED
+FOR EACH ROW
+EXECUTE FUNCTION check_block_parent();
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users DROP COLUMN IF EXISTS sessions_revoked_at;
+DROP INDEX IF EXISTS idx_otps_user_id_purpose;
+ALTER TABLE otps DROP COLUMN IF EXISTS purpose;
+DROP TYPE IF EXISTS otp_purpose;
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+CREATE TYPE otp_purpose AS ENUM ('onboarding', 'password_reset');
+
+-- Existing codes were all issued for onboarding
+ALTER TABLE otps ADD COLUMN purpose otp_purpose NOT NULL DEFAULT 'onboarding';
+
+CREATE INDEX idx_otps_user_id_purpose ON otps(user_id, purpose) WHERE used_at IS NULL;
+
+-- Sessions issued before this instant are no longer accepted
+ALTER TABLE users ADD COLUMN sessions_revoked_at TIMESTAMPTZ;
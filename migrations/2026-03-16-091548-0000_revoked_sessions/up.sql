@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+-- Individual tokens signed out before they expire
+CREATE TABLE revoked_sessions (
+    token_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_revoked_sessions_expires_at ON revoked_sessions(expires_at);
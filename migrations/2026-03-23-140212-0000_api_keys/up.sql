@@ -0,0 +1,19 @@
+-- Your SQL goes here
+
+-- Long-lived credentials for integrations such as channel managers and kiosks
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    -- SHA-256 of the secret, hex encoded; the secret itself is only shown once
+    key_hash TEXT NOT NULL UNIQUE,
+    -- Leading characters of the secret, so keys can be told apart
+    key_prefix TEXT NOT NULL,
+    -- `resource:read` or `resource:write` entries
+    scopes TEXT[] NOT NULL,
+    -- Requests made with the key act on behalf of this staff member
+    created_by UUID NOT NULL REFERENCES staff(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
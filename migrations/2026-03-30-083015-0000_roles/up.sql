@@ -0,0 +1,66 @@
+-- Your SQL goes here
+
+CREATE TABLE roles (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE permissions (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE role_permissions (
+    role_name TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    permission_name TEXT NOT NULL REFERENCES permissions(name) ON DELETE CASCADE,
+    PRIMARY KEY (role_name, permission_name)
+);
+
+-- Users without roles are guests: they only see their own records
+CREATE TABLE user_roles (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role_name TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, role_name)
+);
+
+INSERT INTO roles (name, description) VALUES
+    ('guest', 'Books and manages their own stays'),
+    ('front_desk', 'Handles reservations, arrivals and departures'),
+    ('housekeeping', 'Schedules and completes room turnovers'),
+    ('maintenance', 'Works through maintenance orders'),
+    ('manager', 'Runs day-to-day operations and staff'),
+    ('admin', 'Full access, including roles and API keys');
+
+INSERT INTO permissions (name, description) VALUES
+    ('rooms.read', 'List rooms and read their details and availability'),
+    ('bookings.manage', 'Create, change and move bookings of any guest'),
+    ('housekeeping.manage', 'Schedule, update and remove housekeeping'),
+    ('maintenance.manage', 'Open, reassign, extend and close work orders'),
+    ('reports.moderate', 'Read every report and edit, delete or restore them'),
+    ('users.read', 'Read any user record'),
+    ('users.manage', 'Invite users and sign them out'),
+    ('api_keys.manage', 'Create, list and revoke API keys'),
+    ('roles.manage', 'Assign roles to users');
+
+INSERT INTO role_permissions (role_name, permission_name) VALUES
+    ('front_desk', 'rooms.read'),
+    ('front_desk', 'bookings.manage'),
+    ('front_desk', 'users.read'),
+    ('housekeeping', 'rooms.read'),
+    ('housekeeping', 'housekeeping.manage'),
+    ('maintenance', 'rooms.read'),
+    ('maintenance', 'maintenance.manage'),
+    ('manager', 'rooms.read'),
+    ('manager', 'bookings.manage'),
+    ('manager', 'housekeeping.manage'),
+    ('manager', 'maintenance.manage'),
+    ('manager', 'reports.moderate'),
+    ('manager', 'users.read'),
+    ('manager', 'users.manage');
+
+INSERT INTO role_permissions (role_name, permission_name)
+SELECT 'admin', name FROM permissions;
+
+-- Every staff member could do everything until now; keep it that way until roles are handed out
+INSERT INTO user_roles (user_id, role_name)
+SELECT user_id, 'admin' FROM staff;
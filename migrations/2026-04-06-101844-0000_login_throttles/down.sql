@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS login_failures;
+DROP TABLE IF EXISTS login_throttles;
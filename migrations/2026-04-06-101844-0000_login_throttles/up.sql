@@ -0,0 +1,25 @@
+-- Your SQL goes here
+
+-- Failed sign-ins counted per account and per client address
+CREATE TABLE login_throttles (
+    -- `account` keys on the submitted email, whether or not it exists; `ip` on the client address
+    scope TEXT NOT NULL CHECK (scope IN ('account', 'ip')),
+    subject TEXT NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMPTZ,
+    PRIMARY KEY (scope, subject)
+);
+
+-- Every refused sign-in, kept for auditing
+CREATE TABLE login_failures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email TEXT NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ip_address TEXT,
+    -- The attempt was turned away by a lockout without checking the password
+    locked BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_login_failures_email ON login_failures(email, created_at);
@@ -0,0 +1,35 @@
+-- Your SQL goes here
+
+-- Second factor for sign-ins; an enrollment stays pending until its first code is confirmed
+CREATE TABLE totp_credentials (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Base32 shared secret, as handed to the authenticator app
+    secret TEXT NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    -- Time step of the last accepted code, so that no code is accepted twice
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Single-use codes for when the authenticator is lost; only hashes are kept
+CREATE TABLE totp_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_totp_recovery_codes_user_id ON totp_recovery_codes(user_id);
+
+-- Password sign-ins waiting for their TOTP code
+CREATE TABLE login_challenges (
+    -- SHA-256 of the token handed to the client
+    token_hash TEXT PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Members of these roles get no permissions until they enroll
+ALTER TABLE roles ADD COLUMN requires_totp BOOLEAN NOT NULL DEFAULT FALSE;
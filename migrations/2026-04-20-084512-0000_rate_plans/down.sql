@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM permissions WHERE name = 'rates.manage';
+DROP TABLE IF EXISTS rate_weekday_rules;
+DROP TABLE IF EXISTS rate_seasons;
+DROP TABLE IF EXISTS rate_plans;
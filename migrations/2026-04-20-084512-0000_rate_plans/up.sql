@@ -0,0 +1,52 @@
+-- Your SQL goes here
+
+-- Ways of selling a room class, e.g. "Flexible", "Non-refundable" or "Bed & Breakfast"
+CREATE TABLE rate_plans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    class_id UUID NOT NULL REFERENCES room_classes(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- Nightly price when no season or weekday rule applies; the class base price if unset
+    base_price NUMERIC(10, 2) CHECK (base_price >= 0),
+    refundable BOOLEAN NOT NULL DEFAULT TRUE,
+    includes_breakfast BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Archived plans are kept for past bookings but no longer sold
+    archived_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT rate_plans_class_name_key UNIQUE (class_id, name)
+);
+
+CREATE INDEX idx_rate_plans_class_id ON rate_plans(class_id);
+
+-- Date ranged prices that take precedence over everything else in a plan
+CREATE TABLE rate_seasons (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    plan_id UUID NOT NULL REFERENCES rate_plans(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- Nights from `starts_on` up to and including `ends_on`
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    nightly_price NUMERIC(10, 2) NOT NULL CHECK (nightly_price >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (starts_on <= ends_on),
+    -- A night never falls in two seasons of the same plan, so the price is never ambiguous
+    CONSTRAINT rate_seasons_no_overlaps EXCLUDE USING GIST (
+        plan_id WITH =,
+        daterange(starts_on, ends_on, '[]') WITH &&
+    )
+);
+
+-- Prices for a day of the week, applied outside of seasons
+CREATE TABLE rate_weekday_rules (
+    plan_id UUID NOT NULL REFERENCES rate_plans(id) ON DELETE CASCADE,
+    -- ISO 8601 day of the week: 1 is Monday, 7 is Sunday
+    weekday SMALLINT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+    nightly_price NUMERIC(10, 2) NOT NULL CHECK (nightly_price >= 0),
+    PRIMARY KEY (plan_id, weekday)
+);
+
+INSERT INTO permissions (name, description) VALUES
+    ('rates.manage', 'Create rate plans and set their seasons and weekday prices');
+
+INSERT INTO role_permissions (role_name, permission_name) VALUES
+    ('manager', 'rates.manage'),
+    ('admin', 'rates.manage');
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+UPDATE permissions
+SET description = 'Create rate plans and set their seasons and weekday prices'
+WHERE name = 'rates.manage';
+
+DROP TABLE IF EXISTS stay_rules;
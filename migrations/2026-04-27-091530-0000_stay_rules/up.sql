@@ -0,0 +1,26 @@
+-- Your SQL goes here
+
+-- Length-of-stay and arrival/departure restrictions on a room class for a range of dates.
+-- Rules may overlap; a stay has to satisfy every rule that applies to it.
+CREATE TABLE stay_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    class_id UUID NOT NULL REFERENCES room_classes(id) ON DELETE CASCADE,
+    -- Dates from `starts_on` up to and including `ends_on`
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    -- Length limits apply to stays arriving within the dates
+    min_nights INTEGER CHECK (min_nights >= 1),
+    max_nights INTEGER CHECK (max_nights >= 1),
+    -- No stay may start, or end, on one of the dates
+    closed_to_arrival BOOLEAN NOT NULL DEFAULT FALSE,
+    closed_to_departure BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (starts_on <= ends_on),
+    CHECK (max_nights >= min_nights)
+);
+
+CREATE INDEX idx_stay_rules_class_dates ON stay_rules(class_id, starts_on, ends_on);
+
+UPDATE permissions
+SET description = 'Create rate plans and stay rules, and set seasonal and weekday prices'
+WHERE name = 'rates.manage';
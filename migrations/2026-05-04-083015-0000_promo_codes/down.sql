@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM permissions WHERE name = 'promotions.manage';
+
+ALTER TABLE bookings
+    DROP COLUMN IF EXISTS discount,
+    DROP COLUMN IF EXISTS promo_code_id;
+
+DROP TABLE IF EXISTS promo_code_classes;
+DROP TABLE IF EXISTS promo_codes;
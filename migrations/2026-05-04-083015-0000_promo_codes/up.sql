@@ -0,0 +1,46 @@
+-- Your SQL goes here
+
+-- Discount codes, each standing for one marketing campaign
+CREATE TABLE promo_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Matched case-insensitively, stored upper case
+    code TEXT NOT NULL CHECK (code <> '' AND code = UPPER(code)),
+    campaign TEXT NOT NULL,
+    -- Exactly one of a share of the room subtotal or a fixed amount off it
+    percent_off NUMERIC(5, 2) CHECK (percent_off > 0 AND percent_off <= 100),
+    amount_off NUMERIC(10, 2) CHECK (amount_off > 0),
+    -- When the code can be redeemed; open ended if unset
+    starts_at TIMESTAMPTZ,
+    ends_at TIMESTAMPTZ,
+    -- Bookings that may hold the code at once, overall and per guest; cancelled ones don't count
+    max_uses INTEGER CHECK (max_uses >= 1),
+    max_uses_per_guest INTEGER CHECK (max_uses_per_guest >= 1),
+    min_nights INTEGER CHECK (min_nights >= 1),
+    disabled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT promo_codes_code_key UNIQUE (code),
+    CHECK ((percent_off IS NULL) <> (amount_off IS NULL)),
+    CHECK (ends_at > starts_at)
+);
+
+-- Room classes a code is limited to; a code without any applies to every class
+CREATE TABLE promo_code_classes (
+    promo_code_id UUID NOT NULL REFERENCES promo_codes(id) ON DELETE CASCADE,
+    class_id UUID NOT NULL REFERENCES room_classes(id) ON DELETE CASCADE,
+    PRIMARY KEY (promo_code_id, class_id)
+);
+
+-- The code a booking was made with and what it took off, for attributing revenue to campaigns
+ALTER TABLE bookings
+    ADD COLUMN promo_code_id UUID REFERENCES promo_codes(id),
+    ADD COLUMN discount NUMERIC(10, 2) CHECK (discount >= 0),
+    ADD CONSTRAINT bookings_promo_discount_check CHECK ((promo_code_id IS NULL) = (discount IS NULL));
+
+CREATE INDEX idx_bookings_promo_code_id ON bookings(promo_code_id) WHERE promo_code_id IS NOT NULL;
+
+INSERT INTO permissions (name, description) VALUES
+    ('promotions.manage', 'Create and disable promo codes');
+
+INSERT INTO role_permissions (role_name, permission_name) VALUES
+    ('manager', 'promotions.manage'),
+    ('admin', 'promotions.manage');
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+UPDATE permissions
+SET description = 'Create rate plans and stay rules, and set seasonal and weekday prices'
+WHERE name = 'rates.manage';
+
+DROP TABLE IF EXISTS occupancy_bands;
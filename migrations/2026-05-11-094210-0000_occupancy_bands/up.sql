@@ -0,0 +1,16 @@
+-- Your SQL goes here
+
+-- Price adjustments for a room class that apply once enough of its rooms are sold for a night.
+-- The band with the highest threshold reached applies, on top of the base price.
+CREATE TABLE occupancy_bands (
+    class_id UUID NOT NULL REFERENCES room_classes(id) ON DELETE CASCADE,
+    -- Percentage of the class's rooms sold for the night, 1 to 100
+    min_occupancy SMALLINT NOT NULL CHECK (min_occupancy BETWEEN 1 AND 100),
+    -- Percentage added to the base price; negative to discount
+    adjustment NUMERIC(5, 2) NOT NULL CHECK (adjustment > -100),
+    PRIMARY KEY (class_id, min_occupancy)
+);
+
+UPDATE permissions
+SET description = 'Create rate plans, stay rules and occupancy bands, and set seasonal and weekday prices'
+WHERE name = 'rates.manage';
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+
+-- Guests staying, which taxes and promo quotes are worked out for when the stay changes
+ALTER TABLE bookings
+    ADD COLUMN guests INTEGER NOT NULL DEFAULT 1 CHECK (guests >= 1);
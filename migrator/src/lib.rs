@@ -1,6 +1,12 @@
+use std::fmt::Display;
+
+use diesel::prelude::*;
 use diesel_async::AsyncMigrationHarness;
-use diesel_async::{AsyncPgConnection, pooled_connection::deadpool::Pool};
+use diesel_async::{AsyncPgConnection, RunQueryDsl, pooled_connection::deadpool::Pool};
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
+use infra::models::BlockKind;
+use infra::schema::{blocks, bookings, housekeeping, maintenance};
+use uuid::Uuid;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../migrations");
 
@@ -12,3 +18,113 @@ pub async fn run_migrations(
     harness.run_pending_migrations(MIGRATIONS)?;
     Ok(())
 }
+
+/// A block that breaks the one-child-per-block rule.
+#[derive(Debug)]
+pub enum BlockIssue {
+    /// The block has no child row at all.
+    Orphan {
+        block_id: Uuid,
+        kind: Option<BlockKind>,
+    },
+    /// The block's children do not match its kind, or there is more than one.
+    Mismatch {
+        block_id: Uuid,
+        kind: Option<BlockKind>,
+        children: Vec<BlockKind>,
+    },
+}
+
+impl Display for BlockIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind_name = |kind: &Option<BlockKind>| match kind {
+            Some(kind) => kind.to_string(),
+            None => "no kind".to_string(),
+        };
+
+        match self {
+            BlockIssue::Orphan { block_id, kind } => {
+                write!(f, "orphan    {} ({})", block_id, kind_name(kind))
+            }
+            BlockIssue::Mismatch {
+                block_id,
+                kind,
+                children,
+            } => {
+                let children = children
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "mismatch  {} ({}) has children: {}",
+                    block_id,
+                    kind_name(kind),
+                    children
+                )
+            }
+        }
+    }
+}
+
+/// Lists blocks that do not have exactly one child row matching their kind.
+///
+/// The constraint triggers keep new data consistent; this finds rows written before them.
+pub async fn check_block_integrity(
+    pool: &Pool<AsyncPgConnection>,
+) -> Result<Vec<BlockIssue>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut conn = pool.get().await?;
+
+    // Block id and kind, then the id found in each child table
+    type BlockRow = (
+        Uuid,
+        Option<BlockKind>,
+        Option<Uuid>,
+        Option<Uuid>,
+        Option<Uuid>,
+    );
+
+    let rows: Vec<BlockRow> = blocks::table
+        .left_join(bookings::table)
+        .left_join(housekeeping::table)
+        .left_join(maintenance::table)
+        .select((
+            blocks::id,
+            blocks::kind,
+            bookings::block_id.nullable(),
+            housekeeping::block_id.nullable(),
+            maintenance::block_id.nullable(),
+        ))
+        .order(blocks::created_at.asc())
+        .load(&mut conn)
+        .await?;
+
+    let issues = rows
+        .into_iter()
+        .filter_map(|(block_id, kind, booking, task, record)| {
+            let children: Vec<BlockKind> = [
+                (booking, BlockKind::Booking),
+                (task, BlockKind::Housekeeping),
+                (record, BlockKind::Maintenance),
+            ]
+            .into_iter()
+            .filter_map(|(child, child_kind)| child.map(|_| child_kind))
+            .collect();
+
+            if children.is_empty() {
+                Some(BlockIssue::Orphan { block_id, kind })
+            } else if children.len() > 1 || kind != Some(children[0]) {
+                Some(BlockIssue::Mismatch {
+                    block_id,
+                    kind,
+                    children,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(issues)
+}
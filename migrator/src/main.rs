@@ -20,9 +20,20 @@ async fn main() {
 
     let pool = db::init_pool(&app_settings.database).expect("Failed to connect to database");
 
+    match std::env::args().nth(1).as_deref() {
+        None | Some("run") => run(&pool).await,
+        Some("check") => check(&pool).await,
+        Some(command) => {
+            eprintln!("✗ Unknown command `{}`, expected `run` or `check`", command);
+            std::process::exit(2);
+        }
+    }
+}
+
+async fn run(pool: &db::DbPool) {
     println!("Running migrations...");
 
-    match migrator::run_migrations(&pool).await {
+    match migrator::run_migrations(pool).await {
         Ok(_) => println!("✓ Migrations completed successfully"),
         Err(e) => {
             eprintln!("✗ Migration error: {}", e);
@@ -30,3 +41,22 @@ async fn main() {
         }
     }
 }
+
+async fn check(pool: &db::DbPool) {
+    println!("Checking block integrity...");
+
+    match migrator::check_block_integrity(pool).await {
+        Ok(issues) if issues.is_empty() => println!("✓ Every block has exactly one child"),
+        Ok(issues) => {
+            for issue in &issues {
+                println!("  {}", issue);
+            }
+            eprintln!("✗ Found {} inconsistent block(s)", issues.len());
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("✗ Check error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
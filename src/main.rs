@@ -30,6 +30,8 @@ async fn main() -> std::io::Result<()> {
         .await
         .expect("Failed to run migrations");
 
+    infra::jobs::spawn_hold_sweeper(pool.clone(), &settings.bookings);
+
     info!(
         "Starting server at http://{}:{} in {run_mode}",
         settings.server.host, settings.server.port